@@ -6,7 +6,7 @@ use ark_groth16::Groth16;
 use ark_marlin::Marlin;
 use ark_poly::univariate::DensePolynomial;
 use ark_poly_commit::{ipa_pc::InnerProductArgPC, marlin_pc::MarlinKZG10, sonic_pc::SonicKZG10};
-use ark_std::{self, rc::Rc, test_rng, time::Instant};
+use ark_std::{self, test_rng, time::Instant};
 use arkworks_gadgets::{
 	arbitrary::bridge_data::{constraints::BridgeDataGadget, BridgeData, Input as BridgeDataInput},
 	circuit::bridge::BridgeCircuit,
@@ -14,7 +14,7 @@ use arkworks_gadgets::{
 		bridge::{constraints::BridgeLeafGadget, BridgeLeaf, Public as LeafPublic},
 		LeafCreation,
 	},
-	merkle_tree::{Config as MerkleConfig, SparseMerkleTree},
+	merkle_tree::{Config as MerkleConfig, Rc, SparseMerkleTree},
 	poseidon::{constraints::CRHGadget, sbox::PoseidonSbox, PoseidonParameters, Rounds, CRH},
 	set::{
 		membership::{constraints::SetMembershipGadget, SetMembership},
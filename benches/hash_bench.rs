@@ -0,0 +1,88 @@
+use ark_bn254::Fr;
+use ark_ff::UniformRand;
+use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, ToBytesGadget};
+use ark_relations::r1cs::ConstraintSystem;
+use ark_std::{test_rng, time::Instant};
+use arkworks_gadgets::{
+	field_hasher::{
+		constraints::{FieldHasherGadget, MiMCHasherGadget, PoseidonHasherGadget},
+		FieldHasher, MiMCHasher, PoseidonHasher,
+	},
+	setup::common::{
+		setup_mimc_220, setup_params_x5_3, setup_params_x5_5, Curve, MiMCRounds_220_3,
+		PoseidonRounds_x5_3, PoseidonRounds_x5_5,
+	},
+};
+
+/// Number of constraints used to decompose two field elements into bytes,
+/// which every byte oriented hasher pays before hashing
+fn packing_constraints() -> usize {
+	let rng = &mut test_rng();
+	let cs = ConstraintSystem::<Fr>::new_ref();
+	let left = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(rng))).unwrap();
+	let right = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(rng))).unwrap();
+
+	let before = cs.num_constraints();
+	left.to_bytes().unwrap();
+	right.to_bytes().unwrap();
+	cs.num_constraints() - before
+}
+
+/// Number of constraints of a single two-to-one compression
+fn compression_constraints<HG: FieldHasherGadget<Fr>>(hasher: HG::Native) -> usize {
+	let rng = &mut test_rng();
+	let cs = ConstraintSystem::<Fr>::new_ref();
+	let hasher_var = HG::from_native(cs.clone(), hasher).unwrap();
+	let left = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(rng))).unwrap();
+	let right = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(rng))).unwrap();
+
+	let before = cs.num_constraints();
+	hasher_var.hash_two(&left, &right).unwrap();
+	assert!(cs.is_satisfied().unwrap());
+	cs.num_constraints() - before
+}
+
+fn benchmark_hasher<HG: FieldHasherGadget<Fr>>(name: &str, hasher: HG::Native, num_iter: u32) {
+	let rng = &mut test_rng();
+	let mut left = Fr::rand(rng);
+	let right = Fr::rand(rng);
+
+	let start = Instant::now();
+	for _ in 0..num_iter {
+		left = hasher.hash_two(&left, &right).unwrap();
+	}
+	let elapsed = start.elapsed();
+	let per_hash = elapsed / num_iter;
+
+	let constraints = compression_constraints::<HG>(hasher);
+	let packing = packing_constraints();
+	println!(
+		"{}: native {:?} per hash ({:.0} hashes/s), {} constraints per compression ({} without \
+		 byte packing)",
+		name,
+		per_hash,
+		f64::from(num_iter) / elapsed.as_secs_f64(),
+		constraints,
+		constraints - packing,
+	);
+}
+
+fn main() {
+	let num_iter = 1000;
+
+	benchmark_hasher::<PoseidonHasherGadget<Fr, PoseidonRounds_x5_3>>(
+		"Poseidon_x5_3",
+		PoseidonHasher::new(setup_params_x5_3(Curve::Bn254)),
+		num_iter,
+	);
+	benchmark_hasher::<PoseidonHasherGadget<Fr, PoseidonRounds_x5_5>>(
+		"Poseidon_x5_5",
+		PoseidonHasher::new(setup_params_x5_5(Curve::Bn254)),
+		num_iter,
+	);
+	benchmark_hasher::<MiMCHasherGadget<Fr, MiMCRounds_220_3>>(
+		"MiMC_220_3",
+		MiMCHasher::new(setup_mimc_220(Curve::Bn254)),
+		num_iter,
+	);
+}
@@ -0,0 +1,89 @@
+//! Compile-only crate making sure the hashing, leaf and merkle tree modules
+//! build without `std`, e.g. for a Substrate runtime:
+//!
+//! `cargo build --manifest-path no-std-check/Cargo.toml --target
+//! wasm32-unknown-unknown`
+#![no_std]
+
+use ark_bn254::Fr;
+use ark_crypto_primitives::CRH as CRHTrait;
+use ark_std::{rand::Rng, rc::Rc};
+use arkworks_gadgets::{
+	identity::CRH as IdentityCRH,
+	leaf::{mixer::MixerLeaf, LeafCreation},
+	merkle_tree::{Config, SparseMerkleTree},
+	mimc::{MiMCParameters, Rounds as MiMCRounds, CRH as MiMCCRH},
+	poseidon::{sbox::PoseidonSbox, PoseidonParameters, Rounds, CRH},
+	utils::{get_mds_poseidon_bn254_x5_3, get_rounds_mimc_220, get_rounds_poseidon_bn254_x5_3},
+	Error,
+};
+
+#[derive(Default, Clone)]
+pub struct PoseidonRounds3;
+
+impl Rounds for PoseidonRounds3 {
+	const FULL_ROUNDS: usize = 8;
+	const PARTIAL_ROUNDS: usize = 57;
+	const SBOX: PoseidonSbox = PoseidonSbox::Exponentiation(5);
+	const WIDTH: usize = 3;
+}
+
+#[derive(Default, Clone)]
+pub struct MiMCRounds220;
+
+impl MiMCRounds for MiMCRounds220 {
+	const ROUNDS: usize = 220;
+	const WIDTH: usize = 3;
+}
+
+type PoseidonCRH3 = CRH<Fr, PoseidonRounds3>;
+type MiMC220 = MiMCCRH<Fr, MiMCRounds220>;
+type Leaf = MixerLeaf<Fr, PoseidonCRH3>;
+
+#[derive(Clone, PartialEq)]
+pub struct TreeConfig;
+impl Config for TreeConfig {
+	type H = PoseidonCRH3;
+	type LeafH = IdentityCRH<Fr>;
+
+	const HEIGHT: u8 = 20;
+}
+
+fn poseidon_params() -> PoseidonParameters<Fr> {
+	PoseidonParameters::new(
+		get_rounds_poseidon_bn254_x5_3::<Fr>(),
+		get_mds_poseidon_bn254_x5_3::<Fr>(),
+	)
+}
+
+pub fn poseidon_hash(input: &[u8]) -> Result<Fr, Error> {
+	<PoseidonCRH3 as CRHTrait>::evaluate(&poseidon_params(), input)
+}
+
+pub fn mimc_hash(input: &[u8]) -> Result<Fr, Error> {
+	let params = MiMCParameters::<Fr>::new(
+		Fr::from(0u64),
+		MiMCRounds220::ROUNDS,
+		MiMCRounds220::WIDTH,
+		MiMCRounds220::WIDTH,
+		get_rounds_mimc_220(),
+	);
+	<MiMC220 as CRHTrait>::evaluate(&params, input)
+}
+
+pub fn mixer_leaf<R: Rng>(rng: &mut R) -> Result<(Fr, Fr), Error> {
+	let params = poseidon_params();
+	let secrets = Leaf::generate_secrets(rng)?;
+	let leaf = Leaf::create_leaf(&secrets, &(), &params)?;
+	let nullifier_hash = Leaf::create_nullifier(&secrets, &params)?;
+	Ok((leaf, nullifier_hash))
+}
+
+pub fn merkle_root(leaves: &[Fr]) -> Result<Fr, Error> {
+	let tree = SparseMerkleTree::<TreeConfig>::new_sequential(
+		Rc::new(poseidon_params()),
+		Rc::new(()),
+		leaves,
+	)?;
+	Ok(tree.root().inner())
+}
@@ -1 +1,2 @@
-cargo build --release --no-default-features --features r1cs
\ No newline at end of file
+cargo build --release --no-default-features --features r1cs
+cargo build --release --manifest-path no-std-check/Cargo.toml --target wasm32-unknown-unknown
@@ -4,4 +4,5 @@ else
     curl https://sh.rustup.rs -sSf | sh -s -- -y
     source $HOME/.cargo/env
     export PATH=$HOME/.cargo/bin:$PATH
-fi
\ No newline at end of file
+fi
+rustup target add wasm32-unknown-unknown
@@ -0,0 +1,118 @@
+use crate::Vec;
+use ark_crypto_primitives::{Error, CRH as CRHTrait};
+use ark_ff::fields::PrimeField;
+use ark_std::{marker::PhantomData, rand::Rng};
+use blake2::{Blake2b, Blake2s};
+use digest::Digest;
+
+/// Number of bits read on top of the field size, so that reducing them
+/// modulo the field order has a bias of at most 2^-128
+pub const REDUCTION_SECURITY_BITS: usize = 128;
+
+/// CRH over the bytes of the input, for deployments that commit to their
+/// preimages with a standard hash instead of an algebraic one. The output is
+/// read as a little endian integer of at least `REDUCTION_SECURITY_BITS`
+/// more than the field size, and reduced modulo the field order.
+///
+/// When a single digest of `D` is long enough, the output is `D(input)`.
+/// Shorter digests, such as the 256 bits of Blake2s or SHA-256, are extended
+/// by concatenating `D(i || input)` for the block counters `i = 0, 1, ...`,
+/// one byte each.
+///
+/// This hash has no gadget, proving it inside a circuit is very expensive.
+pub struct CRH<F: PrimeField, D: Digest> {
+	field: PhantomData<F>,
+	digest: PhantomData<D>,
+}
+
+impl<F: PrimeField, D: Digest> CRH<F, D> {
+	/// Number of digests of `D` read to reduce an output into `F` without
+	/// bias
+	pub fn num_blocks() -> usize {
+		let digest_bits = D::output_size() * 8;
+		let required_bits = F::size_in_bits() + REDUCTION_SECURITY_BITS;
+		(required_bits + digest_bits - 1) / digest_bits
+	}
+}
+
+impl<F: PrimeField, D: Digest> CRHTrait for CRH<F, D> {
+	type Output = F;
+	type Parameters = ();
+
+	// Any number of bytes is accepted
+	const INPUT_SIZE_BITS: usize = usize::MAX;
+
+	fn setup<R: Rng>(_: &mut R) -> Result<Self::Parameters, Error> {
+		Ok(())
+	}
+
+	fn evaluate(_: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+		let num_blocks = Self::num_blocks();
+		if num_blocks == 1 {
+			return Ok(F::from_le_bytes_mod_order(&D::digest(input)));
+		}
+		let mut bytes = Vec::with_capacity(num_blocks * D::output_size());
+		for i in 0..num_blocks {
+			bytes.extend_from_slice(&D::new().chain([i as u8]).chain(input).finalize());
+		}
+		Ok(F::from_le_bytes_mod_order(&bytes))
+	}
+}
+
+/// Byte CRH over Blake2b, whose 512 bit digest reduces safely into any of the
+/// supported fields
+pub type Blake2bCRH<F> = CRH<F, Blake2b>;
+
+/// Byte CRH over Blake2s, whose 256 bit digest is read twice to reduce
+/// safely into the supported fields
+pub type Blake2sCRH<F> = CRH<F, Blake2s>;
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_bn254::Fr;
+	use ark_ff::FpParameters;
+	use ark_std::test_rng;
+
+	#[test]
+	fn should_hash_deterministically_into_field() {
+		let rng = &mut test_rng();
+		let params = Blake2bCRH::<Fr>::setup(rng).unwrap();
+
+		let a = Blake2bCRH::<Fr>::evaluate(&params, b"preimage").unwrap();
+		let b = Blake2bCRH::<Fr>::evaluate(&params, b"preimage").unwrap();
+		let c = Blake2bCRH::<Fr>::evaluate(&params, b"preimagf").unwrap();
+		assert_eq!(a, b);
+		assert_ne!(a, c);
+
+		let modulus = <Fr as PrimeField>::Params::MODULUS;
+		for i in 0..32u8 {
+			let out = Blake2bCRH::<Fr>::evaluate(&params, &[i; 32]).unwrap();
+			assert!(out.into_repr() < modulus);
+		}
+
+		// The reduction matches reading the digest modulo the field order
+		let digest = Blake2b::digest(b"preimage");
+		assert_eq!(a, Fr::from_le_bytes_mod_order(&digest));
+	}
+
+	#[test]
+	fn should_extend_short_digest() {
+		let rng = &mut test_rng();
+		let params = Blake2sCRH::<Fr>::setup(rng).unwrap();
+		assert_eq!(Blake2bCRH::<Fr>::num_blocks(), 1);
+		assert_eq!(Blake2sCRH::<Fr>::num_blocks(), 2);
+
+		let a = Blake2sCRH::<Fr>::evaluate(&params, b"preimage").unwrap();
+		let b = Blake2sCRH::<Fr>::evaluate(&params, b"preimage").unwrap();
+		let c = Blake2sCRH::<Fr>::evaluate(&params, b"preimagf").unwrap();
+		assert_eq!(a, b);
+		assert_ne!(a, c);
+
+		// Two counter prefixed digests are read, not the single digest
+		let mut bytes = Blake2s::new().chain([0u8]).chain(b"preimage").finalize().to_vec();
+		bytes.extend_from_slice(&Blake2s::new().chain([1u8]).chain(b"preimage").finalize());
+		assert_eq!(a, Fr::from_le_bytes_mod_order(&bytes));
+		assert_ne!(a, Fr::from_le_bytes_mod_order(&Blake2s::digest(b"preimage")));
+	}
+}
@@ -0,0 +1,400 @@
+use crate::{
+	error::GadgetError,
+	leaf::{constraints::LeafCreationGadget, LeafCreation},
+	merkle_tree::{
+		constraints::{NodeVar, PathVar},
+		Config as MerkleConfig, Path,
+	},
+	set::membership::constraints::enforce_set_membership,
+	Vec,
+};
+use ark_crypto_primitives::{crh::CRHGadget, CRH};
+use ark_ff::{fields::PrimeField, ToConstraintField};
+use ark_r1cs_std::{eq::EqGadget, fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::marker::PhantomData;
+
+/// Gadget proving that a leaf is in one of a set of Merkle trees, given by
+/// their roots, and that it has a given nullifier hash
+pub struct AnchorGadget<F, H, HG, C, LHGT, HGT, L, LG>
+where
+	F: PrimeField,
+	H: CRH,
+	HG: CRHGadget<H, F>,
+	C: MerkleConfig,
+	LHGT: CRHGadget<C::LeafH, F>,
+	HGT: CRHGadget<C::H, F, OutputVar = FpVar<F>>,
+	L: LeafCreation<H>,
+	LG: LeafCreationGadget<F, H, HG, L>,
+{
+	_field: PhantomData<F>,
+	_hasher: PhantomData<H>,
+	_hasher_gadget: PhantomData<HG>,
+	_merkle_config: PhantomData<C>,
+	_leaf_hasher_gadget: PhantomData<LHGT>,
+	_tree_hasher_gadget: PhantomData<HGT>,
+	_leaf_creation: PhantomData<L>,
+	_leaf_creation_gadget: PhantomData<LG>,
+}
+
+impl<F, H, HG, C, LHGT, HGT, L, LG> AnchorGadget<F, H, HG, C, LHGT, HGT, L, LG>
+where
+	F: PrimeField,
+	H: CRH,
+	HG: CRHGadget<H, F>,
+	C: MerkleConfig,
+	LHGT: CRHGadget<C::LeafH, F>,
+	HGT: CRHGadget<C::H, F, OutputVar = FpVar<F>>,
+	L: LeafCreation<H>,
+	LG: LeafCreationGadget<F, H, HG, L>,
+{
+	/// Creates the leaf, enforces that its nullifier is `nullifier_hash` and
+	/// that `path` roots it at one of `root_set`. Nullifiers binding the index
+	/// of the leaf read it from `path`, and its public inputs from
+	/// `leaf_public`. Returns the computed root.
+	pub fn enforce(
+		leaf_private: &LG::PrivateVar,
+		leaf_public: &LG::PublicVar,
+		hasher_params: &HG::ParametersVar,
+		path: &PathVar<F, C, HGT, LHGT>,
+		nullifier_hash: &LG::NullifierVar,
+		root_set: &[FpVar<F>],
+	) -> Result<FpVar<F>, SynthesisError> {
+		let leaf = LG::create_leaf(leaf_private, leaf_public, hasher_params)?;
+		let (root, index) = path.root_and_index(&leaf)?;
+		let nullifier = LG::create_nullifier_at(leaf_private, leaf_public, &index, hasher_params)?;
+		nullifier.enforce_equal(nullifier_hash)?;
+
+		let root = match root {
+			NodeVar::Inner(root) => root,
+			// Only a path of height zero ends on a leaf
+			NodeVar::Leaf(_) => return Err(SynthesisError::Unsatisfiable),
+		};
+		enforce_set_membership(&root, root_set)?;
+
+		Ok(root)
+	}
+}
+
+/// Anchor circuit for the standard arkworks proving flow, proving with
+/// `AnchorGadget` that the leaf of `L` is in a tree of the `M` roots of the
+/// set and that its nullifier hash is the public one.
+pub struct AnchorCircuit<F, H, HG, C, LHGT, HGT, L, LG, const M: usize>
+where
+	F: PrimeField,
+	H: CRH,
+	HG: CRHGadget<H, F>,
+	C: MerkleConfig,
+	LHGT: CRHGadget<C::LeafH, F>,
+	HGT: CRHGadget<C::H, F, OutputVar = FpVar<F>>,
+	L: LeafCreation<H>,
+	LG: LeafCreationGadget<F, H, HG, L>,
+{
+	leaf_private_inputs: L::Private,
+	leaf_public_inputs: L::Public,
+	hasher_params: H::Parameters,
+	path: Path<C>,
+	roots: [F; M],
+	nullifier_hash: L::Nullifier,
+	arbitrary_input: F,
+	_hasher_gadget: PhantomData<HG>,
+	_leaf_hasher_gadget: PhantomData<LHGT>,
+	_tree_hasher_gadget: PhantomData<HGT>,
+	_leaf_creation_gadget: PhantomData<LG>,
+}
+
+impl<F, H, HG, C, LHGT, HGT, L, LG, const M: usize>
+	AnchorCircuit<F, H, HG, C, LHGT, HGT, L, LG, M>
+where
+	F: PrimeField,
+	H: CRH,
+	HG: CRHGadget<H, F>,
+	C: MerkleConfig,
+	LHGT: CRHGadget<C::LeafH, F>,
+	HGT: CRHGadget<C::H, F, OutputVar = FpVar<F>>,
+	L: LeafCreation<H>,
+	LG: LeafCreationGadget<F, H, HG, L>,
+{
+	/// Builds the circuit, creating the nullifier hash of the secrets
+	pub fn new(
+		leaf_private_inputs: L::Private,
+		leaf_public_inputs: L::Public,
+		hasher_params: H::Parameters,
+		path: Path<C>,
+		roots: [F; M],
+		arbitrary_input: F,
+	) -> Result<Self, GadgetError> {
+		let nullifier_hash = L::create_nullifier(&leaf_private_inputs, &hasher_params)?;
+		Ok(Self {
+			leaf_private_inputs,
+			leaf_public_inputs,
+			hasher_params,
+			path,
+			roots,
+			nullifier_hash,
+			arbitrary_input,
+			_hasher_gadget: PhantomData,
+			_leaf_hasher_gadget: PhantomData,
+			_tree_hasher_gadget: PhantomData,
+			_leaf_creation_gadget: PhantomData,
+		})
+	}
+
+	/// Leaf of the secrets, as inserted in the tree
+	pub fn leaf(&self) -> Result<L::Leaf, GadgetError> {
+		L::create_leaf(
+			&self.leaf_private_inputs,
+			&self.leaf_public_inputs,
+			&self.hasher_params,
+		)
+	}
+
+	/// Public inputs to verify a proof of the circuit with, in the order
+	/// `generate_constraints` allocates them: the public inputs of the leaf
+	/// (e.g. the chain id of a bridge leaf), the nullifier hash, the `M` roots
+	/// and the arbitrary input. `None` if the leaf inputs or the nullifier
+	/// hash don't convert to field elements.
+	pub fn public_inputs(&self) -> Option<Vec<F>>
+	where
+		L::Public: ToConstraintField<F>,
+		L::Nullifier: ToConstraintField<F>,
+	{
+		let mut inputs = self.leaf_public_inputs.to_field_elements()?;
+		inputs.extend(self.nullifier_hash.to_field_elements()?);
+		inputs.extend_from_slice(&self.roots);
+		inputs.push(self.arbitrary_input);
+		Some(inputs)
+	}
+}
+
+impl<F, H, HG, C, LHGT, HGT, L, LG, const M: usize> Clone
+	for AnchorCircuit<F, H, HG, C, LHGT, HGT, L, LG, M>
+where
+	F: PrimeField,
+	H: CRH,
+	HG: CRHGadget<H, F>,
+	C: MerkleConfig,
+	LHGT: CRHGadget<C::LeafH, F>,
+	HGT: CRHGadget<C::H, F, OutputVar = FpVar<F>>,
+	L: LeafCreation<H>,
+	LG: LeafCreationGadget<F, H, HG, L>,
+{
+	fn clone(&self) -> Self {
+		Self {
+			leaf_private_inputs: self.leaf_private_inputs.clone(),
+			leaf_public_inputs: self.leaf_public_inputs.clone(),
+			hasher_params: self.hasher_params.clone(),
+			path: self.path.clone(),
+			roots: self.roots,
+			nullifier_hash: self.nullifier_hash.clone(),
+			arbitrary_input: self.arbitrary_input,
+			_hasher_gadget: PhantomData,
+			_leaf_hasher_gadget: PhantomData,
+			_tree_hasher_gadget: PhantomData,
+			_leaf_creation_gadget: PhantomData,
+		}
+	}
+}
+
+impl<F, H, HG, C, LHGT, HGT, L, LG, const M: usize> ConstraintSynthesizer<F>
+	for AnchorCircuit<F, H, HG, C, LHGT, HGT, L, LG, M>
+where
+	F: PrimeField,
+	H: CRH,
+	HG: CRHGadget<H, F>,
+	C: MerkleConfig,
+	LHGT: CRHGadget<C::LeafH, F>,
+	HGT: CRHGadget<C::H, F, OutputVar = FpVar<F>>,
+	L: LeafCreation<H>,
+	LG: LeafCreationGadget<F, H, HG, L>,
+{
+	fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+		let leaf_public = self.leaf_public_inputs;
+		let nullifier_hash = self.nullifier_hash;
+		let roots = self.roots;
+		let arbitrary_input = self.arbitrary_input;
+		let leaf_private = self.leaf_private_inputs;
+		let path = self.path;
+
+		// Public inputs
+		let leaf_public_var = LG::PublicVar::new_input(cs.clone(), || Ok(leaf_public))?;
+		let nullifier_hash_var = LG::NullifierVar::new_input(cs.clone(), || Ok(nullifier_hash))?;
+		let roots_var = Vec::<FpVar<F>>::new_input(cs.clone(), || Ok(roots.to_vec()))?;
+		let arbitrary_input_var = FpVar::new_input(cs.clone(), || Ok(arbitrary_input))?;
+
+		// Constants
+		let hasher_params_var = HG::ParametersVar::new_constant(cs.clone(), self.hasher_params)?;
+
+		// Private inputs
+		let leaf_private_var = LG::PrivateVar::new_witness(cs.clone(), || Ok(leaf_private))?;
+		let path_var = PathVar::<F, C, HGT, LHGT>::new_witness(cs, || Ok(path))?;
+
+		AnchorGadget::<F, H, HG, C, LHGT, HGT, L, LG>::enforce(
+			&leaf_private_var,
+			&leaf_public_var,
+			&hasher_params_var,
+			&path_var,
+			&nullifier_hash_var,
+			&roots_var,
+		)?;
+		// Binds the arbitrary input to the proof
+		let _ = &arbitrary_input_var * &arbitrary_input_var;
+
+		Ok(())
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		leaf::bridge::{
+			constraints::{PrivateVar, PublicVar},
+			Private as LeafPrivate, Public as LeafPublic,
+		},
+		poseidon::constraints::PoseidonParametersVar,
+		setup::{
+			bridge::{setup_leaf_x5, LeafGadget_x5, Leaf_x5},
+			common::*,
+		},
+		Vec,
+	};
+	use ark_bls12_381::Fr as BlsFr;
+	use ark_ff::UniformRand;
+	use ark_r1cs_std::prelude::*;
+	use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+	use ark_std::test_rng;
+
+	type Anchor = AnchorGadget<
+		BlsFr,
+		PoseidonCRH_x5_5<BlsFr>,
+		PoseidonCRH_x5_5Gadget<BlsFr>,
+		TreeConfig_x5<BlsFr>,
+		LeafCRHGadget<BlsFr>,
+		PoseidonCRH_x5_3Gadget<BlsFr>,
+		Leaf_x5<BlsFr>,
+		LeafGadget_x5<BlsFr>,
+	>;
+
+	/// Allocates the anchor inputs and runs the gadget, returning the
+	/// constraint system
+	fn enforce_anchor(
+		leaf_private: LeafPrivate<BlsFr>,
+		chain_id: BlsFr,
+		nullifier_hash: BlsFr,
+		path: Path<TreeConfig_x5<BlsFr>>,
+		roots: Vec<BlsFr>,
+	) -> ConstraintSystemRef<BlsFr> {
+		let curve = Curve::Bls381;
+		let cs = ConstraintSystem::<BlsFr>::new_ref();
+		let params5 = setup_params_x5_5::<BlsFr>(curve);
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), params5).unwrap();
+		let leaf_public = LeafPublic::new(chain_id);
+		let leaf_public_var = PublicVar::new_input(cs.clone(), || Ok(leaf_public)).unwrap();
+		let nullifier_hash_var = FpVar::new_input(cs.clone(), || Ok(nullifier_hash)).unwrap();
+		let roots_var = Vec::<FpVar<BlsFr>>::new_input(cs.clone(), || Ok(roots)).unwrap();
+		let leaf_private_var = PrivateVar::new_witness(cs.clone(), || Ok(leaf_private)).unwrap();
+		let path_var = PathVar::new_witness(cs.clone(), || Ok(path)).unwrap();
+
+		Anchor::enforce(
+			&leaf_private_var,
+			&leaf_public_var,
+			&params_var,
+			&path_var,
+			&nullifier_hash_var,
+			&roots_var,
+		)
+		.unwrap();
+		cs
+	}
+
+	#[test]
+	fn should_verify_leaf_in_second_tree() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+		let params3 = setup_params_x5_3::<BlsFr>(curve);
+		let params5 = setup_params_x5_5::<BlsFr>(curve);
+		let chain_id = BlsFr::rand(rng);
+		let (leaf_private, _, leaf, nullifier_hash) = setup_leaf_x5(chain_id, &params5, rng);
+
+		let first_tree = setup_tree_x5(&[BlsFr::rand(rng), BlsFr::rand(rng)], &params3);
+		let (second_tree, path) =
+			setup_tree_and_create_path_x5(&[BlsFr::rand(rng), leaf], 1, &params3);
+		let roots = vec![first_tree.root().inner(), second_tree.root().inner()];
+
+		let cs = enforce_anchor(
+			leaf_private.clone(),
+			chain_id,
+			nullifier_hash,
+			path.clone(),
+			roots.clone(),
+		);
+		assert!(cs.is_satisfied().unwrap());
+
+		// Wrong nullifier hash
+		let cs = enforce_anchor(
+			leaf_private.clone(),
+			chain_id,
+			BlsFr::rand(rng),
+			path.clone(),
+			roots,
+		);
+		assert!(!cs.is_satisfied().unwrap());
+
+		// The second tree is not in the set
+		let roots = vec![first_tree.root().inner(), BlsFr::rand(rng)];
+		let cs = enforce_anchor(leaf_private, chain_id, nullifier_hash, path, roots);
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_satisfy_anchor_circuit() {
+		type Circuit = AnchorCircuit<
+			BlsFr,
+			PoseidonCRH_x5_5<BlsFr>,
+			PoseidonCRH_x5_5Gadget<BlsFr>,
+			TreeConfig_x5<BlsFr>,
+			LeafCRHGadget<BlsFr>,
+			PoseidonCRH_x5_3Gadget<BlsFr>,
+			Leaf_x5<BlsFr>,
+			LeafGadget_x5<BlsFr>,
+			2,
+		>;
+
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+		let params3 = setup_params_x5_3::<BlsFr>(curve);
+		let params5 = setup_params_x5_5::<BlsFr>(curve);
+		let chain_id = BlsFr::rand(rng);
+		let (leaf_private, leaf_public, leaf, nullifier_hash) =
+			setup_leaf_x5(chain_id, &params5, rng);
+		let (tree, path) = setup_tree_and_create_path_x5(&[BlsFr::rand(rng), leaf], 1, &params3);
+
+		let circuit = |roots: [BlsFr; 2]| {
+			let circuit = Circuit::new(
+				leaf_private.clone(),
+				leaf_public.clone(),
+				params5.clone(),
+				path.clone(),
+				roots,
+				BlsFr::rand(&mut test_rng()),
+			)
+			.unwrap();
+			assert_eq!(circuit.leaf().unwrap(), leaf);
+			let public_inputs = circuit.public_inputs().unwrap();
+			assert_eq!(public_inputs[..2], [chain_id, nullifier_hash]);
+
+			let cs = ConstraintSystem::<BlsFr>::new_ref();
+			circuit.generate_constraints(cs.clone()).unwrap();
+			// The instance is the constant one followed by the public inputs
+			let instance = cs.borrow().unwrap().instance_assignment.clone();
+			assert_eq!(instance[1..], public_inputs[..]);
+			cs.is_satisfied().unwrap()
+		};
+
+		assert!(circuit([BlsFr::rand(rng), tree.root().inner()]));
+		// The root of the path is not in the set
+		assert!(!circuit([BlsFr::rand(rng), BlsFr::rand(rng)]));
+	}
+}
@@ -160,11 +160,19 @@ where
 		let leaf_private_var = LG::PrivateVar::new_witness(cs.clone(), || Ok(leaf_private))?;
 		let path_var = PathVar::<F, C, HGT, LHGT>::new_witness(cs.clone(), || Ok(path))?;
 
-		// Creating the leaf and checking the membership inside the tree
+		// Creating the leaf and checking the membership inside the tree. The
+		// nullifier reads the index of the leaf from its path and the salt
+		// from the public inputs, not from witnesses, so that a leaf can't be
+		// spent again under another index or in another pool.
 		let mixer_leaf = LG::create_leaf(&leaf_private_var, &leaf_public_var, &hasher_params_var)?;
-		let mixer_nullifier = LG::create_nullifier(&leaf_private_var, &hasher_params_var)?;
-		let is_member =
-			path_var.check_membership(&NodeVar::Inner(root_var.clone()), &mixer_leaf)?;
+		let (computed_root, index) = path_var.root_and_index(&mixer_leaf)?;
+		let mixer_nullifier = LG::create_nullifier_at(
+			&leaf_private_var,
+			&leaf_public_var,
+			&index,
+			&hasher_params_var,
+		)?;
+		let is_member = NodeVar::Inner(root_var.clone()).is_eq(&computed_root)?;
 		// Constraining arbitrary inputs
 		AG::constrain(&arbitrary_input_var)?;
 
@@ -179,13 +187,52 @@ where
 #[cfg(feature = "default_poseidon")]
 #[cfg(test)]
 mod test {
-	use crate::setup::{common::*, mixer::*};
+	use super::MixerCircuit;
+	use crate::{
+		leaf::{
+			mixer::{constraints::MixerLeafGadget, IndexedNullifier, MixerLeaf, SaltedNullifier},
+			LeafCreation,
+		},
+		setup::{common::*, mixer::*},
+	};
 	use ark_bls12_381::{Bls12_381, Fr as BlsFr};
 	use ark_crypto_primitives::SNARK;
 	use ark_ff::UniformRand;
 	use ark_groth16::Groth16;
+	use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
 	use ark_std::test_rng;
 
+	type IndexedLeaf = MixerLeaf<BlsFr, PoseidonCRH_x5_5<BlsFr>, IndexedNullifier>;
+	type IndexedLeafGadget =
+		MixerLeafGadget<BlsFr, PoseidonCRH_x5_5<BlsFr>, PoseidonCRH_x5_5Gadget<BlsFr>, IndexedLeaf>;
+	type IndexedCircuit = MixerCircuit<
+		BlsFr,
+		MixerConstraintData<BlsFr>,
+		MixerConstraintDataGadget<BlsFr>,
+		PoseidonCRH_x5_5<BlsFr>,
+		PoseidonCRH_x5_5Gadget<BlsFr>,
+		TreeConfig_x5<BlsFr>,
+		LeafCRHGadget<BlsFr>,
+		PoseidonCRH_x5_3Gadget<BlsFr>,
+		IndexedLeaf,
+		IndexedLeafGadget,
+	>;
+	type SaltedLeaf = MixerLeaf<BlsFr, PoseidonCRH_x5_5<BlsFr>, SaltedNullifier>;
+	type SaltedLeafGadget =
+		MixerLeafGadget<BlsFr, PoseidonCRH_x5_5<BlsFr>, PoseidonCRH_x5_5Gadget<BlsFr>, SaltedLeaf>;
+	type SaltedCircuit = MixerCircuit<
+		BlsFr,
+		MixerConstraintData<BlsFr>,
+		MixerConstraintDataGadget<BlsFr>,
+		PoseidonCRH_x5_5<BlsFr>,
+		PoseidonCRH_x5_5Gadget<BlsFr>,
+		TreeConfig_x5<BlsFr>,
+		LeafCRHGadget<BlsFr>,
+		PoseidonCRH_x5_3Gadget<BlsFr>,
+		SaltedLeaf,
+		SaltedLeafGadget,
+	>;
+
 	#[test]
 	fn setup_and_prove_mixer_groth16() {
 		let rng = &mut test_rng();
@@ -324,4 +371,77 @@ mod test {
 		let res = verify_groth16::<Bls12_381>(&vk, &public_inputs, &proof);
 		assert!(res);
 	}
+
+	#[test]
+	fn should_bind_indexed_nullifier_to_path() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+		let params5 = setup_params_x5_5(curve);
+		let params3 = setup_params_x5_3(curve);
+		let arbitrary_input = setup_arbitrary_data(BlsFr::rand(rng), BlsFr::rand(rng));
+
+		let secrets = IndexedLeaf::generate_secrets(rng).unwrap();
+		let leaf = IndexedLeaf::create_leaf(&secrets, &(), &params5).unwrap();
+		let (tree, path) = setup_tree_and_create_path_x5(&[BlsFr::rand(rng), leaf], 1, &params3);
+		let root = tree.root().inner();
+
+		let satisfied = |index: u64| {
+			let secrets = secrets.clone().with_index(index);
+			let nullifier_hash = IndexedLeaf::create_nullifier(&secrets, &params5).unwrap();
+			let circuit = IndexedCircuit::new(
+				arbitrary_input.clone(),
+				secrets,
+				(),
+				params5.clone(),
+				path.clone(),
+				root,
+				nullifier_hash,
+			);
+			let cs = ConstraintSystem::<BlsFr>::new_ref();
+			circuit.generate_constraints(cs.clone()).unwrap();
+			cs.is_satisfied().unwrap()
+		};
+
+		// Only the index of the path gives a valid nullifier hash, so the
+		// leaf can't be spent again under another index
+		assert!(satisfied(1));
+		assert!(!satisfied(0));
+		assert!(!satisfied(3));
+	}
+
+	#[test]
+	fn should_bind_salted_nullifier_to_public_salt() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+		let params5 = setup_params_x5_5(curve);
+		let params3 = setup_params_x5_3(curve);
+		let arbitrary_input = setup_arbitrary_data(BlsFr::rand(rng), BlsFr::rand(rng));
+
+		let salt = BlsFr::from(7u64);
+		let secrets = SaltedLeaf::generate_secrets(rng).unwrap().with_salt(salt);
+		let leaf = SaltedLeaf::create_leaf(&secrets, &salt, &params5).unwrap();
+		let nullifier_hash = SaltedLeaf::create_nullifier(&secrets, &params5).unwrap();
+		let (tree, path) = setup_tree_and_create_path_x5(&[leaf], 0, &params3);
+		let root = tree.root().inner();
+
+		let satisfied = |public_salt: BlsFr| {
+			let circuit = SaltedCircuit::new(
+				arbitrary_input.clone(),
+				secrets.clone(),
+				public_salt,
+				params5.clone(),
+				path.clone(),
+				root,
+				nullifier_hash,
+			);
+			let cs = ConstraintSystem::<BlsFr>::new_ref();
+			circuit.generate_constraints(cs.clone()).unwrap();
+			cs.is_satisfied().unwrap()
+		};
+
+		// The nullifier hash only verifies against the salt of its pool,
+		// which is a public input
+		assert!(satisfied(salt));
+		assert!(!satisfied(BlsFr::from(8u64)));
+	}
 }
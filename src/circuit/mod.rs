@@ -1,4 +1,6 @@
+pub mod anchor;
 pub mod basic;
 pub mod bridge;
 pub mod mixer;
 pub mod poseidon;
+pub mod vanchor;
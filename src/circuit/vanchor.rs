@@ -0,0 +1,475 @@
+use crate::{
+	commitment::poseidon::CommitmentGadget,
+	field_hasher::{
+		commit_with_rate,
+		constraints::{commit_with_rate_var, FieldHasherGadget},
+		FieldHasher,
+	},
+	poseidon::{
+		constraints::{CRHGadget as PoseidonCRHGadget, PoseidonParametersVar},
+		PoseidonParameters, Rounds, CRH as PoseidonCRH,
+	},
+	range::enforce_bit_length,
+	Vec,
+};
+use ark_crypto_primitives::{crh::CRHGadget, Error, CRH};
+use ark_ff::{to_bytes, FpParameters, PrimeField};
+use ark_r1cs_std::{
+	fields::{fp::FpVar, FieldVar},
+	prelude::*,
+};
+use ark_relations::r1cs::SynthesisError;
+
+/// Maximum bit size of a UTXO amount, so that sums of amounts can't wrap
+/// around the field modulus.
+pub const AMOUNT_BITS: usize = 248;
+
+/// Poseidon hash of `inputs`, each serialized to bytes as `CRH::evaluate`
+/// expects
+pub(crate) fn hash<F: PrimeField, H: Rounds>(
+	params: &PoseidonParameters<F>,
+	inputs: &[F],
+) -> Result<F, Error> {
+	let mut bytes = Vec::new();
+	for input in inputs {
+		bytes.extend(to_bytes![input]?);
+	}
+	<PoseidonCRH<F, H> as CRH>::evaluate(params, &bytes)
+}
+
+/// Gadget of `hash`
+pub(crate) fn hash_var<F: PrimeField, H: Rounds>(
+	params: &PoseidonParametersVar<F>,
+	inputs: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+	let mut bytes = Vec::new();
+	for input in inputs {
+		bytes.extend(input.to_bytes()?);
+	}
+	<PoseidonCRHGadget<F, H> as CRHGadget<_, _>>::evaluate(params, &bytes)
+}
+
+/// Number of external data fields absorbed by each hash of `hash_ext_data`
+pub const EXT_DATA_RATE: usize = 3;
+
+/// Hashes the external data of a transaction (recipient, relayer, fee,
+/// refund, ...) into its `ext_data_hash`: the length prefixed commitment of
+/// `field_hasher::commit_with_rate`, absorbing the fields `EXT_DATA_RATE` at
+/// a time.
+pub fn hash_ext_data<F: PrimeField, H: FieldHasher<F>>(
+	fields: &[F],
+	hasher: &H,
+) -> Result<F, Error> {
+	commit_with_rate(hasher, fields, EXT_DATA_RATE)
+}
+
+/// Gadget of `hash_ext_data`. The number of fields is fixed by the circuit.
+pub fn hash_ext_data_var<F: PrimeField, HG: FieldHasherGadget<F>>(
+	fields: &[FpVar<F>],
+	hasher: &HG,
+) -> Result<FpVar<F>, SynthesisError> {
+	commit_with_rate_var(hasher, fields, EXT_DATA_RATE)
+}
+
+/// Enforces that the witnessed external data `fields` hash to the public
+/// `ext_data_hash`
+pub fn enforce_ext_data_hash<F: PrimeField, HG: FieldHasherGadget<F>>(
+	fields: &[FpVar<F>],
+	ext_data_hash: &FpVar<F>,
+	hasher: &HG,
+) -> Result<(), SynthesisError> {
+	hash_ext_data_var(fields, hasher)?.enforce_equal(ext_data_hash)
+}
+
+/// Hashes public inputs (roots, nullifier hashes, `ext_data_hash`, ...) into
+/// a single digest a verifier can bind to, with `CRH::hash_elements` absorbing
+/// them in blocks of the rate of `H`. Fails when `H` has a `CAPACITY` of zero
+/// and there are more inputs than the rate.
+pub fn hash_public_inputs<F: PrimeField, H: Rounds>(
+	params: &PoseidonParameters<F>,
+	inputs: &[F],
+) -> Result<F, Error> {
+	PoseidonCRH::<F, H>::hash_elements(params, inputs)
+}
+
+/// Gadget of `hash_public_inputs`
+pub fn hash_public_inputs_var<F: PrimeField, H: Rounds>(
+	params: &PoseidonParametersVar<F>,
+	inputs: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+	PoseidonCRHGadget::<F, H>::hash_elements(params, inputs)
+}
+
+/// Enforces that `inputs` hash to the public `digest`
+pub fn enforce_public_inputs_hash<F: PrimeField, H: Rounds>(
+	inputs: &[FpVar<F>],
+	digest: &FpVar<F>,
+	params: &PoseidonParametersVar<F>,
+) -> Result<(), SynthesisError> {
+	hash_public_inputs_var::<F, H>(params, inputs)?.enforce_equal(digest)
+}
+
+/// Enforces that `commitment` is the hash of the witnessed `fields`, such as
+/// the `chain_id`, `amount`, `public_key` and `blinding` of an output UTXO,
+/// see `CommitmentGadget::enforce_open_fields`
+pub fn enforce_commitment<F: PrimeField, H: Rounds>(
+	commitment: &FpVar<F>,
+	fields: &[FpVar<F>],
+	params: &PoseidonParametersVar<F>,
+) -> Result<(), SynthesisError> {
+	CommitmentGadget::<F, H>::enforce_open_fields(params, commitment, fields)
+}
+
+/// Domain separator of `derive_linking_tag`, the ASCII bytes of "link"
+pub const LINKING_TAG_DOMAIN: u64 = 0x6c69_6e6b;
+
+/// Linking tag `H(LINKING_TAG_DOMAIN, private_key, epoch)` of the UTXOs owned
+/// by `private_key`. Exposed as a public input, it shows that two spends of
+/// the same epoch come from the same key without revealing it. The domain
+/// separator keeps the tag of any epoch, including 0, apart from the public
+/// key `H(private_key)`.
+pub fn derive_linking_tag<F: PrimeField, H: FieldHasher<F>>(
+	private_key: &F,
+	epoch: &F,
+	hasher: &H,
+) -> Result<F, Error> {
+	hasher.hash(&[F::from(LINKING_TAG_DOMAIN), *private_key, *epoch])
+}
+
+/// Gadget of `derive_linking_tag`
+pub fn derive_linking_tag_var<F: PrimeField, HG: FieldHasherGadget<F>>(
+	private_key: &FpVar<F>,
+	epoch: &FpVar<F>,
+	hasher: &HG,
+) -> Result<FpVar<F>, SynthesisError> {
+	let domain = FpVar::Constant(F::from(LINKING_TAG_DOMAIN));
+	hasher.hash(&[domain, private_key.clone(), epoch.clone()])
+}
+
+/// Enforces that the input amounts plus the public amount equal the output
+/// amounts. The amounts must already be range checked, so that the sums
+/// can't wrap around the field.
+pub fn enforce_balance<F: PrimeField>(
+	inputs: &[FpVar<F>],
+	outputs: &[FpVar<F>],
+	public_amount: &FpVar<F>,
+) -> Result<(), SynthesisError> {
+	enforce_balance_with_fee(inputs, outputs, public_amount, &FpVar::zero())
+}
+
+/// Enforces `sum(inputs) + public_amount == sum(outputs) + fee`, where the
+/// fee is paid out of the transaction to the relayer. The fee is range
+/// checked to `AMOUNT_BITS` so that it can't wrap around the field to mint
+/// value.
+pub fn enforce_balance_with_fee<F: PrimeField>(
+	inputs: &[FpVar<F>],
+	outputs: &[FpVar<F>],
+	public_amount: &FpVar<F>,
+	fee: &FpVar<F>,
+) -> Result<(), SynthesisError> {
+	enforce_bit_length(fee, AMOUNT_BITS)?;
+
+	let sum_ins = inputs.iter().fold(public_amount.clone(), |sum, x| sum + x);
+	let sum_outs = outputs.iter().fold(fee.clone(), |sum, x| sum + x);
+	sum_ins.enforce_equal(&sum_outs)
+}
+
+/// Splits `public_amount` into its sign and magnitude, enforcing that it is
+/// `magnitude` or `-magnitude` and range checking the magnitude to
+/// `AMOUNT_BITS`, so that the public amount can't wrap around the field. An
+/// element above half the modulus reads as negative, and zero as positive.
+pub fn decompose_signed_amount<F: PrimeField>(
+	public_amount: &FpVar<F>,
+) -> Result<(Boolean<F>, FpVar<F>), SynthesisError> {
+	let cs = public_amount.cs();
+	let is_negative = Boolean::new_witness(cs.clone(), || {
+		let value = public_amount.value()?;
+		Ok(value.into_repr() > F::Params::MODULUS_MINUS_ONE_DIV_TWO)
+	})?;
+	let magnitude = FpVar::new_witness(cs, || {
+		let value = public_amount.value()?;
+		Ok(if is_negative.value()? { -value } else { value })
+	})?;
+	enforce_bit_length(&magnitude, AMOUNT_BITS)?;
+
+	let signed = FpVar::conditionally_select(&is_negative, &magnitude.negate()?, &magnitude)?;
+	signed.enforce_equal(public_amount)?;
+	// Zero has a single sign
+	is_negative
+		.and(&magnitude.is_eq(&FpVar::zero())?)?
+		.enforce_equal(&Boolean::FALSE)?;
+
+	Ok((is_negative, magnitude))
+}
+
+/// Enforces the balance of each asset of a multi-asset transaction, whose
+/// inputs and outputs are `(asset_id, amount)` pairs: for every asset id,
+/// the input amounts plus the public amount, if the asset is
+/// `public_asset_id`, equal the output amounts. The amounts must already be
+/// range checked, so that the sums can't wrap around the field.
+///
+/// The sums are checked for the asset of every input, every output and the
+/// public asset, which covers every asset of the transaction at the cost of
+/// a comparison per pair of them.
+pub fn enforce_balance_multi_asset<F: PrimeField>(
+	inputs: &[(FpVar<F>, FpVar<F>)],
+	outputs: &[(FpVar<F>, FpVar<F>)],
+	public_amount: &FpVar<F>,
+	public_asset_id: &FpVar<F>,
+) -> Result<(), SynthesisError> {
+	// Sum of the amounts of `asset_id` among `entries`
+	fn sum_of<F: PrimeField>(
+		asset_id: &FpVar<F>,
+		entries: &[(FpVar<F>, FpVar<F>)],
+	) -> Result<FpVar<F>, SynthesisError> {
+		entries.iter().try_fold(FpVar::zero(), |sum, (id, amount)| {
+			let is_asset = id.is_eq(asset_id)?;
+			Ok(sum + FpVar::conditionally_select(&is_asset, amount, &FpVar::zero())?)
+		})
+	}
+
+	let asset_ids = inputs
+		.iter()
+		.chain(outputs)
+		.map(|(id, _)| id)
+		.chain(Some(public_asset_id));
+	for asset_id in asset_ids {
+		let is_public_asset = public_asset_id.is_eq(asset_id)?;
+		let public_part =
+			FpVar::conditionally_select(&is_public_asset, public_amount, &FpVar::zero())?;
+		let sum_ins = sum_of(asset_id, inputs)? + public_part;
+		sum_ins.enforce_equal(&sum_of(asset_id, outputs)?)?;
+	}
+	Ok(())
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::{constraints::PoseidonHasherGadget, PoseidonHasher},
+		setup::common::{setup_params_x5_5, Curve, PoseidonRounds5Capacity1, PoseidonRounds_x5_5},
+	};
+	use ark_bn254::Fr as Bn254Fr;
+	use ark_ff::Field;
+	use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+	use ark_std::{test_rng, UniformRand};
+
+	type ExtDataHasher = PoseidonHasher<Bn254Fr, PoseidonRounds_x5_5>;
+	type ExtDataHasherGadget = PoseidonHasherGadget<Bn254Fr, PoseidonRounds_x5_5>;
+
+	#[test]
+	fn should_hash_ext_data_in_circuit() {
+		let rng = &mut test_rng();
+		let hasher = ExtDataHasher::new(setup_params_x5_5(Curve::Bn254));
+
+		for len in 0..=7 {
+			let fields: Vec<Bn254Fr> = (0..len).map(|_| Bn254Fr::rand(rng)).collect();
+			let ext_data_hash = hash_ext_data(&fields, &hasher).unwrap();
+
+			let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+			let hasher_var = ExtDataHasherGadget::from_native(cs.clone(), hasher.clone()).unwrap();
+			let fields_var = Vec::<FpVar<_>>::new_witness(cs.clone(), || Ok(fields)).unwrap();
+			let ext_data_hash_var = FpVar::new_input(cs.clone(), || Ok(ext_data_hash)).unwrap();
+
+			let res = hash_ext_data_var(&fields_var, &hasher_var).unwrap();
+			assert_eq!(res.value().unwrap(), ext_data_hash, "length {}", len);
+			enforce_ext_data_hash(&fields_var, &ext_data_hash_var, &hasher_var).unwrap();
+			assert!(cs.is_satisfied().unwrap());
+		}
+
+		// A trailing zero field is not the same as padding
+		let fields = vec![Bn254Fr::from(1u64), Bn254Fr::from(2u64)];
+		let padded = vec![
+			Bn254Fr::from(1u64),
+			Bn254Fr::from(2u64),
+			Bn254Fr::from(0u64),
+		];
+		assert_ne!(
+			hash_ext_data(&fields, &hasher).unwrap(),
+			hash_ext_data(&padded, &hasher).unwrap()
+		);
+	}
+
+	#[test]
+	fn should_hash_public_inputs_in_circuit() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_5::<Bn254Fr>(Curve::Bn254);
+		// Root, two nullifier hashes, ext_data_hash and public amount take two
+		// blocks of the rate of 4
+		let inputs: Vec<Bn254Fr> = (0..5).map(|_| Bn254Fr::rand(rng)).collect();
+		let digest = hash_public_inputs::<_, PoseidonRounds5Capacity1>(&params, &inputs).unwrap();
+
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+		let inputs_var = Vec::<FpVar<_>>::new_input(cs.clone(), || Ok(inputs.clone())).unwrap();
+		let digest_var = FpVar::new_input(cs.clone(), || Ok(digest)).unwrap();
+
+		let res = hash_public_inputs_var::<_, PoseidonRounds5Capacity1>(&params_var, &inputs_var);
+		assert_eq!(res.unwrap().value().unwrap(), digest);
+		enforce_public_inputs_hash::<_, PoseidonRounds5Capacity1>(
+			&inputs_var,
+			&digest_var,
+			&params_var,
+		)
+		.unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		// Swapping two inputs changes the digest
+		let mut swapped = inputs;
+		swapped.swap(1, 2);
+		let swapped_var = Vec::<FpVar<_>>::new_input(cs.clone(), || Ok(swapped)).unwrap();
+		enforce_public_inputs_hash::<_, PoseidonRounds5Capacity1>(
+			&swapped_var,
+			&digest_var,
+			&params_var,
+		)
+		.unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_reject_altered_ext_data() {
+		let rng = &mut test_rng();
+		let hasher = ExtDataHasher::new(setup_params_x5_5(Curve::Bn254));
+		// recipient, relayer, fee, refund
+		let mut fields: Vec<Bn254Fr> = (0..4).map(|_| Bn254Fr::rand(rng)).collect();
+		let ext_data_hash = hash_ext_data(&fields, &hasher).unwrap();
+
+		// The relayer raises its fee
+		fields[2] += Bn254Fr::from(1u64);
+
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let hasher_var = ExtDataHasherGadget::from_native(cs.clone(), hasher).unwrap();
+		let fields_var = Vec::<FpVar<_>>::new_witness(cs.clone(), || Ok(fields)).unwrap();
+		let ext_data_hash_var = FpVar::new_input(cs.clone(), || Ok(ext_data_hash)).unwrap();
+
+		enforce_ext_data_hash(&fields_var, &ext_data_hash_var, &hasher_var).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_enforce_balance_per_asset() {
+		let entries = |cs: &ConstraintSystemRef<Bn254Fr>, values: &[(u64, u64)]| {
+			values
+				.iter()
+				.map(|(id, amount)| {
+					let id = FpVar::new_witness(cs.clone(), || Ok(Bn254Fr::from(*id))).unwrap();
+					let amount =
+						FpVar::new_witness(cs.clone(), || Ok(Bn254Fr::from(*amount))).unwrap();
+					(id, amount)
+				})
+				.collect::<Vec<_>>()
+		};
+		let public = |cs: &ConstraintSystemRef<Bn254Fr>, amount: u64, id: u64| {
+			(
+				FpVar::new_input(cs.clone(), || Ok(Bn254Fr::from(amount))).unwrap(),
+				FpVar::new_input(cs.clone(), || Ok(Bn254Fr::from(id))).unwrap(),
+			)
+		};
+
+		// A single asset, depositing 3
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let ins = entries(&cs, &[(1, 10), (1, 7)]);
+		let outs = entries(&cs, &[(1, 15), (1, 5)]);
+		let (amount, id) = public(&cs, 3, 1);
+		enforce_balance_multi_asset(&ins, &outs, &amount, &id).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		// Two assets, each balanced on its own
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let ins = entries(&cs, &[(1, 10), (2, 5)]);
+		let outs = entries(&cs, &[(2, 5), (1, 10)]);
+		let (amount, id) = public(&cs, 0, 1);
+		enforce_balance_multi_asset(&ins, &outs, &amount, &id).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		// Moving 2 from asset 2 to asset 1 keeps the total but not the assets
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let ins = entries(&cs, &[(1, 10), (2, 5)]);
+		let outs = entries(&cs, &[(1, 12), (2, 3)]);
+		let (amount, id) = public(&cs, 0, 1);
+		enforce_balance_multi_asset(&ins, &outs, &amount, &id).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+
+		// The public amount only counts for the public asset
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let ins = entries(&cs, &[(1, 10), (2, 5)]);
+		let outs = entries(&cs, &[(1, 10), (2, 8)]);
+		let (amount, id) = public(&cs, 3, 1);
+		enforce_balance_multi_asset(&ins, &outs, &amount, &id).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+
+		// Nor can it be deposited in an asset no output holds
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let ins = entries(&cs, &[(1, 10)]);
+		let outs = entries(&cs, &[(1, 10)]);
+		let (amount, id) = public(&cs, 3, 2);
+		enforce_balance_multi_asset(&ins, &outs, &amount, &id).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_enforce_balance_with_fee() {
+		let witness = |cs: &ConstraintSystemRef<Bn254Fr>, values: &[i64]| {
+			values
+				.iter()
+				.map(|v| {
+					let value = if *v < 0 {
+						-Bn254Fr::from(v.unsigned_abs())
+					} else {
+						Bn254Fr::from(*v as u64)
+					};
+					FpVar::new_witness(cs.clone(), || Ok(value)).unwrap()
+				})
+				.collect::<Vec<_>>()
+		};
+
+		// Inputs of 10 and 7, withdrawing 4 publicly with a fee of 2
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let ins = witness(&cs, &[10, 7]);
+		let outs = witness(&cs, &[5, 6]);
+		let amounts = witness(&cs, &[-4, 2]);
+		enforce_balance_with_fee(&ins, &outs, &amounts[0], &amounts[1]).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		// A fee off by one breaks the balance
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let ins = witness(&cs, &[10, 7]);
+		let outs = witness(&cs, &[5, 6]);
+		let amounts = witness(&cs, &[-4, 3]);
+		enforce_balance_with_fee(&ins, &outs, &amounts[0], &amounts[1]).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+
+		// As does a negative fee, even when it balances
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let ins = witness(&cs, &[10, 7]);
+		let outs = witness(&cs, &[10, 9]);
+		let amounts = witness(&cs, &[0, -2]);
+		enforce_balance_with_fee(&ins, &outs, &amounts[0], &amounts[1]).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_decompose_signed_amount() {
+		let decompose = |public_amount: Bn254Fr| {
+			let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+			let public_amount_var = FpVar::new_input(cs.clone(), || Ok(public_amount)).unwrap();
+			let (is_negative, magnitude) = decompose_signed_amount(&public_amount_var).unwrap();
+			let decomposed = (is_negative.value().unwrap(), magnitude.value().unwrap());
+			(decomposed, cs.is_satisfied().unwrap())
+		};
+
+		let positive = Bn254Fr::from(7u64);
+		assert_eq!(decompose(positive), ((false, positive), true));
+		assert_eq!(decompose(-positive), ((true, positive), true));
+		let zero = Bn254Fr::from(0u64);
+		assert_eq!(decompose(zero), ((false, zero), true));
+
+		// Amounts past `AMOUNT_BITS` on either side don't fit the magnitude
+		let too_large = Bn254Fr::from(2u64).pow([AMOUNT_BITS as u64]);
+		assert!(!decompose(too_large).1);
+		assert!(!decompose(-too_large).1);
+	}
+}
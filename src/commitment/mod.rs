@@ -0,0 +1,6 @@
+//! Hiding commitments to field elements, opened in circuits with the gadget
+//! next to each of them. Lists of any length, such as the external data of a
+//! transaction, are committed to with `field_hasher::commit_with_rate`
+//! instead.
+
+pub mod poseidon;
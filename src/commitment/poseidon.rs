@@ -0,0 +1,155 @@
+use crate::poseidon::{PoseidonParameters, Rounds, CRH};
+use ark_crypto_primitives::{crh::CRH as CRHTrait, Error};
+use ark_ff::{to_bytes, PrimeField};
+use ark_std::marker::PhantomData;
+
+#[cfg(feature = "r1cs")]
+use crate::{
+	poseidon::constraints::{CRHGadget, PoseidonParametersVar},
+	Vec,
+};
+#[cfg(feature = "r1cs")]
+use ark_crypto_primitives::crh::constraints::CRHGadget as CRHGadgetTrait;
+#[cfg(feature = "r1cs")]
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+#[cfg(feature = "r1cs")]
+use ark_relations::r1cs::SynthesisError;
+
+/// Commitment `H(value, randomness)` with Poseidon, an algebraic alternative
+/// to Pedersen commitments that is cheap to open in a circuit. It hides
+/// `value` as long as the randomness is uniform and never reused.
+pub struct Commitment<F: PrimeField, P: Rounds> {
+	field: PhantomData<F>,
+	rounds: PhantomData<P>,
+}
+
+impl<F: PrimeField, P: Rounds> Commitment<F, P> {
+	pub fn commit(params: &PoseidonParameters<F>, value: F, randomness: F) -> Result<F, Error> {
+		Self::commit_fields(params, &[value, randomness])
+	}
+
+	/// Commitment `H(fields)` to several values at once, the randomness being
+	/// one of the `fields`, such as the blinding of a VAnchor UTXO
+	pub fn commit_fields(params: &PoseidonParameters<F>, fields: &[F]) -> Result<F, Error> {
+		let bytes = to_bytes![fields]?;
+		CRH::<F, P>::evaluate(params, &bytes)
+	}
+
+	/// Checks that `commitment` opens to `value` with `randomness`
+	pub fn open(
+		params: &PoseidonParameters<F>,
+		commitment: &F,
+		value: F,
+		randomness: F,
+	) -> Result<bool, Error> {
+		Ok(&Self::commit(params, value, randomness)? == commitment)
+	}
+}
+
+/// Gadget of `Commitment`
+#[cfg(feature = "r1cs")]
+pub struct CommitmentGadget<F: PrimeField, P: Rounds> {
+	field: PhantomData<F>,
+	rounds: PhantomData<P>,
+}
+
+#[cfg(feature = "r1cs")]
+impl<F: PrimeField, P: Rounds> CommitmentGadget<F, P> {
+	pub fn commit(
+		params: &PoseidonParametersVar<F>,
+		value: &FpVar<F>,
+		randomness: &FpVar<F>,
+	) -> Result<FpVar<F>, SynthesisError> {
+		Self::commit_fields(params, &[value.clone(), randomness.clone()])
+	}
+
+	/// Gadget of `Commitment::commit_fields`
+	pub fn commit_fields(
+		params: &PoseidonParametersVar<F>,
+		fields: &[FpVar<F>],
+	) -> Result<FpVar<F>, SynthesisError> {
+		let mut bytes = Vec::new();
+		for field in fields {
+			bytes.extend(field.to_bytes()?);
+		}
+		CRHGadget::<F, P>::evaluate(params, &bytes)
+	}
+
+	/// Enforces that `commitment` opens to `value` with `randomness`
+	pub fn enforce_open(
+		params: &PoseidonParametersVar<F>,
+		commitment: &FpVar<F>,
+		value: &FpVar<F>,
+		randomness: &FpVar<F>,
+	) -> Result<(), SynthesisError> {
+		Self::commit(params, value, randomness)?.enforce_equal(commitment)
+	}
+
+	/// Enforces that `commitment` opens to `fields`
+	pub fn enforce_open_fields(
+		params: &PoseidonParametersVar<F>,
+		commitment: &FpVar<F>,
+		fields: &[FpVar<F>],
+	) -> Result<(), SynthesisError> {
+		Self::commit_fields(params, fields)?.enforce_equal(commitment)
+	}
+}
+
+#[cfg(all(feature = "r1cs", feature = "default_poseidon"))]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3};
+	use ark_bn254::Fr;
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{test_rng, UniformRand};
+
+	type PoseidonCommitment = Commitment<Fr, PoseidonRounds_x5_3>;
+	type PoseidonCommitmentGadget = CommitmentGadget<Fr, PoseidonRounds_x5_3>;
+
+	#[test]
+	fn should_open_commitment() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let value = Fr::from(42u64);
+		let randomness = Fr::rand(rng);
+		let wrong_randomness = Fr::rand(rng);
+
+		let commitment = PoseidonCommitment::commit(&params, value, randomness).unwrap();
+		assert_eq!(
+			PoseidonCommitment::commit_fields(&params, &[value, randomness]).unwrap(),
+			commitment
+		);
+		assert!(PoseidonCommitment::open(&params, &commitment, value, randomness).unwrap());
+		assert!(!PoseidonCommitment::open(&params, &commitment, value, wrong_randomness).unwrap());
+		// The same value is hidden behind another commitment
+		assert_ne!(
+			commitment,
+			PoseidonCommitment::commit(&params, value, wrong_randomness).unwrap()
+		);
+
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+		let commitment_var = FpVar::new_input(cs.clone(), || Ok(commitment)).unwrap();
+		let value_var = FpVar::new_witness(cs.clone(), || Ok(value)).unwrap();
+		let randomness_var = FpVar::new_witness(cs.clone(), || Ok(randomness)).unwrap();
+		PoseidonCommitmentGadget::enforce_open(
+			&params_var,
+			&commitment_var,
+			&value_var,
+			&randomness_var,
+		)
+		.unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		let wrong_randomness_var = FpVar::new_witness(cs.clone(), || Ok(wrong_randomness)).unwrap();
+		PoseidonCommitmentGadget::enforce_open(
+			&params_var,
+			&commitment_var,
+			&value_var,
+			&wrong_randomness_var,
+		)
+		.unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+}
@@ -0,0 +1,180 @@
+use ark_crypto_primitives::Error;
+use ark_std::error::Error as ArkError;
+
+/// Failures of the native APIs, so that callers can match on them instead of
+/// inspecting the boxed ark `Error`, which they convert into with `?` or
+/// `into` where a trait requires it.
+#[derive(Debug)]
+pub enum GadgetError {
+	/// More inputs than the hash absorbs
+	InputTooLong { len: usize, max: usize },
+	/// Parameters of another state width than expected
+	WidthMismatch { expected: usize, actual: usize },
+	/// MDS matrix that isn't square
+	InvalidMdsShape,
+	/// MDS matrix with a zero determinant
+	SingularMds,
+	/// Odd full rounds, or a capacity taking up the whole width
+	InvalidRounds,
+	/// Another number of round keys than the rounds of the permutation read
+	RoundKeysMismatch { expected: usize, actual: usize },
+	/// Merkle path of another height than expected
+	PathLengthMismatch { expected: usize, actual: usize },
+	/// Leaf index past the end of the tree
+	InvalidLeafIndex(u64),
+	/// Failure of an underlying arkworks primitive
+	Ark(Error),
+}
+
+impl core::fmt::Display for GadgetError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		use GadgetError::*;
+		let msg = match self {
+			InputTooLong { len, max } => format!("{} inputs exceed the maximum of {}", len, max),
+			WidthMismatch { expected, actual } => {
+				format!("expected a width of {}, found {}", expected, actual)
+			}
+			InvalidMdsShape => format!("mds matrix is not a square matrix of the state width"),
+			SingularMds => format!("mds matrix is not invertible"),
+			InvalidRounds => format!("full rounds must be even and the capacity below the width"),
+			RoundKeysMismatch { expected, actual } => {
+				format!("expected {} round keys, found {}", expected, actual)
+			}
+			PathLengthMismatch { expected, actual } => {
+				format!("expected a path of height {}, found {}", expected, actual)
+			}
+			InvalidLeafIndex(index) => format!("leaf index {} is out of bounds", index),
+			Ark(err) => format!("{}", err),
+		};
+		write!(f, "{}", msg)
+	}
+}
+
+impl ArkError for GadgetError {}
+
+impl From<Error> for GadgetError {
+	fn from(err: Error) -> Self {
+		// Recovers the typed errors that went through a trait returning the ark
+		// error, such as `CRH::evaluate`, which needs `std` to downcast
+		#[cfg(feature = "std")]
+		let err = match err.downcast::<GadgetError>() {
+			Ok(err) => return *err,
+			Err(err) => err,
+		};
+		GadgetError::Ark(err)
+	}
+}
+
+impl From<ark_std::io::Error> for GadgetError {
+	fn from(err: ark_std::io::Error) -> Self {
+		GadgetError::Ark(err.into())
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::PoseidonHasher,
+		identity::CRH as IdentityCRH,
+		leaf::{
+			bridge::{BridgeLeaf, Public as BridgePublic},
+			LeafCreation,
+		},
+		merkle_tree::{fixed_path::Path, Config, Rc, SparseMerkleTree},
+		poseidon::{Poseidon, PoseidonParameters, CRH as PoseidonCRH},
+		setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3, PoseidonRounds_x5_5},
+		utils::{get_mds_poseidon_bn254_x5_3, get_rounds_poseidon_bn254_x5_3},
+	};
+	use ark_bn254::Fr;
+	use ark_std::{convert::TryFrom, test_rng};
+
+	#[derive(Clone, PartialEq)]
+	struct Config3;
+	impl Config for Config3 {
+		type H = PoseidonCRH<Fr, PoseidonRounds_x5_3>;
+		type LeafH = IdentityCRH<Fr>;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_report_width_mismatch() {
+		let rounds = get_rounds_poseidon_bn254_x5_3::<Fr>();
+		let mds = get_mds_poseidon_bn254_x5_3::<Fr>();
+		let err = PoseidonParameters::<Fr>::new_checked::<PoseidonRounds_x5_5>(rounds, mds)
+			.err()
+			.unwrap();
+		assert!(matches!(err, GadgetError::WidthMismatch {
+			expected: 5,
+			actual: 3
+		}));
+
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let inputs = vec![Fr::from(1u64); 4];
+		let err = Poseidon::hash(&params, &inputs).err().unwrap();
+		assert!(matches!(err, GadgetError::InputTooLong { len: 4, max: 3 }));
+	}
+
+	#[test]
+	fn should_report_round_keys_mismatch() {
+		let mut rounds = get_rounds_poseidon_bn254_x5_3::<Fr>();
+		rounds.truncate(rounds.len() - 3);
+		let mds = get_mds_poseidon_bn254_x5_3::<Fr>();
+		let err = PoseidonParameters::<Fr>::new_checked::<PoseidonRounds_x5_3>(rounds, mds)
+			.err()
+			.unwrap();
+		assert!(matches!(err, GadgetError::RoundKeysMismatch {
+			expected: 195,
+			actual: 192
+		}));
+		assert_eq!(format!("{}", err), "expected 195 round keys, found 192");
+	}
+
+	#[test]
+	fn should_report_path_length_mismatch() {
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let leaves = vec![Fr::from(1u64), Fr::from(2u64)];
+		let mut tree =
+			SparseMerkleTree::<Config3>::new_sequential(Rc::new(params), Rc::new(()), &leaves)
+				.unwrap();
+
+		let dynamic = tree.generate_membership_proof(0);
+		let err = Path::<Fr, PoseidonHasher<Fr, PoseidonRounds_x5_3>, 4>::try_from(&dynamic)
+			.err()
+			.unwrap();
+		assert!(matches!(err, GadgetError::PathLengthMismatch {
+			expected: 4,
+			actual: 3
+		}));
+
+		let err = tree.update(8, &leaves[0]).err().unwrap();
+		assert!(matches!(err, GadgetError::InvalidLeafIndex(8)));
+
+		// The typed error still converts into the ark error
+		let ark_err: Error = err.into();
+		assert_eq!(format!("{}", ark_err), "leaf index 8 is out of bounds");
+	}
+
+	#[test]
+	fn should_report_input_too_long() {
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let inputs = vec![Fr::from(1u64); 4];
+		let err = PoseidonCRH::<Fr, PoseidonRounds_x5_3>::hash_elements(&params, &inputs)
+			.err()
+			.unwrap();
+		assert!(matches!(
+			GadgetError::from(err),
+			GadgetError::InputTooLong { len: 4, max: 3 }
+		));
+
+		// A bridge leaf hashes four secrets, more than a width of 3 absorbs
+		type Leaf = BridgeLeaf<Fr, PoseidonCRH<Fr, PoseidonRounds_x5_3>>;
+		let rng = &mut test_rng();
+		let secrets = Leaf::generate_secrets(rng).unwrap();
+		let public = BridgePublic::new(Fr::from(1u64));
+		let err = Leaf::create_leaf(&secrets, &public, &params).err().unwrap();
+		assert!(matches!(err, GadgetError::InputTooLong { len: 4, max: 3 }));
+	}
+}
@@ -0,0 +1,178 @@
+use super::{commit_chunks, CRHFieldHasher, FieldHasher, COMMIT_RATE};
+use crate::{
+	mimc::{constraints::CRHGadget as MiMCCRHGadget, CRH as MiMCCRH},
+	poseidon::{constraints::CRHGadget as PoseidonCRHGadget, CRH as PoseidonCRH},
+	Vec,
+};
+use ark_crypto_primitives::{crh::CRHGadget, CRH};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::marker::PhantomData;
+
+/// Gadget of a `FieldHasher`, holding its allocated parameters.
+pub trait FieldHasherGadget<F: PrimeField>: Sized + Clone {
+	type Native: FieldHasher<F>;
+
+	/// Allocates the parameters of `native` as constants
+	fn from_native(
+		cs: ConstraintSystemRef<F>,
+		native: Self::Native,
+	) -> Result<Self, SynthesisError>;
+
+	fn hash(&self, inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError>;
+
+	/// Hashes two tree nodes, matching `FieldHasher::hash_two`
+	fn hash_two(&self, left: &FpVar<F>, right: &FpVar<F>) -> Result<FpVar<F>, SynthesisError> {
+		self.hash(&[left.clone(), right.clone()])
+	}
+}
+
+/// Gadget of `CRHFieldHasher`, backed by the gadget of its `CRH`
+pub struct CRHFieldHasherGadget<F, H, HG>
+where
+	F: PrimeField,
+	H: CRH<Output = F>,
+	HG: CRHGadget<H, F, OutputVar = FpVar<F>>,
+{
+	pub params: HG::ParametersVar,
+	hasher: PhantomData<H>,
+}
+
+impl<F, H, HG> Clone for CRHFieldHasherGadget<F, H, HG>
+where
+	F: PrimeField,
+	H: CRH<Output = F>,
+	HG: CRHGadget<H, F, OutputVar = FpVar<F>>,
+{
+	fn clone(&self) -> Self {
+		Self {
+			params: self.params.clone(),
+			hasher: PhantomData,
+		}
+	}
+}
+
+impl<F, H, HG> FieldHasherGadget<F> for CRHFieldHasherGadget<F, H, HG>
+where
+	F: PrimeField,
+	H: CRH<Output = F>,
+	HG: CRHGadget<H, F, OutputVar = FpVar<F>>,
+{
+	type Native = CRHFieldHasher<F, H>;
+
+	fn from_native(
+		cs: ConstraintSystemRef<F>,
+		native: Self::Native,
+	) -> Result<Self, SynthesisError> {
+		Ok(Self {
+			params: HG::ParametersVar::new_constant(cs, native.params)?,
+			hasher: PhantomData,
+		})
+	}
+
+	fn hash(&self, inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+		let mut bytes = Vec::new();
+		for input in inputs {
+			bytes.extend(input.to_bytes()?);
+		}
+		HG::evaluate(&self.params, &bytes)
+	}
+}
+
+/// Gadget of `DummyFieldHasher`, summing its inputs without any constraint
+#[cfg(feature = "test-utils")]
+#[derive(Clone)]
+pub struct DummyFieldHasherGadget<F: PrimeField> {
+	field: PhantomData<F>,
+}
+
+#[cfg(feature = "test-utils")]
+impl<F: PrimeField> FieldHasherGadget<F> for DummyFieldHasherGadget<F> {
+	type Native = super::DummyFieldHasher<F>;
+
+	fn from_native(_: ConstraintSystemRef<F>, _: Self::Native) -> Result<Self, SynthesisError> {
+		Ok(Self { field: PhantomData })
+	}
+
+	fn hash(&self, inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+		Ok(inputs.iter().fold(FpVar::zero(), |sum, x| sum + x))
+	}
+}
+
+/// Gadget of `commit`. The number of items is fixed by the circuit.
+pub fn commit_var<F: PrimeField, HG: FieldHasherGadget<F>>(
+	hasher: &HG,
+	items: &[FpVar<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+	commit_with_rate_var(hasher, items, COMMIT_RATE)
+}
+
+/// Gadget of `commit_with_rate`. The number of items is fixed by the
+/// circuit.
+pub fn commit_with_rate_var<F: PrimeField, HG: FieldHasherGadget<F>>(
+	hasher: &HG,
+	items: &[FpVar<F>],
+	rate: usize,
+) -> Result<FpVar<F>, SynthesisError> {
+	let mut state = FpVar::Constant(F::from(items.len() as u64));
+	for chunk in commit_chunks(items, rate) {
+		let mut inputs = vec![state];
+		inputs.extend_from_slice(chunk);
+		inputs.resize(rate + 1, FpVar::zero());
+		state = hasher.hash(&inputs)?;
+	}
+	Ok(state)
+}
+
+pub type PoseidonHasherGadget<F, P> =
+	CRHFieldHasherGadget<F, PoseidonCRH<F, P>, PoseidonCRHGadget<F, P>>;
+pub type MiMCHasherGadget<F, P> = CRHFieldHasherGadget<F, MiMCCRH<F, P>, MiMCCRHGadget<F, P>>;
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::{commit, PoseidonHasher},
+		setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3},
+	};
+	use ark_bn254::Fr;
+	use ark_ff::Zero;
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{test_rng, UniformRand};
+
+	#[test]
+	fn should_commit_with_length_prefix() {
+		let rng = &mut test_rng();
+		let hasher =
+			PoseidonHasher::<Fr, PoseidonRounds_x5_3>::new(setup_params_x5_3(Curve::Bn254));
+		let a = Fr::rand(rng);
+
+		let single = commit(&hasher, &[a]).unwrap();
+		let padded = commit(&hasher, &[a, Fr::zero()]).unwrap();
+		assert_ne!(single, padded);
+		assert_ne!(
+			commit(&hasher, &[]).unwrap(),
+			commit(&hasher, &[Fr::zero()]).unwrap()
+		);
+
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let hasher_var = PoseidonHasherGadget::<Fr, PoseidonRounds_x5_3>::from_native(
+			cs.clone(),
+			hasher.clone(),
+		)
+		.unwrap();
+		for len in 0..6 {
+			let items = (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+			let items_var = items
+				.iter()
+				.map(|item| FpVar::new_witness(cs.clone(), || Ok(*item)).unwrap())
+				.collect::<Vec<_>>();
+			let res = commit(&hasher, &items).unwrap();
+			let res_var = commit_var(&hasher_var, &items_var).unwrap();
+			assert_eq!(res, res_var.value().unwrap());
+		}
+		assert!(cs.is_satisfied().unwrap());
+	}
+}
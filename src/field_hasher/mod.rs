@@ -0,0 +1,218 @@
+use crate::{mimc::CRH as MiMCCRH, poseidon::CRH as PoseidonCRH, Vec};
+use ark_crypto_primitives::{Error, CRH};
+use ark_ff::{to_bytes, PrimeField};
+use ark_std::{boxed::Box, marker::PhantomData};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+#[cfg(feature = "sponge")]
+pub mod sponge;
+
+/// A hash function over field elements, holding its own parameters.
+pub trait FieldHasher<F: PrimeField> {
+	fn hash(&self, inputs: &[F]) -> Result<F, Error>;
+
+	/// Hashes two tree nodes, the same way `SparseMerkleTree` hashes inner
+	/// nodes
+	fn hash_two(&self, left: &F, right: &F) -> Result<F, Error> {
+		self.hash(&[*left, *right])
+	}
+}
+
+/// `FieldHasher` backed by a byte oriented `CRH`, hashing the concatenated
+/// little-endian encoding of the inputs.
+pub struct CRHFieldHasher<F: PrimeField, H: CRH<Output = F>> {
+	pub params: H::Parameters,
+	field: PhantomData<F>,
+}
+
+impl<F: PrimeField, H: CRH<Output = F>> CRHFieldHasher<F, H> {
+	pub fn new(params: H::Parameters) -> Self {
+		Self {
+			params,
+			field: PhantomData,
+		}
+	}
+}
+
+impl<F: PrimeField, H: CRH<Output = F>> Clone for CRHFieldHasher<F, H> {
+	fn clone(&self) -> Self {
+		Self::new(self.params.clone())
+	}
+}
+
+impl<F: PrimeField, H: CRH<Output = F>> FieldHasher<F> for CRHFieldHasher<F, H> {
+	fn hash(&self, inputs: &[F]) -> Result<F, Error> {
+		let bytes: Vec<u8> = to_bytes![inputs]?;
+		H::evaluate(&self.params, &bytes)
+	}
+}
+
+/// Number of items absorbed by each hash of `commit`, so that the state and
+/// a chunk fit in the 3 inputs of the smallest Poseidon instance
+pub const COMMIT_RATE: usize = 2;
+
+/// Commits to a list of items of any length, absorbing them `COMMIT_RATE` at
+/// a time, see `commit_with_rate`
+pub fn commit<F: PrimeField, H: FieldHasher<F>>(hasher: &H, items: &[F]) -> Result<F, Error> {
+	commit_with_rate(hasher, items, COMMIT_RATE)
+}
+
+/// Commits to a list of items of any length. The items are absorbed `rate`
+/// at a time, each hash taking the previous one followed by the next chunk,
+/// zero-padded, so `hasher` must take `rate + 1` inputs. The chain starts
+/// from the number of items, so that `[a]` and `[a, 0]`, which pad to the
+/// same chunk, don't commit to the same value.
+pub fn commit_with_rate<F: PrimeField, H: FieldHasher<F>>(
+	hasher: &H,
+	items: &[F],
+	rate: usize,
+) -> Result<F, Error> {
+	let mut state = F::from(items.len() as u64);
+	for chunk in commit_chunks(items, rate) {
+		let mut inputs = vec![state];
+		inputs.extend_from_slice(chunk);
+		inputs.resize(rate + 1, F::zero());
+		state = hasher.hash(&inputs)?;
+	}
+	Ok(state)
+}
+
+/// Splits the items in chunks of `rate`, with a single empty chunk when
+/// there are none so that the length is always hashed
+pub(crate) fn commit_chunks<T>(items: &[T], rate: usize) -> Vec<&[T]> {
+	if items.is_empty() {
+		vec![items]
+	} else {
+		items.chunks(rate).collect()
+	}
+}
+
+pub type PoseidonHasher<F, P> = CRHFieldHasher<F, PoseidonCRH<F, P>>;
+pub type MiMCHasher<F, P> = CRHFieldHasher<F, MiMCCRH<F, P>>;
+
+/// Object safe counterpart of `FieldHasher`, implemented by every cloneable
+/// hasher, so that hashers of different types can be held as
+/// `Box<dyn DynFieldHasher<F>>`, e.g. when the hash is picked at runtime.
+pub trait DynFieldHasher<F: PrimeField> {
+	fn hash_dyn(&self, inputs: &[F]) -> Result<F, Error>;
+
+	fn hash_two_dyn(&self, left: &F, right: &F) -> Result<F, Error>;
+
+	fn clone_boxed(&self) -> Box<dyn DynFieldHasher<F>>;
+}
+
+impl<F: PrimeField, H: FieldHasher<F> + Clone + 'static> DynFieldHasher<F> for H {
+	fn hash_dyn(&self, inputs: &[F]) -> Result<F, Error> {
+		self.hash(inputs)
+	}
+
+	fn hash_two_dyn(&self, left: &F, right: &F) -> Result<F, Error> {
+		self.hash_two(left, right)
+	}
+
+	fn clone_boxed(&self) -> Box<dyn DynFieldHasher<F>> {
+		Box::new(self.clone())
+	}
+}
+
+/// `FieldHasher` dispatching to a boxed `DynFieldHasher`, usable wherever a
+/// hasher type is expected, such as `IncrementalMerkleTree`
+pub struct BoxedFieldHasher<F: PrimeField>(pub Box<dyn DynFieldHasher<F>>);
+
+impl<F: PrimeField> BoxedFieldHasher<F> {
+	pub fn new<H: DynFieldHasher<F> + 'static>(hasher: H) -> Self {
+		Self(Box::new(hasher))
+	}
+}
+
+impl<F: PrimeField> Clone for BoxedFieldHasher<F> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone_boxed())
+	}
+}
+
+impl<F: PrimeField> FieldHasher<F> for BoxedFieldHasher<F> {
+	fn hash(&self, inputs: &[F]) -> Result<F, Error> {
+		self.0.hash_dyn(inputs)
+	}
+
+	fn hash_two(&self, left: &F, right: &F) -> Result<F, Error> {
+		self.0.hash_two_dyn(left, right)
+	}
+}
+
+/// Insecure hasher summing its inputs, to check the wiring of a circuit
+/// without paying for a real hash, or to rule the hash out when debugging
+#[cfg(feature = "test-utils")]
+#[derive(Default, Clone)]
+pub struct DummyFieldHasher<F: PrimeField> {
+	field: PhantomData<F>,
+}
+
+#[cfg(feature = "test-utils")]
+impl<F: PrimeField> FieldHasher<F> for DummyFieldHasher<F> {
+	fn hash(&self, inputs: &[F]) -> Result<F, Error> {
+		Ok(inputs.iter().sum())
+	}
+}
+
+#[cfg(all(feature = "default_poseidon", feature = "default_mimc"))]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		merkle_tree::incremental::IncrementalMerkleTree,
+		setup::common::{
+			setup_mimc_220, setup_params_x5_3, Curve, MiMCRounds_220_3, PoseidonRounds_x5_3,
+		},
+	};
+	use ark_bn254::Fr;
+	use ark_std::{test_rng, UniformRand};
+
+	/// Picks the hasher from a flag, as read from a config
+	fn select_hasher(use_mimc: bool) -> Box<dyn DynFieldHasher<Fr>> {
+		if use_mimc {
+			Box::new(MiMCHasher::<Fr, MiMCRounds_220_3>::new(setup_mimc_220(
+				Curve::Bn254,
+			)))
+		} else {
+			Box::new(PoseidonHasher::<Fr, PoseidonRounds_x5_3>::new(
+				setup_params_x5_3(Curve::Bn254),
+			))
+		}
+	}
+
+	fn root_of<H: FieldHasher<Fr>>(hasher: H, leaves: &[Fr]) -> Fr {
+		let mut tree = IncrementalMerkleTree::<Fr, H, 3>::new(hasher, Fr::from(0u64)).unwrap();
+		for leaf in leaves {
+			tree.append(*leaf).unwrap();
+		}
+		tree.root()
+	}
+
+	#[test]
+	fn should_build_trees_with_runtime_selected_hasher() {
+		let rng = &mut test_rng();
+		let leaves = [Fr::rand(rng), Fr::rand(rng), Fr::rand(rng)];
+		let hashers: Vec<Box<dyn DynFieldHasher<Fr>>> =
+			vec![select_hasher(false), select_hasher(true)];
+
+		let roots = hashers
+			.into_iter()
+			.map(|hasher| root_of(BoxedFieldHasher(hasher), &leaves))
+			.collect::<Vec<_>>();
+
+		// Same roots as the statically dispatched hashers
+		let poseidon =
+			PoseidonHasher::<Fr, PoseidonRounds_x5_3>::new(setup_params_x5_3(Curve::Bn254));
+		let mimc = MiMCHasher::<Fr, MiMCRounds_220_3>::new(setup_mimc_220(Curve::Bn254));
+		assert_eq!(roots[0], root_of(poseidon.clone(), &leaves));
+		assert_eq!(roots[1], root_of(mimc, &leaves));
+		assert_ne!(roots[0], roots[1]);
+
+		let boxed = BoxedFieldHasher::new(poseidon);
+		assert_eq!(root_of(boxed.clone(), &leaves), roots[0]);
+		assert_eq!(root_of(boxed, &leaves), roots[0]);
+	}
+}
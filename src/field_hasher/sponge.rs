@@ -0,0 +1,127 @@
+//! `FieldHasher` backed by the Poseidon sponge of `ark-sponge`.
+//!
+//! The sponge and `PoseidonHasher` give different digests, even when they
+//! are built from the same constants:
+//! - `PoseidonHasher` packs the byte encoding of its inputs into the state,
+//!   while the sponge absorbs field elements into its rate, keeping the first
+//!   `Rounds::CAPACITY` elements of the state, at least one, as capacity.
+//! - The sponge keeps one row of round constants per round, rather than a flat
+//!   list of round keys.
+//! - The sponge only supports exponentiation S-boxes.
+//!
+//! A tree built with one can't be checked with the other, and there is no
+//! gadget for the sponge hasher in this crate.
+use super::FieldHasher;
+use crate::poseidon::{sbox::PoseidonSbox, PoseidonError, PoseidonParameters, Rounds};
+use ark_crypto_primitives::Error;
+use ark_ff::PrimeField;
+use ark_sponge::{
+	poseidon::{PoseidonParameters as SpongeParameters, PoseidonSponge},
+	CryptographicSponge,
+};
+use ark_std::vec::Vec;
+
+/// Wraps a fresh `PoseidonSponge`, which is cloned for every hash so that
+/// hashes don't depend on each other.
+#[derive(Clone)]
+pub struct PoseidonSpongeHasher<F: PrimeField>(PoseidonSponge<F>);
+
+impl<F: PrimeField> PoseidonSpongeHasher<F> {
+	pub fn new(params: &SpongeParameters<F>) -> Self {
+		Self(PoseidonSponge::new(params))
+	}
+
+	/// Builds the sponge from this crate's parameters, with a capacity of
+	/// `P::CAPACITY` elements, or one if it is 0. Fails if `P` uses the
+	/// inverse S-box, or different S-boxes in full and partial rounds.
+	pub fn from_poseidon<P: Rounds>(params: &PoseidonParameters<F>) -> Result<Self, Error> {
+		if P::FULL_SBOX != P::PARTIAL_SBOX {
+			return Err(PoseidonError::UnsupportedSbox.into());
+		}
+		let alpha = match P::FULL_SBOX {
+			PoseidonSbox::Exponentiation(alpha) => alpha as u64,
+			PoseidonSbox::Inverse => return Err(PoseidonError::UnsupportedSbox.into()),
+		};
+		let ark = params
+			.round_keys
+			.chunks(P::WIDTH)
+			.map(|round| round.to_vec())
+			.collect::<Vec<_>>();
+		let capacity = P::CAPACITY.max(1);
+		let sponge_params = SpongeParameters::new(
+			P::FULL_ROUNDS,
+			P::PARTIAL_ROUNDS,
+			alpha,
+			params.mds_matrix.clone(),
+			ark,
+			P::WIDTH - capacity,
+			capacity,
+		);
+
+		Ok(Self::new(&sponge_params))
+	}
+}
+
+impl<F: PrimeField> FieldHasher<F> for PoseidonSpongeHasher<F> {
+	fn hash(&self, inputs: &[F]) -> Result<F, Error> {
+		let mut sponge = self.0.clone();
+		sponge.absorb(&inputs.to_vec());
+		Ok(sponge.squeeze_field_elements::<F>(1)[0])
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::PoseidonHasher,
+		merkle_tree::fixed_path::Path,
+		setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3},
+	};
+	use ark_bn254::Fr;
+	use ark_std::{test_rng, UniformRand};
+
+	#[test]
+	fn should_verify_proof_of_small_tree() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let hasher = PoseidonSpongeHasher::from_poseidon::<PoseidonRounds_x5_3>(&params).unwrap();
+
+		let leaves: Vec<Fr> = (0..4).map(|_| Fr::rand(rng)).collect();
+		let left = hasher.hash_two(&leaves[0], &leaves[1]).unwrap();
+		let right = hasher.hash_two(&leaves[2], &leaves[3]).unwrap();
+		let root = hasher.hash_two(&left, &right).unwrap();
+
+		let path =
+			Path::<Fr, PoseidonSpongeHasher<Fr>, 2>::new([(leaves[2], leaves[3]), (left, right)]);
+		assert!(path.check_membership(&root, &leaves[2], &hasher).unwrap());
+		assert!(!path.check_membership(&root, &leaves[0], &hasher).unwrap());
+
+		// Same constants, different construction
+		let crh_hasher = PoseidonHasher::<Fr, PoseidonRounds_x5_3>::new(params);
+		assert_ne!(crh_hasher.hash_two(&leaves[0], &leaves[1]).unwrap(), left);
+	}
+
+	#[test]
+	fn should_reject_unsupported_sbox() {
+		#[derive(Default, Clone)]
+		struct InverseRounds;
+
+		impl Rounds for InverseRounds {
+			const FULL_ROUNDS: usize = 8;
+			const PARTIAL_ROUNDS: usize = 57;
+			const SBOX: PoseidonSbox = PoseidonSbox::Inverse;
+			const WIDTH: usize = 3;
+		}
+
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let err = PoseidonSpongeHasher::from_poseidon::<InverseRounds>(&params)
+			.err()
+			.unwrap();
+		assert_eq!(
+			format!("{}", err),
+			format!("{}", PoseidonError::UnsupportedSbox)
+		);
+	}
+}
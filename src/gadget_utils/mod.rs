@@ -0,0 +1,212 @@
+use crate::Vec;
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::SynthesisError;
+
+/// Returns whether `a` and `b` are element-wise equal. Slices of different
+/// lengths are never equal, and two empty slices are.
+pub fn is_equal_all<F: PrimeField>(
+	a: &[FpVar<F>],
+	b: &[FpVar<F>],
+) -> Result<Boolean<F>, SynthesisError> {
+	if a.len() != b.len() {
+		return Ok(Boolean::FALSE);
+	}
+	if a.is_empty() {
+		return Ok(Boolean::TRUE);
+	}
+
+	let is_equal = a
+		.iter()
+		.zip(b)
+		.map(|(x, y)| x.is_eq(y))
+		.collect::<Result<Vec<_>, _>>()?;
+	Boolean::kary_and(&is_equal)
+}
+
+/// Enforces that `a` and `b` are element-wise equal, failing with
+/// `SynthesisError::Unsatisfiable` if their lengths differ.
+pub fn enforce_equal_all<F: PrimeField>(
+	a: &[FpVar<F>],
+	b: &[FpVar<F>],
+) -> Result<(), SynthesisError> {
+	if a.len() != b.len() {
+		return Err(SynthesisError::Unsatisfiable);
+	}
+
+	for (x, y) in a.iter().zip(b) {
+		x.enforce_equal(y)?;
+	}
+	Ok(())
+}
+
+/// Returns whether `x` equals any element of `set`. An empty set is never a
+/// match.
+pub fn is_member<F: PrimeField>(
+	x: &FpVar<F>,
+	set: &[FpVar<F>],
+) -> Result<Boolean<F>, SynthesisError> {
+	if set.is_empty() {
+		return Ok(Boolean::FALSE);
+	}
+
+	let is_equal = set
+		.iter()
+		.map(|elem| x.is_eq(elem))
+		.collect::<Result<Vec<_>, _>>()?;
+	Boolean::kary_or(&is_equal)
+}
+
+/// Enforces that `x` is either `left` or `right`, returning whether it is
+/// `left`. Used to check that a hash is one of the children of the next
+/// level of a Merkle path.
+pub fn enforce_one_of_two<F, T>(x: &T, left: &T, right: &T) -> Result<Boolean<F>, SynthesisError>
+where
+	F: PrimeField,
+	T: EqGadget<F> + CondSelectGadget<F>,
+{
+	let is_left = x.is_eq(left)?;
+	x.enforce_equal(&T::conditionally_select(&is_left, left, right)?)?;
+	Ok(is_left)
+}
+
+/// Enforces that `x` is nonzero by witnessing its inverse and enforcing
+/// `x * x^{-1} == 1`, which has no solution for zero.
+pub fn enforce_nonzero<F: PrimeField>(x: &FpVar<F>) -> Result<(), SynthesisError> {
+	x.inverse()?;
+	Ok(())
+}
+
+/// Enforces that `x` equals no element of `set`, by witnessing the inverse of
+/// every `x - s_i`. Costs one constraint per element, so it suits small
+/// published sets such as a list of banned nullifiers.
+pub fn enforce_not_member<F: PrimeField>(
+	x: &FpVar<F>,
+	set: &[FpVar<F>],
+) -> Result<(), SynthesisError> {
+	for elem in set {
+		enforce_nonzero(&(x - elem))?;
+	}
+	Ok(())
+}
+
+/// Returns whether `x` is nonzero, without constraining it to be.
+pub fn is_nonzero<F: PrimeField>(x: &FpVar<F>) -> Result<Boolean<F>, SynthesisError> {
+	x.is_neq(&FpVar::zero())
+}
+
+/// Enforces that the chain id a leaf was created for is the public chain id
+/// of the circuit, so that a leaf minted for one chain can't be spent on
+/// another.
+pub fn enforce_chain_id<F: PrimeField>(
+	leaf_chain_id: &FpVar<F>,
+	public_chain_id: &FpVar<F>,
+) -> Result<(), SynthesisError> {
+	leaf_chain_id.enforce_equal(public_chain_id)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_bls12_381::Fr;
+	use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef};
+	use ark_std::{test_rng, UniformRand};
+
+	fn witnesses(cs: &ConstraintSystemRef<Fr>, values: &[u64]) -> Vec<FpVar<Fr>> {
+		values
+			.iter()
+			.map(|v| FpVar::new_witness(cs.clone(), || Ok(Fr::from(*v))).unwrap())
+			.collect()
+	}
+
+	#[test]
+	fn should_compare_slices() {
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let a = witnesses(&cs, &[1, 2, 3]);
+		let b = witnesses(&cs, &[1, 2, 3]);
+		let c = witnesses(&cs, &[1, 2, 4]);
+
+		assert!(is_equal_all(&a, &b).unwrap().value().unwrap());
+		assert!(!is_equal_all(&a, &c).unwrap().value().unwrap());
+		assert!(!is_equal_all(&a, &c[..2]).unwrap().value().unwrap());
+		assert!(is_equal_all::<Fr>(&[], &[]).unwrap().value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_enforce_equal_slices() {
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let a = witnesses(&cs, &[1, 2, 3]);
+		let b = witnesses(&cs, &[1, 2, 3]);
+		enforce_equal_all(&a, &b).unwrap();
+		enforce_equal_all::<Fr>(&[], &[]).unwrap();
+		assert!(enforce_equal_all(&a, &b[..2]).is_err());
+		assert!(cs.is_satisfied().unwrap());
+
+		let c = witnesses(&cs, &[1, 2, 4]);
+		enforce_equal_all(&a, &c).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_check_set_membership() {
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let set = witnesses(&cs, &[5, 6, 7]);
+		let member = witnesses(&cs, &[6]).remove(0);
+		let non_member = witnesses(&cs, &[8]).remove(0);
+
+		assert!(is_member(&member, &set).unwrap().value().unwrap());
+		assert!(!is_member(&non_member, &set).unwrap().value().unwrap());
+		assert!(!is_member(&member, &[]).unwrap().value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_enforce_not_member() {
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let banned = witnesses(&cs, &[5, 6, 7]);
+		let allowed = witnesses(&cs, &[8]).remove(0);
+		enforce_not_member(&allowed, &banned).unwrap();
+		enforce_not_member(&allowed, &[]).unwrap();
+		assert_eq!(cs.num_constraints(), 3);
+		assert!(cs.is_satisfied().unwrap());
+
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let banned = witnesses(&cs, &[5, 6, 7]);
+		let member = witnesses(&cs, &[6]).remove(0);
+		enforce_not_member(&member, &banned).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_enforce_one_of_two() {
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let values = witnesses(&cs, &[1, 2, 3]);
+
+		let is_left = enforce_one_of_two(&values[0], &values[0], &values[1]).unwrap();
+		assert!(is_left.value().unwrap());
+		let is_left = enforce_one_of_two(&values[1], &values[0], &values[1]).unwrap();
+		assert!(!is_left.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+
+		enforce_one_of_two(&values[2], &values[0], &values[1]).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_enforce_nonzero() {
+		let rng = &mut test_rng();
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let x = FpVar::new_witness(cs.clone(), || Ok(Fr::rand(rng))).unwrap();
+		assert!(is_nonzero(&x).unwrap().value().unwrap());
+		enforce_nonzero(&x).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let zero = witnesses(&cs, &[0]).remove(0);
+		assert!(!is_nonzero(&zero).unwrap().value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+		enforce_nonzero(&zero).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+}
@@ -1,5 +1,5 @@
 use super::CRH;
-use crate::utils::to_field_var_elements;
+use crate::utils::{full_chunk_size, to_field_var_elements_chunked, Endianness};
 use ark_crypto_primitives::crh::constraints::CRHGadget as CRHGadgetTrait;
 use ark_ff::PrimeField;
 use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, prelude::*, uint8::UInt8};
@@ -24,7 +24,8 @@ impl<F: PrimeField> CRHGadgetTrait<CRH<F>, F> for CRHGadget<F> {
 		_: &Self::ParametersVar,
 		input: &[UInt8<F>],
 	) -> Result<Self::OutputVar, SynthesisError> {
-		let f_var_inputs: Vec<FpVar<F>> = to_field_var_elements(input)?;
+		let f_var_inputs: Vec<FpVar<F>> =
+			to_field_var_elements_chunked(input, full_chunk_size::<F>(), Endianness::Little)?;
 		assert!(f_var_inputs.len() == 1);
 		f_var_inputs
 			.get(0)
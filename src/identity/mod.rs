@@ -1,4 +1,4 @@
-use crate::utils::to_field_elements;
+use crate::utils::{full_chunk_size, to_field_elements_chunked, Endianness};
 use ark_crypto_primitives::{CryptoError, Error, CRH as CRHTrait};
 use ark_ff::{fields::PrimeField, BigInteger};
 use ark_std::{marker::PhantomData, rand::Rng, vec::Vec};
@@ -21,7 +21,8 @@ impl<F: PrimeField> CRHTrait for CRH<F> {
 	}
 
 	fn evaluate(_: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
-		let f_inputs: Vec<F> = to_field_elements(input)?;
+		let f_inputs: Vec<F> =
+			to_field_elements_chunked(input, full_chunk_size::<F>(), Endianness::Little)?;
 
 		assert!(f_inputs.len() == 1);
 
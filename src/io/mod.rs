@@ -0,0 +1,194 @@
+//! Helpers to persist verifying keys and proofs, based on their canonical
+//! (compressed) serialization.
+
+use crate::{
+	utils::{fr_from_bytes_be, fr_to_bytes_be, FIELD_BYTES_SIZE},
+	Vec,
+};
+use ark_crypto_primitives::Error;
+use ark_ec::PairingEngine;
+use ark_groth16::{Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	error::Error as ArkError,
+	io::{Read, Write},
+};
+
+#[derive(Debug)]
+pub enum IoError {
+	InvalidHex,
+	InvalidBase64,
+}
+
+impl core::fmt::Display for IoError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		use IoError::*;
+		let msg = match self {
+			InvalidHex => format!("invalid hex string"),
+			InvalidBase64 => format!("invalid base64 string"),
+		};
+		write!(f, "{}", msg)
+	}
+}
+
+impl ArkError for IoError {}
+
+pub fn write_vk<E: PairingEngine, W: Write>(vk: &VerifyingKey<E>, writer: W) -> Result<(), Error> {
+	vk.serialize(writer)?;
+	Ok(())
+}
+
+pub fn read_vk<E: PairingEngine, R: Read>(reader: R) -> Result<VerifyingKey<E>, Error> {
+	Ok(VerifyingKey::deserialize(reader)?)
+}
+
+pub fn write_proof<E: PairingEngine, W: Write>(proof: &Proof<E>, writer: W) -> Result<(), Error> {
+	proof.serialize(writer)?;
+	Ok(())
+}
+
+pub fn read_proof<E: PairingEngine, R: Read>(reader: R) -> Result<Proof<E>, Error> {
+	Ok(Proof::deserialize(reader)?)
+}
+
+/// Serializes any canonically serializable value into a byte vector
+pub fn to_bytes<T: CanonicalSerialize>(value: &T) -> Result<Vec<u8>, Error> {
+	let mut bytes = Vec::with_capacity(value.serialized_size());
+	value.serialize(&mut bytes)?;
+	Ok(bytes)
+}
+
+pub fn from_bytes<T: CanonicalDeserialize>(mut bytes: &[u8]) -> Result<T, Error> {
+	Ok(T::deserialize(&mut bytes)?)
+}
+
+/// Size of a public input in the calldata of Solidity verifiers
+pub const PUBLIC_INPUT_SIZE: usize = FIELD_BYTES_SIZE;
+
+/// Serializes public inputs as the big endian `uint256` words that Solidity
+/// verifiers take, in the order the circuit expects them
+pub fn serialize_public_inputs<E: PairingEngine>(
+	inputs: &[E::Fr],
+) -> Result<Vec<[u8; PUBLIC_INPUT_SIZE]>, Error> {
+	inputs.iter().map(|input| Ok(fr_to_bytes_be(input)?)).collect()
+}
+
+/// Parses public inputs from `serialize_public_inputs`, rejecting any word
+/// that isn't the canonical encoding of a field element with
+/// `FieldBytesError::NonCanonical`
+pub fn parse_public_inputs<E: PairingEngine>(
+	words: &[[u8; PUBLIC_INPUT_SIZE]],
+) -> Result<Vec<E::Fr>, Error> {
+	words.iter().map(|word| Ok(fr_from_bytes_be(word)?)).collect()
+}
+
+#[cfg(feature = "encoding")]
+pub fn to_hex<T: CanonicalSerialize>(value: &T) -> Result<ark_std::string::String, Error> {
+	Ok(hex::encode(to_bytes(value)?))
+}
+
+#[cfg(feature = "encoding")]
+pub fn from_hex<T: CanonicalDeserialize>(s: &str) -> Result<T, Error> {
+	let bytes = hex::decode(s.trim_start_matches("0x")).map_err(|_| IoError::InvalidHex)?;
+	from_bytes(&bytes)
+}
+
+#[cfg(feature = "encoding")]
+pub fn to_base64<T: CanonicalSerialize>(value: &T) -> Result<ark_std::string::String, Error> {
+	Ok(base64::encode(to_bytes(value)?))
+}
+
+#[cfg(feature = "encoding")]
+pub fn from_base64<T: CanonicalDeserialize>(s: &str) -> Result<T, Error> {
+	let bytes = base64::decode(s).map_err(|_| IoError::InvalidBase64)?;
+	from_bytes(&bytes)
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		leaf::mixer::Private,
+		prover::mixer::MixerProver,
+		setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3},
+	};
+	use ark_bn254::{Bn254, Fr as Bn254Fr};
+	use ark_ff::{One, Zero};
+	use ark_std::test_rng;
+
+	type Prover = MixerProver<Bn254, PoseidonRounds_x5_3, 4>;
+
+	#[test]
+	fn should_round_trip_vk_and_proof() {
+		let rng = &mut test_rng();
+		let prover = Prover::new(setup_params_x5_3::<Bn254Fr>(Curve::Bn254));
+
+		let secrets = Private::generate(rng);
+		let (leaf, nullifier_hash) = prover.create_leaf(&secrets).unwrap();
+		let tree = prover.create_tree(&[leaf]).unwrap();
+		let root = tree.root().inner();
+		let path = tree.generate_membership_proof(0);
+
+		let (pk, vk) = prover.setup(rng).unwrap();
+		let proof = prover
+			.create_proof(&pk, secrets, path, root, nullifier_hash, rng)
+			.unwrap();
+
+		let mut vk_bytes = Vec::new();
+		write_vk(&vk, &mut vk_bytes).unwrap();
+		let mut proof_bytes = Vec::new();
+		write_proof(&proof, &mut proof_bytes).unwrap();
+
+		let vk_new = read_vk::<Bn254, _>(&vk_bytes[..]).unwrap();
+		let proof_new = read_proof::<Bn254, _>(&proof_bytes[..]).unwrap();
+		assert_eq!(vk, vk_new);
+
+		let public_inputs = Prover::public_inputs(root, nullifier_hash);
+		assert!(Prover::verify(&vk_new, &public_inputs, &proof_new));
+	}
+
+	#[test]
+	fn should_serialize_public_inputs_for_solidity() {
+		let root = Bn254Fr::from(0x0102u64);
+		let nullifier_hash = -Bn254Fr::one();
+		let public_inputs = Prover::public_inputs(root, nullifier_hash);
+
+		let words = serialize_public_inputs::<Bn254>(&public_inputs).unwrap();
+		let mut expected_root = [0u8; 32];
+		expected_root[30] = 0x01;
+		expected_root[31] = 0x02;
+		// The bn254 scalar field modulus minus one
+		let expected_nullifier_hash = [
+			0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81,
+			0x58, 0x5d, 0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93,
+			0xf0, 0x00, 0x00, 0x00,
+		];
+		assert_eq!(words, vec![expected_root, expected_nullifier_hash]);
+		assert_eq!(parse_public_inputs::<Bn254>(&words).unwrap(), public_inputs);
+
+		// The modulus itself is rejected instead of being reduced to zero
+		let mut modulus = expected_nullifier_hash;
+		modulus[31] = 0x01;
+		assert!(parse_public_inputs::<Bn254>(&[modulus]).is_err());
+		assert_eq!(parse_public_inputs::<Bn254>(&[[0u8; 32]]).unwrap(), vec![
+			Bn254Fr::zero()
+		]);
+	}
+
+	#[cfg(feature = "encoding")]
+	#[test]
+	fn should_round_trip_hex_and_base64() {
+		let rng = &mut test_rng();
+		let prover = Prover::new(setup_params_x5_3::<Bn254Fr>(Curve::Bn254));
+		let (_, vk) = prover.setup(rng).unwrap();
+
+		let vk_hex = to_hex(&vk).unwrap();
+		assert_eq!(vk, from_hex::<VerifyingKey<Bn254>>(&vk_hex).unwrap());
+
+		let vk_base64 = to_base64(&vk).unwrap();
+		assert_eq!(vk, from_base64::<VerifyingKey<Bn254>>(&vk_base64).unwrap());
+
+		assert!(from_hex::<VerifyingKey<Bn254>>("not hex").is_err());
+	}
+}
@@ -1,5 +1,5 @@
-use crate::leaf::LeafCreation;
-use ark_crypto_primitives::{crh::CRH, Error};
+use crate::{error::GadgetError, leaf::LeafCreation};
+use ark_crypto_primitives::crh::CRH;
 use ark_ff::{fields::PrimeField, to_bytes};
 use ark_std::{marker::PhantomData, rand::Rng};
 
@@ -37,7 +37,7 @@ impl<F: PrimeField, H: CRH> LeafCreation<H> for BasicLeaf<F, H> {
 	type Private = Private<F>;
 	type Public = Public<F>;
 
-	fn generate_secrets<R: Rng>(r: &mut R) -> Result<Self::Private, Error> {
+	fn generate_secrets<R: Rng>(r: &mut R) -> Result<Self::Private, GadgetError> {
 		Ok(Self::Private::generate(r))
 	}
 
@@ -45,14 +45,17 @@ impl<F: PrimeField, H: CRH> LeafCreation<H> for BasicLeaf<F, H> {
 		s: &Self::Private,
 		_: &Self::Public,
 		h: &H::Parameters,
-	) -> Result<Self::Leaf, Error> {
+	) -> Result<Self::Leaf, GadgetError> {
 		let bytes = to_bytes![s.r, s.nullifier]?;
-		H::evaluate(h, &bytes)
+		Ok(H::evaluate(h, &bytes)?)
 	}
 
-	fn create_nullifier(s: &Self::Private, h: &H::Parameters) -> Result<Self::Nullifier, Error> {
+	fn create_nullifier(
+		s: &Self::Private,
+		h: &H::Parameters,
+	) -> Result<Self::Nullifier, GadgetError> {
 		let bytes = to_bytes![s.nullifier, s.nullifier]?;
-		H::evaluate(h, &bytes)
+		Ok(H::evaluate(h, &bytes)?)
 	}
 }
 
@@ -1,5 +1,6 @@
 use super::{BridgeLeaf, Output, Private, Public};
 use crate::{
+	gadget_utils::{enforce_equal_all, is_equal_all},
 	leaf::{LeafCreation, LeafCreationGadget},
 	Vec,
 };
@@ -79,9 +80,17 @@ impl<F: PrimeField> CondSelectGadget<F> for OutputVar<F> {
 
 impl<F: PrimeField> EqGadget<F> for OutputVar<F> {
 	fn is_eq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
-		self.leaf
-			.is_eq(&other.leaf)
-			.and(self.nullifier_hash.is_eq(&other.nullifier_hash))
+		is_equal_all(&[self.leaf.clone(), self.nullifier_hash.clone()], &[
+			other.leaf.clone(),
+			other.nullifier_hash.clone(),
+		])
+	}
+
+	fn enforce_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+		enforce_equal_all(&[self.leaf.clone(), self.nullifier_hash.clone()], &[
+			other.leaf.clone(),
+			other.nullifier_hash.clone(),
+		])
 	}
 }
 
@@ -1,12 +1,17 @@
-use crate::leaf::LeafCreation;
-use ark_crypto_primitives::{crh::CRH, Error};
-use ark_ff::{fields::PrimeField, to_bytes, ToBytes};
+use crate::{error::GadgetError, leaf::LeafCreation, Vec};
+use ark_crypto_primitives::crh::CRH;
+use ark_ff::{fields::PrimeField, to_bytes, ToBytes, ToConstraintField};
 use ark_std::{
 	io::{Result as IoResult, Write},
 	marker::PhantomData,
 	rand::Rng,
 };
 
+#[cfg(feature = "zeroize")]
+use crate::leaf::zeroize_field;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
 #[cfg(feature = "r1cs")]
 pub mod constraints;
 
@@ -27,6 +32,26 @@ impl<F: PrimeField> Private<F> {
 	}
 }
 
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> Zeroize for Private<F> {
+	fn zeroize(&mut self) {
+		zeroize_field(&mut self.r);
+		zeroize_field(&mut self.nullifier);
+		zeroize_field(&mut self.rho);
+	}
+}
+
+/// Wipes the secrets once they are no longer used
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> Drop for Private<F> {
+	fn drop(&mut self) {
+		self.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> ZeroizeOnDrop for Private<F> {}
+
 #[derive(Default, Clone)]
 pub struct Public<F: PrimeField> {
 	pub chain_id: F,
@@ -38,6 +63,13 @@ impl<F: PrimeField> Public<F> {
 	}
 }
 
+/// The chain id, as `PublicVar` allocates it
+impl<F: PrimeField> ToConstraintField<F> for Public<F> {
+	fn to_field_elements(&self) -> Option<Vec<F>> {
+		Some(vec![self.chain_id])
+	}
+}
+
 #[derive(Clone, Eq, PartialEq, Debug, Hash, Default)]
 pub struct Output<F: PrimeField> {
 	pub leaf: F,
@@ -64,7 +96,7 @@ impl<F: PrimeField, H: CRH> LeafCreation<H> for BridgeLeaf<F, H> {
 	type Private = Private<F>;
 	type Public = Public<F>;
 
-	fn generate_secrets<R: Rng>(r: &mut R) -> Result<Self::Private, Error> {
+	fn generate_secrets<R: Rng>(r: &mut R) -> Result<Self::Private, GadgetError> {
 		Ok(Self::Private::generate(r))
 	}
 
@@ -72,14 +104,17 @@ impl<F: PrimeField, H: CRH> LeafCreation<H> for BridgeLeaf<F, H> {
 		s: &Self::Private,
 		p: &Self::Public,
 		h: &H::Parameters,
-	) -> Result<Self::Leaf, Error> {
+	) -> Result<Self::Leaf, GadgetError> {
 		let input_bytes = to_bytes![s.r, s.nullifier, s.rho, p.chain_id]?;
-		H::evaluate(h, &input_bytes)
+		Ok(H::evaluate(h, &input_bytes)?)
 	}
 
-	fn create_nullifier(s: &Self::Private, h: &H::Parameters) -> Result<Self::Nullifier, Error> {
+	fn create_nullifier(
+		s: &Self::Private,
+		h: &H::Parameters,
+	) -> Result<Self::Nullifier, GadgetError> {
 		let nullifier_bytes = to_bytes![s.nullifier, s.nullifier]?;
-		H::evaluate(h, &nullifier_bytes)
+		Ok(H::evaluate(h, &nullifier_bytes)?)
 	}
 }
 
@@ -1,13 +1,13 @@
 use ark_crypto_primitives::crh::{constraints::CRHGadget, CRH};
-use ark_r1cs_std::prelude::*;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
 use ark_relations::r1cs::SynthesisError;
 
-use ark_ff::Field;
+use ark_ff::PrimeField;
 use core::fmt::Debug;
 
 use crate::leaf::LeafCreation;
 
-pub trait LeafCreationGadget<F: Field, H: CRH, HG: CRHGadget<H, F>, L: LeafCreation<H>>:
+pub trait LeafCreationGadget<F: PrimeField, H: CRH, HG: CRHGadget<H, F>, L: LeafCreation<H>>:
 	Sized
 {
 	type LeafVar: EqGadget<F>
@@ -41,4 +41,17 @@ pub trait LeafCreationGadget<F: Field, H: CRH, HG: CRHGadget<H, F>, L: LeafCreat
 		s: &Self::PrivateVar,
 		h: &HG::ParametersVar,
 	) -> Result<Self::NullifierVar, SynthesisError>;
+
+	/// Nullifier of the leaf with public inputs `p` at `index`, which
+	/// circuits derive from the membership path of the leaf so that it can't
+	/// be picked freely. Defaults to `create_nullifier`, for nullifiers that
+	/// depend on neither.
+	fn create_nullifier_at(
+		s: &Self::PrivateVar,
+		_p: &Self::PublicVar,
+		_index: &FpVar<F>,
+		h: &HG::ParametersVar,
+	) -> Result<Self::NullifierVar, SynthesisError> {
+		Self::create_nullifier(s, h)
+	}
 }
@@ -1,5 +1,8 @@
-use super::{MixerLeaf, Output, Private};
+use super::{
+	DoubleNullifier, IndexedNullifier, MixerLeaf, NullifierScheme, Output, Private, SaltedNullifier,
+};
 use crate::{
+	gadget_utils::{enforce_equal_all, is_equal_all},
 	leaf::{LeafCreation, LeafCreationGadget},
 	Vec,
 };
@@ -23,6 +26,83 @@ impl<F: PrimeField> PrivateVar<F> {
 	}
 }
 
+/// Gadget of a `NullifierScheme`. The index and the salt of the leaf aren't
+/// witnessed secrets, where they could be picked freely: schemes binding them
+/// hash the `index` the circuit derives from the membership path and the
+/// public inputs `p` of the leaf.
+pub trait NullifierSchemeGadget<F: PrimeField, H: CRH, HG: CRHGadget<H, F>>:
+	NullifierScheme<F, H>
+{
+	type PublicVar: AllocVar<Self::Public, F> + Clone;
+
+	fn nullifier_hash_var(
+		s: &PrivateVar<F>,
+		p: Option<&Self::PublicVar>,
+		index: Option<&FpVar<F>>,
+		h: &HG::ParametersVar,
+	) -> Result<HG::OutputVar, SynthesisError>;
+}
+
+impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> NullifierSchemeGadget<F, H, HG>
+	for DoubleNullifier
+{
+	type PublicVar = PublicVar<F>;
+
+	fn nullifier_hash_var(
+		s: &PrivateVar<F>,
+		_: Option<&Self::PublicVar>,
+		_: Option<&FpVar<F>>,
+		h: &HG::ParametersVar,
+	) -> Result<HG::OutputVar, SynthesisError> {
+		let mut nullifier_hash_bytes = Vec::new();
+		nullifier_hash_bytes.extend(s.nullifier.to_bytes()?);
+		nullifier_hash_bytes.extend(s.nullifier.to_bytes()?);
+		HG::evaluate(h, &nullifier_hash_bytes)
+	}
+}
+
+impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> NullifierSchemeGadget<F, H, HG>
+	for IndexedNullifier
+{
+	type PublicVar = PublicVar<F>;
+
+	/// Fails with `SynthesisError::AssignmentMissing` without an index
+	fn nullifier_hash_var(
+		s: &PrivateVar<F>,
+		_: Option<&Self::PublicVar>,
+		index: Option<&FpVar<F>>,
+		h: &HG::ParametersVar,
+	) -> Result<HG::OutputVar, SynthesisError> {
+		let index = index.ok_or(SynthesisError::AssignmentMissing)?;
+		let mut nullifier_hash_bytes = Vec::new();
+		nullifier_hash_bytes.extend(s.nullifier.to_bytes()?);
+		nullifier_hash_bytes.extend(index.to_bytes()?);
+		HG::evaluate(h, &nullifier_hash_bytes)
+	}
+}
+
+impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> NullifierSchemeGadget<F, H, HG>
+	for SaltedNullifier
+{
+	type PublicVar = FpVar<F>;
+
+	/// Fails with `SynthesisError::AssignmentMissing` without the public salt
+	fn nullifier_hash_var(
+		s: &PrivateVar<F>,
+		salt: Option<&Self::PublicVar>,
+		_: Option<&FpVar<F>>,
+		h: &HG::ParametersVar,
+	) -> Result<HG::OutputVar, SynthesisError> {
+		let salt = salt.ok_or(SynthesisError::AssignmentMissing)?;
+		let nullifier_bytes = s.nullifier.to_bytes()?;
+		let mut nullifier_hash_bytes = Vec::new();
+		nullifier_hash_bytes.extend_from_slice(&nullifier_bytes);
+		nullifier_hash_bytes.extend_from_slice(&nullifier_bytes);
+		nullifier_hash_bytes.extend(salt.to_bytes()?);
+		HG::evaluate(h, &nullifier_hash_bytes)
+	}
+}
+
 #[derive(Clone, Default)]
 pub struct PublicVar<F: PrimeField> {
 	field: PhantomData<F>,
@@ -73,9 +153,17 @@ impl<F: PrimeField> CondSelectGadget<F> for OutputVar<F> {
 
 impl<F: PrimeField> EqGadget<F> for OutputVar<F> {
 	fn is_eq(&self, other: &Self) -> Result<Boolean<F>, SynthesisError> {
-		self.leaf
-			.is_eq(&other.leaf)
-			.and(self.nullifier_hash.is_eq(&other.nullifier_hash))
+		is_equal_all(&[self.leaf.clone(), self.nullifier_hash.clone()], &[
+			other.leaf.clone(),
+			other.nullifier_hash.clone(),
+		])
+	}
+
+	fn enforce_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+		enforce_equal_all(&[self.leaf.clone(), self.nullifier_hash.clone()], &[
+			other.leaf.clone(),
+			other.nullifier_hash.clone(),
+		])
 	}
 }
 
@@ -95,13 +183,18 @@ pub struct MixerLeafGadget<F: PrimeField, H: CRH, HG: CRHGadget<H, F>, L: LeafCr
 	leaf_creation: PhantomData<L>,
 }
 
-impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> LeafCreationGadget<F, H, HG, MixerLeaf<F, H>>
-	for MixerLeafGadget<F, H, HG, MixerLeaf<F, H>>
+impl<F, H, HG, N> LeafCreationGadget<F, H, HG, MixerLeaf<F, H, N>>
+	for MixerLeafGadget<F, H, HG, MixerLeaf<F, H, N>>
+where
+	F: PrimeField,
+	H: CRH,
+	HG: CRHGadget<H, F>,
+	N: NullifierSchemeGadget<F, H, HG>,
 {
 	type LeafVar = HG::OutputVar;
 	type NullifierVar = HG::OutputVar;
 	type PrivateVar = PrivateVar<F>;
-	type PublicVar = PublicVar<F>;
+	type PublicVar = N::PublicVar;
 
 	fn create_leaf(
 		s: &Self::PrivateVar,
@@ -120,10 +213,43 @@ impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> LeafCreationGadget<F, H, HG, Mi
 		s: &Self::PrivateVar,
 		h: &HG::ParametersVar,
 	) -> Result<Self::NullifierVar, SynthesisError> {
+		N::nullifier_hash_var(s, None, None, h)
+	}
+
+	fn create_nullifier_at(
+		s: &Self::PrivateVar,
+		p: &Self::PublicVar,
+		index: &FpVar<F>,
+		h: &HG::ParametersVar,
+	) -> Result<Self::NullifierVar, SynthesisError> {
+		N::nullifier_hash_var(s, Some(p), Some(index), h)
+	}
+}
+
+impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> MixerLeafGadget<F, H, HG, MixerLeaf<F, H>> {
+	/// Computes the leaf and the `DoubleNullifier` hash with a single
+	/// parameters allocation, serializing each secret only once. Returns the
+	/// same values as `create_leaf` and `create_nullifier`.
+	pub fn create_leaf_and_nullifier(
+		h: &HG::ParametersVar,
+		s: &PrivateVar<F>,
+	) -> Result<(HG::OutputVar, HG::OutputVar), SynthesisError> {
+		let r_bytes = s.r.to_bytes()?;
+		let nullifier_bytes = s.nullifier.to_bytes()?;
+		let rho_bytes = s.rho.to_bytes()?;
+
+		let mut leaf_bytes = Vec::new();
+		leaf_bytes.extend_from_slice(&r_bytes);
+		leaf_bytes.extend_from_slice(&nullifier_bytes);
+		leaf_bytes.extend_from_slice(&rho_bytes);
+		let leaf = HG::evaluate(h, &leaf_bytes)?;
+
 		let mut nullifier_hash_bytes = Vec::new();
-		nullifier_hash_bytes.extend(s.nullifier.to_bytes()?);
-		nullifier_hash_bytes.extend(s.nullifier.to_bytes()?);
-		HG::evaluate(h, &nullifier_hash_bytes)
+		nullifier_hash_bytes.extend_from_slice(&nullifier_bytes);
+		nullifier_hash_bytes.extend_from_slice(&nullifier_bytes);
+		let nullifier_hash = HG::evaluate(h, &nullifier_hash_bytes)?;
+
+		Ok((leaf, nullifier_hash))
 	}
 }
 
@@ -242,4 +368,176 @@ mod test {
 		assert!(nullifier_res.value().unwrap());
 		assert!(nullifier_res.cs().is_satisfied().unwrap());
 	}
+
+	#[test]
+	fn should_create_leaf_and_nullifier_with_shared_params() {
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_5::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_5::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+		let secrets = Leaf::generate_secrets(rng).unwrap();
+
+		// Separate calls
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let secrets_var = PrivateVar::new_witness(cs.clone(), || Ok(&secrets)).unwrap();
+		let leaf_params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+		let public_var = PublicVar::default();
+		let leaf_var =
+			LeafGadget::create_leaf(&secrets_var, &public_var, &leaf_params_var).unwrap();
+		let nullifier_params_var =
+			PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+		let nullifier_var =
+			LeafGadget::create_nullifier(&secrets_var, &nullifier_params_var).unwrap();
+		let separate_constraints = cs.num_constraints();
+
+		// Combined call
+		let combined_cs = ConstraintSystem::<Fq>::new_ref();
+		let secrets_var = PrivateVar::new_witness(combined_cs.clone(), || Ok(&secrets)).unwrap();
+		let variables_before = combined_cs.num_witness_variables();
+		let params_var = PoseidonParametersVar::new_constant(combined_cs.clone(), &params).unwrap();
+		// The parameters are constants, their allocation adds no variables
+		assert_eq!(combined_cs.num_witness_variables(), variables_before);
+		assert!(params_var
+			.mds_matrix
+			.iter()
+			.flatten()
+			.all(|m| m.is_constant()));
+
+		let (combined_leaf_var, combined_nullifier_var) =
+			LeafGadget::create_leaf_and_nullifier(&params_var, &secrets_var).unwrap();
+
+		assert_eq!(
+			leaf_var.value().unwrap(),
+			combined_leaf_var.value().unwrap()
+		);
+		assert_eq!(
+			nullifier_var.value().unwrap(),
+			combined_nullifier_var.value().unwrap()
+		);
+		assert!(combined_cs.num_constraints() < separate_constraints);
+		assert!(cs.is_satisfied().unwrap());
+		assert!(combined_cs.is_satisfied().unwrap());
+	}
+
+	/// Checks that the gadget of the scheme `N` computes its native
+	/// nullifier hash, given the public inputs of the leaf
+	fn check_nullifier_scheme<N: NullifierSchemeGadget<Fq, PoseidonCRH5, PoseidonCRH5Gadget>>(
+		secrets: &Private<Fq>,
+		public: &N::Public,
+	) -> Fq {
+		let rounds = get_rounds_poseidon_bls381_x5_5::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_5::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+		let nullifier_hash = N::nullifier_hash(secrets, &params).unwrap();
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+		let secrets_var = PrivateVar::new_witness(cs.clone(), || Ok(secrets)).unwrap();
+		let public_var = N::PublicVar::new_input(cs.clone(), || Ok(public)).unwrap();
+		let index_var = FpVar::new_witness(cs.clone(), || Ok(secrets.index())).unwrap();
+		let nullifier_hash_var =
+			N::nullifier_hash_var(&secrets_var, Some(&public_var), Some(&index_var), &params_var)
+				.unwrap();
+		assert_eq!(nullifier_hash_var.value().unwrap(), nullifier_hash);
+		assert!(cs.is_satisfied().unwrap());
+		nullifier_hash
+	}
+
+	#[test]
+	fn should_match_native_nullifier_schemes() {
+		let rng = &mut test_rng();
+		let secrets = Leaf::generate_secrets(rng).unwrap().with_index(5);
+
+		let double = check_nullifier_scheme::<DoubleNullifier>(&secrets, &());
+		let indexed = check_nullifier_scheme::<IndexedNullifier>(&secrets, &());
+		assert_ne!(double, indexed);
+
+		// The index only changes the indexed scheme
+		let moved = secrets.clone().with_index(6);
+		assert_eq!(check_nullifier_scheme::<DoubleNullifier>(&moved, &()), double);
+		assert_ne!(check_nullifier_scheme::<IndexedNullifier>(&moved, &()), indexed);
+
+		// Through the leaf gadget of the indexed scheme
+		type IndexedLeaf = MixerLeaf<Fq, PoseidonCRH5, IndexedNullifier>;
+		type IndexedLeafGadget = MixerLeafGadget<Fq, PoseidonCRH5, PoseidonCRH5Gadget, IndexedLeaf>;
+		let rounds = get_rounds_poseidon_bls381_x5_5::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_5::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+		let secrets_var = PrivateVar::new_witness(cs.clone(), || Ok(&secrets)).unwrap();
+		let index_var = FpVar::new_witness(cs.clone(), || Ok(Fq::from(5u64))).unwrap();
+		let public_var = PublicVar::default();
+		let nullifier_var = IndexedLeafGadget::create_nullifier_at(
+			&secrets_var,
+			&public_var,
+			&index_var,
+			&params_var,
+		)
+		.unwrap();
+		assert_eq!(
+			nullifier_var.value().unwrap(),
+			IndexedLeaf::create_nullifier(&secrets, &params).unwrap()
+		);
+
+		// The secrets alone don't give the index
+		assert!(IndexedLeafGadget::create_nullifier(&secrets_var, &params_var).is_err());
+	}
+
+	#[test]
+	fn should_compare_both_output_fields() {
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let output = Output {
+			leaf: Fq::from(1u64),
+			nullifier_hash: Fq::from(2u64),
+		};
+		let other_leaf = Output {
+			leaf: Fq::from(3u64),
+			nullifier_hash: Fq::from(2u64),
+		};
+
+		let output_var = OutputVar::new_witness(cs.clone(), || Ok(&output)).unwrap();
+		let same_var = OutputVar::new_witness(cs.clone(), || Ok(&output)).unwrap();
+		let other_leaf_var = OutputVar::new_witness(cs.clone(), || Ok(&other_leaf)).unwrap();
+
+		assert!(output_var.is_eq(&same_var).unwrap().value().unwrap());
+		assert!(!output_var.is_eq(&other_leaf_var).unwrap().value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_match_native_salted_nullifier() {
+		type SaltedLeaf = MixerLeaf<Fq, PoseidonCRH5, SaltedNullifier>;
+		type SaltedLeafGadget = MixerLeafGadget<Fq, PoseidonCRH5, PoseidonCRH5Gadget, SaltedLeaf>;
+
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_5::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_5::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+		let secrets = SaltedLeaf::generate_secrets(rng).unwrap();
+
+		let mut nullifier_hashes = Vec::new();
+		for &salt in [Fq::from(1u64), Fq::from(2u64)].iter() {
+			let secrets = secrets.clone().with_salt(salt);
+			let nullifier_hash = check_nullifier_scheme::<SaltedNullifier>(&secrets, &salt);
+			assert_eq!(
+				nullifier_hash,
+				SaltedLeaf::create_nullifier(&secrets, &params).unwrap()
+			);
+			nullifier_hashes.push(nullifier_hash);
+		}
+		assert_ne!(nullifier_hashes[0], nullifier_hashes[1]);
+
+		// The salt is read from the public inputs, not from the secrets
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+		let secrets_var = PrivateVar::new_witness(cs.clone(), || Ok(&secrets)).unwrap();
+		let salt_var = FpVar::new_input(cs.clone(), || Ok(Fq::from(2u64))).unwrap();
+		let index_var = FpVar::new_witness(cs.clone(), || Ok(Fq::from(0u64))).unwrap();
+		let nullifier_var =
+			SaltedLeafGadget::create_nullifier_at(&secrets_var, &salt_var, &index_var, &params_var)
+				.unwrap();
+		assert_eq!(nullifier_var.value().unwrap(), nullifier_hashes[1]);
+		assert!(SaltedLeafGadget::create_nullifier(&secrets_var, &params_var).is_err());
+	}
 }
@@ -1,11 +1,17 @@
-use crate::leaf::LeafCreation;
-use ark_crypto_primitives::{crh::CRH, Error};
-use ark_ff::{fields::PrimeField, to_bytes, ToBytes};
+use crate::{error::GadgetError, leaf::LeafCreation};
+use ark_crypto_primitives::{crh::CRH, CryptoError, Error};
+use ark_ff::{fields::PrimeField, to_bytes, BigInteger, FromBytes, ToBytes};
 use ark_std::{
-	io::{Result as IoResult, Write},
+	io::{Read, Result as IoResult, Write},
 	marker::PhantomData,
 	rand::Rng,
 };
+use blake2::{Blake2b, Digest};
+
+#[cfg(feature = "zeroize")]
+use crate::leaf::zeroize_field;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[cfg(feature = "r1cs")]
 pub mod constraints;
@@ -15,6 +21,11 @@ pub struct Private<F: PrimeField> {
 	r: F,
 	nullifier: F,
 	rho: F,
+	/// Index of the leaf in its tree, only read by `IndexedNullifier`
+	index: F,
+	/// Identifier of the pool the leaf is spent in, only read by
+	/// `SaltedNullifier`
+	salt: F,
 }
 
 impl<F: PrimeField> Private<F> {
@@ -23,9 +34,45 @@ impl<F: PrimeField> Private<F> {
 			r: F::rand(rng),
 			nullifier: F::rand(rng),
 			rho: F::rand(rng),
+			index: F::zero(),
+			salt: F::zero(),
 		}
 	}
 
+	/// Derives the secrets from `seed`, so that a note can be recovered from
+	/// it. Each secret is the Blake2b hash of the seed with a counter, reduced
+	/// modulo the field order.
+	pub fn from_seed(seed: &[u8]) -> Self {
+		let derive = |counter: u8| {
+			let digest = Blake2b::new()
+				.chain(b"mixer-leaf-secret")
+				.chain(&[counter])
+				.chain(seed)
+				.finalize();
+			F::from_le_bytes_mod_order(&digest)
+		};
+
+		Self {
+			r: derive(0),
+			nullifier: derive(1),
+			rho: derive(2),
+			index: F::zero(),
+			salt: F::zero(),
+		}
+	}
+
+	/// Sets the index of the leaf in its tree, once it is inserted
+	pub fn with_index(mut self, index: u64) -> Self {
+		self.index = F::from(index);
+		self
+	}
+
+	/// Sets the identifier of the pool the leaf is spent in
+	pub fn with_salt(mut self, salt: F) -> Self {
+		self.salt = salt;
+		self
+	}
+
 	pub fn r(&self) -> F {
 		self.r
 	}
@@ -37,14 +84,56 @@ impl<F: PrimeField> Private<F> {
 	pub fn rho(&self) -> F {
 		self.rho
 	}
+
+	pub fn index(&self) -> F {
+		self.index
+	}
+
+	pub fn salt(&self) -> F {
+		self.salt
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> Zeroize for Private<F> {
+	fn zeroize(&mut self) {
+		zeroize_field(&mut self.r);
+		zeroize_field(&mut self.nullifier);
+		zeroize_field(&mut self.rho);
+		zeroize_field(&mut self.index);
+		zeroize_field(&mut self.salt);
+	}
 }
 
+/// Wipes the secrets once they are no longer used
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> Drop for Private<F> {
+	fn drop(&mut self) {
+		self.zeroize();
+	}
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> ZeroizeOnDrop for Private<F> {}
+
 #[derive(Clone, Eq, PartialEq, Debug, Hash, Default)]
 pub struct Output<F: PrimeField> {
 	pub leaf: F,
 	pub nullifier_hash: F,
 }
 
+impl<F: PrimeField> Output<F> {
+	/// Parses an output written with `ToBytes`, failing if the length isn't
+	/// the size of two field elements or if an element isn't canonical.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+		let field_size = F::BigInt::NUM_LIMBS * 8;
+		if bytes.len() != 2 * field_size {
+			return Err(CryptoError::IncorrectInputLength(bytes.len()).into());
+		}
+		Ok(Self::read(bytes)?)
+	}
+}
+
 impl<F: PrimeField> ToBytes for Output<F> {
 	fn write<W: Write>(&self, mut writer: W) -> IoResult<()> {
 		writer.write(&to_bytes![self.leaf].unwrap())?;
@@ -53,19 +142,84 @@ impl<F: PrimeField> ToBytes for Output<F> {
 	}
 }
 
+impl<F: PrimeField> FromBytes for Output<F> {
+	fn read<R: Read>(mut reader: R) -> IoResult<Self> {
+		let leaf = F::read(&mut reader)?;
+		let nullifier_hash = F::read(&mut reader)?;
+		Ok(Self {
+			leaf,
+			nullifier_hash,
+		})
+	}
+}
+
+/// How the nullifier hash of a mixer leaf is computed, which has to match the
+/// contract checking it
+pub trait NullifierScheme<F: PrimeField, H: CRH> {
+	/// Public inputs of the leaf, which the nullifier hash may be bound to
+	type Public: Clone + Default;
+
+	fn nullifier_hash(s: &Private<F>, h: &H::Parameters) -> Result<H::Output, GadgetError>;
+}
+
+/// `H(nullifier, nullifier)`, the default scheme
+#[derive(Clone)]
+pub struct DoubleNullifier;
+
+impl<F: PrimeField, H: CRH> NullifierScheme<F, H> for DoubleNullifier {
+	type Public = ();
+
+	fn nullifier_hash(s: &Private<F>, h: &H::Parameters) -> Result<H::Output, GadgetError> {
+		let nullifier_bytes = to_bytes![s.nullifier, s.nullifier]?;
+		Ok(H::evaluate(h, &nullifier_bytes)?)
+	}
+}
+
+/// `H(nullifier, index)`, for contracts binding the nullifier hash to the
+/// index of the leaf, set with `Private::with_index`. In circuits the index
+/// is the one read from the membership path, not a witness.
 #[derive(Clone)]
-pub struct MixerLeaf<F: PrimeField, H: CRH> {
+pub struct IndexedNullifier;
+
+impl<F: PrimeField, H: CRH> NullifierScheme<F, H> for IndexedNullifier {
+	type Public = ();
+
+	fn nullifier_hash(s: &Private<F>, h: &H::Parameters) -> Result<H::Output, GadgetError> {
+		let nullifier_bytes = to_bytes![s.nullifier, s.index]?;
+		Ok(H::evaluate(h, &nullifier_bytes)?)
+	}
+}
+
+/// `H(nullifier, nullifier, salt)`, for a nullifier hash bound to the pool
+/// identified by the salt, set with `Private::with_salt`, so that it can't be
+/// replayed in another pool. The salt is the public input of the leaf: in
+/// circuits the nullifier hash is computed from it, not from the secrets.
+#[derive(Clone)]
+pub struct SaltedNullifier;
+
+impl<F: PrimeField, H: CRH> NullifierScheme<F, H> for SaltedNullifier {
+	type Public = F;
+
+	fn nullifier_hash(s: &Private<F>, h: &H::Parameters) -> Result<H::Output, GadgetError> {
+		let nullifier_bytes = to_bytes![s.nullifier, s.nullifier, s.salt]?;
+		Ok(H::evaluate(h, &nullifier_bytes)?)
+	}
+}
+
+#[derive(Clone)]
+pub struct MixerLeaf<F: PrimeField, H: CRH, N = DoubleNullifier> {
 	field: PhantomData<F>,
 	hasher: PhantomData<H>,
+	scheme: PhantomData<N>,
 }
 
-impl<F: PrimeField, H: CRH> LeafCreation<H> for MixerLeaf<F, H> {
+impl<F: PrimeField, H: CRH, N: NullifierScheme<F, H>> LeafCreation<H> for MixerLeaf<F, H, N> {
 	type Leaf = H::Output;
 	type Nullifier = H::Output;
 	type Private = Private<F>;
-	type Public = ();
+	type Public = N::Public;
 
-	fn generate_secrets<R: Rng>(r: &mut R) -> Result<Self::Private, Error> {
+	fn generate_secrets<R: Rng>(r: &mut R) -> Result<Self::Private, GadgetError> {
 		Ok(Self::Private::generate(r))
 	}
 
@@ -73,14 +227,40 @@ impl<F: PrimeField, H: CRH> LeafCreation<H> for MixerLeaf<F, H> {
 		s: &Self::Private,
 		_: &Self::Public,
 		h: &H::Parameters,
-	) -> Result<Self::Leaf, Error> {
+	) -> Result<Self::Leaf, GadgetError> {
+		// Field elements are written with their full, fixed size encoding, so
+		// the boundaries between the secrets don't depend on their magnitude
 		let input_bytes = to_bytes![s.r, s.nullifier, s.rho]?;
-		H::evaluate(h, &input_bytes)
+		Ok(H::evaluate(h, &input_bytes)?)
 	}
 
-	fn create_nullifier(s: &Self::Private, h: &H::Parameters) -> Result<Self::Nullifier, Error> {
-		let nullifier_bytes = to_bytes![s.nullifier, s.nullifier]?;
-		H::evaluate(h, &nullifier_bytes)
+	fn create_nullifier(
+		s: &Self::Private,
+		h: &H::Parameters,
+	) -> Result<Self::Nullifier, GadgetError> {
+		N::nullifier_hash(s, h)
+	}
+}
+
+impl<F: PrimeField, H: CRH, N: NullifierScheme<F, H>> MixerLeaf<F, H, N> {
+	/// Checks that `s` opens to `expected_leaf`
+	pub fn verify_leaf(
+		s: &Private<F>,
+		h: &H::Parameters,
+		expected_leaf: &H::Output,
+	) -> Result<bool, GadgetError> {
+		let leaf = <Self as LeafCreation<H>>::create_leaf(s, &N::Public::default(), h)?;
+		Ok(&leaf == expected_leaf)
+	}
+
+	/// Checks that the nullifier of `s` hashes to `expected_nullifier`
+	pub fn verify_nullifier(
+		s: &Private<F>,
+		h: &H::Parameters,
+		expected_nullifier: &H::Output,
+	) -> Result<bool, GadgetError> {
+		let nullifier_hash = <Self as LeafCreation<H>>::create_nullifier(s, h)?;
+		Ok(&nullifier_hash == expected_nullifier)
 	}
 }
 
@@ -94,7 +274,7 @@ mod test {
 	};
 	use ark_bls12_381::Fq;
 	use ark_crypto_primitives::crh::CRH as CRHTrait;
-	use ark_std::test_rng;
+	use ark_std::{str::FromStr, test_rng, UniformRand};
 
 	#[derive(Default, Clone)]
 	struct PoseidonRounds5;
@@ -129,4 +309,203 @@ mod test {
 		assert_eq!(leaf_res, leaf);
 		assert_eq!(nullifier_res, nullifier_hash);
 	}
+
+	#[test]
+	fn should_verify_leaf_and_nullifier() {
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_5::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_5::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+
+		let secrets = Leaf::generate_secrets(rng).unwrap();
+		let leaf = Leaf::create_leaf(&secrets, &(), &params).unwrap();
+		let nullifier_hash = Leaf::create_nullifier(&secrets, &params).unwrap();
+		assert!(Leaf::verify_leaf(&secrets, &params, &leaf).unwrap());
+		assert!(Leaf::verify_nullifier(&secrets, &params, &nullifier_hash).unwrap());
+
+		let other_secrets = Leaf::generate_secrets(rng).unwrap();
+		assert!(!Leaf::verify_leaf(&other_secrets, &params, &leaf).unwrap());
+		assert!(!Leaf::verify_nullifier(&other_secrets, &params, &nullifier_hash).unwrap());
+		// The leaf is not the nullifier hash
+		assert!(!Leaf::verify_leaf(&secrets, &params, &nullifier_hash).unwrap());
+	}
+
+	#[test]
+	fn should_not_collide_across_secrets() {
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_5::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_5::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+
+		// Small and large secrets take the same number of bytes
+		let elt_size = to_bytes![Fq::from(0u64)].unwrap().len();
+		let small = to_bytes![Fq::from(1u64), Fq::from(2u64), Fq::from(3u64)].unwrap();
+		let large = to_bytes![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)].unwrap();
+		assert_eq!(small.len(), 3 * elt_size);
+		assert_eq!(large.len(), 3 * elt_size);
+
+		let mut hashes = Vec::new();
+		for i in 0..200u64 {
+			// Mix small magnitude secrets in, they have the most leading zeros
+			let secrets = if i % 4 == 0 {
+				Private {
+					r: Fq::from(i),
+					nullifier: Fq::from(i + 1),
+					rho: Fq::from(i + 2),
+					index: Fq::from(0u64),
+					salt: Fq::from(0u64),
+				}
+			} else {
+				Leaf::generate_secrets(rng).unwrap()
+			};
+			let leaf = Leaf::create_leaf(&secrets, &(), &params).unwrap();
+			let nullifier_hash = Leaf::create_nullifier(&secrets, &params).unwrap();
+			assert_ne!(leaf, nullifier_hash);
+
+			assert!(!hashes.contains(&leaf));
+			hashes.push(leaf);
+			assert!(!hashes.contains(&nullifier_hash));
+			hashes.push(nullifier_hash);
+		}
+	}
+
+	#[test]
+	fn should_bind_nullifier_hash_to_index() {
+		type IndexedLeaf = MixerLeaf<Fq, PoseidonCRH5, IndexedNullifier>;
+
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_5::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_5::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+
+		let secrets = IndexedLeaf::generate_secrets(rng).unwrap().with_index(3);
+		let nullifier_inputs = to_bytes![secrets.nullifier, Fq::from(3u64)].unwrap();
+		let nullifier_hash = IndexedLeaf::create_nullifier(&secrets, &params).unwrap();
+		assert_eq!(
+			nullifier_hash,
+			PoseidonCRH5::evaluate(&params, &nullifier_inputs).unwrap()
+		);
+
+		// The leaf doesn't depend on the scheme, the nullifier hash does
+		assert_eq!(
+			IndexedLeaf::create_leaf(&secrets, &(), &params).unwrap(),
+			Leaf::create_leaf(&secrets, &(), &params).unwrap()
+		);
+		assert_ne!(
+			nullifier_hash,
+			Leaf::create_nullifier(&secrets, &params).unwrap()
+		);
+
+		// Another index gives another nullifier hash
+		let moved = secrets.clone().with_index(4);
+		assert_ne!(
+			nullifier_hash,
+			IndexedLeaf::create_nullifier(&moved, &params).unwrap()
+		);
+	}
+
+	#[test]
+	fn should_separate_salted_nullifiers() {
+		type SaltedLeaf = MixerLeaf<Fq, PoseidonCRH5, SaltedNullifier>;
+
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_5::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_5::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+
+		let salt = Fq::from(1u64);
+		let secrets = SaltedLeaf::generate_secrets(rng).unwrap().with_salt(salt);
+		let nullifier_hash = SaltedLeaf::create_nullifier(&secrets, &params).unwrap();
+		let nullifier_inputs = to_bytes![secrets.nullifier, secrets.nullifier, salt].unwrap();
+		assert_eq!(
+			nullifier_hash,
+			PoseidonCRH5::evaluate(&params, &nullifier_inputs).unwrap()
+		);
+
+		// The salt is public, the leaf doesn't depend on it
+		assert_eq!(
+			SaltedLeaf::create_leaf(&secrets, &salt, &params).unwrap(),
+			Leaf::create_leaf(&secrets, &(), &params).unwrap()
+		);
+
+		// Another pool gives another nullifier hash, as does the unsalted one
+		let other_pool = secrets.clone().with_salt(Fq::from(2u64));
+		assert_ne!(
+			nullifier_hash,
+			SaltedLeaf::create_nullifier(&other_pool, &params).unwrap()
+		);
+		assert_ne!(
+			nullifier_hash,
+			Leaf::create_nullifier(&secrets, &params).unwrap()
+		);
+	}
+
+	#[cfg(feature = "zeroize")]
+	#[test]
+	fn should_zeroize_secrets() {
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_5::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_5::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+
+		let secrets = Leaf::generate_secrets(rng).unwrap();
+		let mut copy = secrets.clone();
+		let leaf = Leaf::create_leaf(&secrets, &(), &params).unwrap();
+		assert_eq!(Leaf::create_leaf(&copy, &(), &params).unwrap(), leaf);
+
+		copy.zeroize();
+		let zero = Fq::from(0u64);
+		assert_eq!((copy.r(), copy.nullifier(), copy.rho()), (zero, zero, zero));
+		// Wiping the copy leaves the original untouched
+		assert!(Leaf::verify_leaf(&secrets, &params, &leaf).unwrap());
+	}
+
+	#[test]
+	fn should_read_output_from_bytes() {
+		let rng = &mut test_rng();
+		let output = Output {
+			leaf: Fq::rand(rng),
+			nullifier_hash: Fq::rand(rng),
+		};
+
+		let bytes = to_bytes![output].unwrap();
+		let parsed = Output::<Fq>::from_bytes(&bytes).unwrap();
+		assert_eq!(parsed, output);
+		assert_eq!(to_bytes![parsed].unwrap(), bytes);
+
+		// Truncated input
+		assert!(Output::<Fq>::from_bytes(&bytes[1..]).is_err());
+
+		// The leaf isn't a canonical field element
+		let mut non_canonical = bytes.clone();
+		non_canonical[..bytes.len() / 2]
+			.iter_mut()
+			.for_each(|b| *b = 0xff);
+		assert!(Output::<Fq>::from_bytes(&non_canonical).is_err());
+	}
+
+	#[test]
+	fn should_derive_secrets_from_seed() {
+		let secrets = Private::<Fq>::from_seed(b"seed");
+		let same_secrets = Private::<Fq>::from_seed(b"seed");
+		assert_eq!(secrets.r(), same_secrets.r());
+		assert_eq!(secrets.nullifier(), same_secrets.nullifier());
+		assert_eq!(secrets.rho(), same_secrets.rho());
+
+		// Pinned so that a change of the derivation is noticed
+		let expected_r = Fq::from_str(
+			"277090512348808575438370710117108987096792191524113209972232588859733747609118111118520197556669284227423814827081",
+		)
+		.unwrap();
+		assert_eq!(secrets.r(), expected_r);
+
+		// The three secrets are independent
+		assert_ne!(secrets.r(), secrets.nullifier());
+		assert_ne!(secrets.nullifier(), secrets.rho());
+
+		let other_secrets = Private::<Fq>::from_seed(b"other seed");
+		assert_ne!(secrets.r(), other_secrets.r());
+		assert_ne!(secrets.nullifier(), other_secrets.nullifier());
+		assert_ne!(secrets.rho(), other_secrets.rho());
+	}
 }
@@ -1,4 +1,5 @@
-use ark_crypto_primitives::{crh::CRH, Error};
+use crate::error::GadgetError;
+use ark_crypto_primitives::crh::CRH;
 use ark_ff::bytes::ToBytes;
 use ark_std::{hash::Hash, rand::Rng};
 
@@ -11,17 +12,32 @@ pub mod constraints;
 #[cfg(feature = "r1cs")]
 pub use constraints::*;
 
+/// Native leaves and nullifiers, whose failures are typed so that callers can
+/// match on them, e.g. on `GadgetError::InputTooLong` for a hasher too narrow
+/// for the secrets.
 pub trait LeafCreation<H: CRH>: Sized {
 	type Leaf: ToBytes + Clone + Eq + core::fmt::Debug + Hash + Default;
 	type Nullifier: ToBytes + Clone + Eq + core::fmt::Debug + Hash + Default;
 	type Private: Clone + Default;
 	type Public: Clone + Default;
 
-	fn generate_secrets<R: Rng>(r: &mut R) -> Result<Self::Private, Error>;
+	fn generate_secrets<R: Rng>(r: &mut R) -> Result<Self::Private, GadgetError>;
 	fn create_leaf(
 		s: &Self::Private,
 		p: &Self::Public,
 		h: &H::Parameters,
-	) -> Result<Self::Leaf, Error>;
-	fn create_nullifier(s: &Self::Private, h: &H::Parameters) -> Result<Self::Nullifier, Error>;
+	) -> Result<Self::Leaf, GadgetError>;
+	fn create_nullifier(
+		s: &Self::Private,
+		h: &H::Parameters,
+	) -> Result<Self::Nullifier, GadgetError>;
+}
+
+/// Overwrites a secret field element with zero, whose representation has all
+/// limbs zero. The write is volatile so that it isn't optimized away when the
+/// secret is about to be dropped.
+#[cfg(feature = "zeroize")]
+pub(crate) fn zeroize_field<F: ark_ff::PrimeField>(secret: &mut F) {
+	unsafe { core::ptr::write_volatile(secret, F::zero()) };
+	core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
 }
@@ -7,16 +7,35 @@ use ark_std::boxed::Box;
 pub(crate) use ark_std::vec::Vec;
 
 pub mod arbitrary;
+pub mod byte_hash;
 #[cfg(feature = "r1cs")]
 pub mod circuit;
+pub mod commitment;
+pub mod error;
+pub mod field_hasher;
+#[cfg(feature = "r1cs")]
+pub mod gadget_utils;
 pub mod identity;
+pub mod io;
 pub mod leaf;
 pub mod merkle_tree;
 pub mod mimc;
+pub mod pedersen;
 pub mod poseidon;
+#[cfg(feature = "r1cs")]
+pub mod prover;
+pub mod range;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod reference;
+pub mod rescue;
 pub mod set;
 #[cfg(feature = "r1cs")]
 pub mod setup;
+pub mod signature;
+#[cfg(all(feature = "r1cs", feature = "stats"))]
+pub mod stats;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_vectors;
 pub mod utils;
 
 pub type Error = Box<dyn ark_std::error::Error>;
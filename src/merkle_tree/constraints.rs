@@ -1,12 +1,13 @@
-use super::{Config, Node, Path};
-use crate::Vec;
+use super::{Config, Node, Path, Rc};
+use crate::{gadget_utils::enforce_one_of_two, range::enforce_bit_length, Vec};
 use ark_crypto_primitives::CRHGadget;
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
-	alloc::AllocVar, eq::EqGadget, prelude::*, select::CondSelectGadget, ToBytesGadget,
+	alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, prelude::*, select::CondSelectGadget,
+	ToBytesGadget,
 };
 use ark_relations::r1cs::{Namespace, SynthesisError};
-use ark_std::{borrow::Borrow, rc::Rc};
+use ark_std::{borrow::Borrow, marker::PhantomData};
 
 #[derive(Debug)]
 pub enum NodeVar<F, P, HG, LHG>
@@ -140,31 +141,81 @@ where
 		root: &NodeVar<F, P, HG, LHG>,
 		leaf: L,
 	) -> Result<Boolean<F>, SynthesisError> {
-		assert_eq!(self.path.len(), P::HEIGHT as usize);
+		let computed_root = self.calculate_root(&leaf, None)?;
+		root.is_eq(&computed_root)
+	}
+
+	/// Hashes `leaf` up the path. With `index_bits`, the little-endian bits of
+	/// the leaf index as returned by `index_to_path_bits`, each bit selects on
+	/// which side of its level the previous hash sits. Without them the side
+	/// is derived by comparing the previous hash with the left node.
+	///
+	/// Fails with `SynthesisError::Unsatisfiable` if the path or the bits
+	/// don't have `P::HEIGHT` levels.
+	pub fn calculate_root<L: ToBytesGadget<F>>(
+		&self,
+		leaf: &L,
+		index_bits: Option<&[Boolean<F>]>,
+	) -> Result<NodeVar<F, P, HG, LHG>, SynthesisError> {
+		if self.path.len() != P::HEIGHT as usize {
+			return Err(SynthesisError::Unsatisfiable);
+		}
+		if let Some(bits) = index_bits {
+			if bits.len() != self.path.len() {
+				return Err(SynthesisError::Unsatisfiable);
+			}
+		}
 		// Check that the hash of the given leaf matches the leaf hash in the membership
 		// proof.
-		let leaf_hash = hash_leaf_gadget::<F, P, HG, LHG, L>(self.leaf_params.borrow(), &leaf)?;
-
-		// Check if leaf is one of the bottom-most siblings.
-		let leaf_is_left = leaf_hash.is_eq(&self.path[0].0)?;
-
-		leaf_hash.enforce_equal(&NodeVar::conditionally_select(
-			&leaf_is_left,
-			&self.path[0].0,
-			&self.path[0].1,
-		)?)?;
+		let leaf_hash = hash_leaf_gadget::<F, P, HG, LHG, L>(self.leaf_params.borrow(), leaf)?;
 
 		// Check levels between leaf level and root.
 		let mut previous_hash = leaf_hash;
-		for &(ref left_hash, ref right_hash) in self.path.iter() {
+		for (i, &(ref left_hash, ref right_hash)) in self.path.iter().enumerate() {
 			// Check if the previous_hash matches the correct current hash.
-			let previous_is_left = previous_hash.is_eq(&left_hash)?;
+			match index_bits {
+				Some(bits) => previous_hash.enforce_equal(&NodeVar::conditionally_select(
+					&bits[i], right_hash, left_hash,
+				)?)?,
+				None => {
+					enforce_one_of_two(&previous_hash, left_hash, right_hash)?;
+				}
+			}
 
-			previous_hash.enforce_equal(&NodeVar::conditionally_select(
-				&previous_is_left,
+			previous_hash = hash_inner_node_gadget::<F, P, HG, LHG>(
+				self.inner_params.borrow(),
 				left_hash,
 				right_hash,
-			)?)?;
+			)?;
+		}
+
+		Ok(previous_hash)
+	}
+
+	/// Hashes `leaf` up the path like `calculate_root` without index bits,
+	/// also returning the index of the leaf, read from the side of the
+	/// previous hash at each level. The sides are the booleans the path
+	/// already enforces, so the index costs no extra constraint. A level
+	/// whose nodes are equal reads as a left child.
+	///
+	/// Fails with `SynthesisError::Unsatisfiable` if the path doesn't have
+	/// `P::HEIGHT` levels.
+	pub fn root_and_index<L: ToBytesGadget<F>>(
+		&self,
+		leaf: &L,
+	) -> Result<(NodeVar<F, P, HG, LHG>, FpVar<F>), SynthesisError> {
+		if self.path.len() != P::HEIGHT as usize {
+			return Err(SynthesisError::Unsatisfiable);
+		}
+
+		let mut previous_hash =
+			hash_leaf_gadget::<F, P, HG, LHG, L>(self.leaf_params.borrow(), leaf)?;
+		let mut index = FpVar::zero();
+		let mut power = F::one();
+		for (left_hash, right_hash) in self.path.iter() {
+			let is_left = enforce_one_of_two(&previous_hash, left_hash, right_hash)?;
+			index += FpVar::from(is_left.not()) * power;
+			power.double_in_place();
 
 			previous_hash = hash_inner_node_gadget::<F, P, HG, LHG>(
 				self.inner_params.borrow(),
@@ -173,10 +224,57 @@ where
 			)?;
 		}
 
-		root.is_eq(&previous_hash)
+		Ok((previous_hash, index))
+	}
+
+	/// Enforces that `leaf` is in the tree of `root` without revealing its
+	/// index. `check_membership` derives the side of the path at each level
+	/// from the assigned hashes, so neither the index nor its bits become
+	/// public inputs.
+	pub fn enforce_membership_hidden<L: ToBytesGadget<F>>(
+		&self,
+		root: &NodeVar<F, P, HG, LHG>,
+		leaf: &L,
+	) -> Result<(), SynthesisError> {
+		self.check_membership(root, leaf)?.enforce_equal(&Boolean::TRUE)
 	}
 }
 
+/// Enforces that `leaf` is in both the tree of `root_a` and the tree of
+/// `root_b`, e.g. a tree of the source chain and one of the destination
+/// chain. The checks are independent, but building both paths with
+/// `PathVar::new_with_params` from the same parameters allocates the
+/// hasher parameters once for the two of them.
+pub fn enforce_membership_two<F, P, HG, LHG, L>(
+	leaf: &L,
+	path_a: &PathVar<F, P, HG, LHG>,
+	root_a: &NodeVar<F, P, HG, LHG>,
+	path_b: &PathVar<F, P, HG, LHG>,
+	root_b: &NodeVar<F, P, HG, LHG>,
+) -> Result<(), SynthesisError>
+where
+	F: PrimeField,
+	P: Config,
+	L: ToBytesGadget<F>,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	path_a.calculate_root(leaf, None)?.enforce_equal(root_a)?;
+	path_b.calculate_root(leaf, None)?.enforce_equal(root_b)
+}
+
+/// Decomposes a leaf index into the `height` little-endian bits giving its
+/// side at each level, bit `i` being set when the node at level `i` is a
+/// right child. Enforces that the bits recompose to `index`, so an index of
+/// `2^height` or more makes the system unsatisfiable. A constant index gives
+/// constant bits, see `range::enforce_bit_length`.
+pub fn index_to_path_bits<F: PrimeField>(
+	index: &FpVar<F>,
+	height: usize,
+) -> Result<Vec<Boolean<F>>, SynthesisError> {
+	enforce_bit_length(index, height)
+}
+
 pub(crate) fn hash_leaf_gadget<F, P, HG, LHG, L>(
 	leaf_params: &LHG::ParametersVar,
 	leaf: &L,
@@ -212,62 +310,171 @@ where
 	Ok(NodeVar::Inner(res))
 }
 
-impl<F, P, HG, LHG> AllocVar<Path<P>, F> for PathVar<F, P, HG, LHG>
+impl<F, P, HG, LHG> PathVar<F, P, HG, LHG>
 where
 	F: PrimeField,
 	P: Config,
 	HG: CRHGadget<P::H, F>,
 	LHG: CRHGadget<P::LeafH, F>,
 {
-	fn new_variable<T: Borrow<Path<P>>>(
+	/// Allocates the nodes of `path`, hashing with already allocated
+	/// parameters instead of allocating them from the path. This lets a
+	/// circuit share one parameters allocation between its paths and its
+	/// other hashes. Fails with `SynthesisError::Unsatisfiable` unless the
+	/// path has `P::HEIGHT` levels, so that a path of another tree can't be
+	/// proven against the circuit's root.
+	pub fn new_with_params(
 		cs: impl Into<Namespace<F>>,
-		f: impl FnOnce() -> Result<T, SynthesisError>,
+		path: &Path<P>,
 		mode: AllocationMode,
+		inner_params: Rc<HG::ParametersVar>,
+		leaf_params: Rc<LHG::ParametersVar>,
 	) -> Result<Self, SynthesisError> {
+		if path.path.len() != P::HEIGHT as usize {
+			return Err(SynthesisError::Unsatisfiable);
+		}
+
 		let ns = cs.into();
 		let cs = ns.cs();
 
-		let mut path = Vec::new();
-		let path_obj = f()?;
-		for &(ref l, ref r) in &path_obj.borrow().path {
+		let mut path_var = Vec::new();
+		for &(ref l, ref r) in &path.path {
 			let l_hash =
 				NodeVar::new_variable(ark_relations::ns!(cs, "l_child"), || Ok(l.clone()), mode)?;
 			let r_hash =
 				NodeVar::new_variable(ark_relations::ns!(cs, "r_child"), || Ok(r.clone()), mode)?;
-			path.push((l_hash, r_hash));
+			path_var.push((l_hash, r_hash));
 		}
 
-		let inner_params_var = HG::ParametersVar::new_input(cs.clone(), || {
-			Ok(path_obj.borrow().inner_params.borrow())
-		})?;
+		Ok(PathVar {
+			path: path_var,
+			inner_params,
+			leaf_params,
+		})
+	}
+}
+
+/// Gadget computing the root of a full tree from all of its `2^P::HEIGHT`
+/// leaves, for proving knowledge of a whole leaf set, such as a committee,
+/// behind a public root. Leaves and inner nodes are hashed as in
+/// `SparseMerkleTree`, so the root matches the native one.
+pub struct MerkleRootGadget<F, P, HG, LHG>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	field: PhantomData<F>,
+	config: PhantomData<P>,
+	inner: PhantomData<HG>,
+	leaf: PhantomData<LHG>,
+}
+
+impl<F, P, HG, LHG> MerkleRootGadget<F, P, HG, LHG>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	/// Hashes `leaves` and compresses them pairwise up to the root, failing
+	/// with `SynthesisError::Unsatisfiable` unless there are exactly
+	/// `2^P::HEIGHT` of them
+	pub fn calculate_root(
+		inner_params: &HG::ParametersVar,
+		leaf_params: &LHG::ParametersVar,
+		leaves: &[FpVar<F>],
+	) -> Result<NodeVar<F, P, HG, LHG>, SynthesisError> {
+		if leaves.len() != 1 << P::HEIGHT {
+			return Err(SynthesisError::Unsatisfiable);
+		}
+
+		let mut level = leaves
+			.iter()
+			.map(|leaf| hash_leaf_gadget::<F, P, HG, LHG, _>(leaf_params, leaf))
+			.collect::<Result<Vec<_>, _>>()?;
+		while level.len() > 1 {
+			level = level
+				.chunks(2)
+				.map(|pair| hash_inner_node_gadget(inner_params, &pair[0], &pair[1]))
+				.collect::<Result<Vec<_>, _>>()?;
+		}
+
+		Ok(level.remove(0))
+	}
+
+	/// Enforces that `leaves` are the leaves of the tree with root `root`
+	pub fn enforce_root(
+		inner_params: &HG::ParametersVar,
+		leaf_params: &LHG::ParametersVar,
+		leaves: &[FpVar<F>],
+		root: &NodeVar<F, P, HG, LHG>,
+	) -> Result<(), SynthesisError> {
+		Self::calculate_root(inner_params, leaf_params, leaves)?.enforce_equal(root)
+	}
+}
+
+impl<F, P, HG, LHG> AllocVar<Path<P>, F> for PathVar<F, P, HG, LHG>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	fn new_variable<T: Borrow<Path<P>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
+
+		let path_obj = f()?;
+		let path = path_obj.borrow();
+		let inner_params_var =
+			HG::ParametersVar::new_input(cs.clone(), || Ok(path.inner_params.borrow()))?;
 		let leaf_params_var =
-			LHG::ParametersVar::new_input(cs, || Ok(path_obj.borrow().leaf_params.borrow()))?;
+			LHG::ParametersVar::new_input(cs.clone(), || Ok(path.leaf_params.borrow()))?;
 
-		Ok(PathVar {
+		Self::new_with_params(
+			cs,
 			path,
-			inner_params: Rc::new(inner_params_var),
-			leaf_params: Rc::new(leaf_params_var),
-		})
+			mode,
+			Rc::new(inner_params_var),
+			Rc::new(leaf_params_var),
+		)
 	}
 }
 
 #[cfg(feature = "default_poseidon")]
 #[cfg(test)]
 mod test {
-	use super::{NodeVar, PathVar};
+	use super::{
+		enforce_membership_two, hash_inner_node_gadget, index_to_path_bits, MerkleRootGadget,
+		NodeVar, PathVar,
+	};
 	use crate::{
 		ark_std::UniformRand,
-		merkle_tree::{Config, SparseMerkleTree},
+		gadget_utils::enforce_one_of_two,
+		merkle_tree::{Config, Node, Path, Rc, SparseMerkleTree},
 		poseidon::{
-			constraints::CRHGadget as PoseidonCRHGadget, sbox::PoseidonSbox, PoseidonParameters,
-			Rounds, CRH as PoseidonCRH,
+			constraints::{CRHGadget as PoseidonCRHGadget, PoseidonParametersVar},
+			sbox::PoseidonSbox,
+			PoseidonParameters, Rounds, CRH as PoseidonCRH,
 		},
 		utils::{get_mds_poseidon_bls381_x5_3, get_rounds_poseidon_bls381_x5_3},
 	};
 	use ark_bls12_381::Fq;
-	use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
-	use ark_relations::r1cs::ConstraintSystem;
-	use ark_std::{rc::Rc, test_rng};
+	use ark_crypto_primitives::crh::CRHGadget;
+	use ark_r1cs_std::{
+		alloc::{AllocVar, AllocationMode},
+		eq::EqGadget,
+		fields::fp::FpVar,
+		R1CSVar, ToBytesGadget,
+	};
+	use ark_relations::r1cs::{ConstraintSystem, SynthesisError};
+	use ark_std::test_rng;
 
 	type FieldVar = FpVar<Fq>;
 
@@ -319,4 +526,363 @@ mod test {
 		let res = path_var.check_membership(&root_var, &leaf_var).unwrap();
 		assert!(res.value().unwrap());
 	}
+
+	#[test]
+	fn should_calculate_root_from_index_bits() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params3 = PoseidonParameters::<Fq>::new(rounds3, mds3);
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SMT::new_sequential(inner_params, leaf_params, &leaves).unwrap();
+		let root = smt.root();
+		let path = smt.generate_membership_proof(3);
+
+		let path_var = PathVar::new_witness(cs.clone(), || Ok(path)).unwrap();
+		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
+		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[3])).unwrap();
+		let index_var = FieldVar::new_witness(cs.clone(), || Ok(Fq::from(3u64))).unwrap();
+
+		let bits = index_to_path_bits(&index_var, SMTConfig::HEIGHT as usize).unwrap();
+		let bit_values: Vec<bool> = bits.iter().map(|b| b.value().unwrap()).collect();
+		assert_eq!(bit_values, vec![true, true, false]);
+
+		let computed_root = path_var.calculate_root(&leaf_var, Some(&bits)).unwrap();
+		assert!(computed_root.is_eq(&root_var).unwrap().value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+
+		// The path of leaf 3 doesn't match the bits of index 2
+		let wrong_index_var = FieldVar::new_witness(cs.clone(), || Ok(Fq::from(2u64))).unwrap();
+		let wrong_bits = index_to_path_bits(&wrong_index_var, SMTConfig::HEIGHT as usize).unwrap();
+		path_var
+			.calculate_root(&leaf_var, Some(&wrong_bits))
+			.unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_decompose_constant_index() {
+		let index_var = FieldVar::Constant(Fq::from(6u64));
+		let bits = index_to_path_bits(&index_var, SMTConfig::HEIGHT as usize).unwrap();
+		assert!(bits.iter().all(|b| b.is_constant()));
+		let bit_values: Vec<bool> = bits.iter().map(|b| b.value().unwrap()).collect();
+		assert_eq!(bit_values, vec![false, true, true]);
+	}
+
+	#[test]
+	fn should_read_index_from_path() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = Rc::new(PoseidonParameters::<Fq>::new(rounds3, mds3));
+
+		let leaves: Vec<Fq> = (0..6).map(|_| Fq::rand(rng)).collect();
+		let smt = SMT::new_sequential(params.clone(), params, &leaves).unwrap();
+		let root = smt.root();
+
+		for &index in [0u64, 5].iter() {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let path = smt.generate_membership_proof(index);
+			let path_var = PathVar::new_witness(cs.clone(), || Ok(path)).unwrap();
+			let root_var = SMTNode::new_input(cs.clone(), || Ok(root.clone())).unwrap();
+			let leaf_var =
+				FieldVar::new_witness(cs.clone(), || Ok(leaves[index as usize])).unwrap();
+
+			let before = cs.num_constraints();
+			let (computed_root, index_var) = path_var.root_and_index(&leaf_var).unwrap();
+			let index_constraints = cs.num_constraints() - before;
+			assert_eq!(index_var.value().unwrap(), Fq::from(index));
+			assert!(computed_root.is_eq(&root_var).unwrap().value().unwrap());
+			assert!(cs.is_satisfied().unwrap());
+
+			// Reading the index adds nothing to the path checks
+			let before = cs.num_constraints();
+			path_var.calculate_root(&leaf_var, None).unwrap();
+			assert_eq!(cs.num_constraints() - before, index_constraints);
+		}
+	}
+
+	#[test]
+	fn should_enforce_membership_with_hidden_index() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = Rc::new(PoseidonParameters::<Fq>::new(rounds3, mds3));
+
+		let leaves: Vec<Fq> = (0..6).map(|_| Fq::rand(rng)).collect();
+		let smt = SMT::new_sequential(params.clone(), params, &leaves).unwrap();
+		let root = smt.root();
+		let path = smt.generate_membership_proof(5);
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let path_var = PathVar::new_witness(cs.clone(), || Ok(path)).unwrap();
+		let root_var = SMTNode::new_input(cs.clone(), || Ok(root.clone())).unwrap();
+		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[5])).unwrap();
+		path_var
+			.enforce_membership_hidden(&root_var, &leaf_var)
+			.unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		// The root is the only public input besides the constant one
+		let instance = cs.borrow().unwrap().instance_assignment.clone();
+		assert_eq!(instance, vec![Fq::from(1u64), root.inner()]);
+		assert!(!instance.contains(&Fq::from(5u64)));
+
+		let wrong_leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[4])).unwrap();
+		path_var
+			.enforce_membership_hidden(&root_var, &wrong_leaf_var)
+			.unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig2;
+	impl Config for SMTConfig2 {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 2;
+	}
+
+	type SMTRoot2 = MerkleRootGadget<Fq, SMTConfig2, SMTCRHGadget, SMTCRHGadget>;
+	type SMTNode2 = NodeVar<Fq, SMTConfig2, SMTCRHGadget, SMTCRHGadget>;
+
+	#[test]
+	fn should_calculate_root_from_all_leaves() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = Rc::new(PoseidonParameters::<Fq>::new(rounds3, mds3));
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt =
+			SparseMerkleTree::<SMTConfig2>::new_sequential(params.clone(), params.clone(), &leaves)
+				.unwrap();
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), params.as_ref()).unwrap();
+		let leaf_vars = leaves
+			.iter()
+			.map(|leaf| FieldVar::new_witness(cs.clone(), || Ok(*leaf)).unwrap())
+			.collect::<Vec<_>>();
+		let root_var = SMTNode2::new_input(cs.clone(), || Ok(smt.root())).unwrap();
+
+		let computed_root = SMTRoot2::calculate_root(&params_var, &params_var, &leaf_vars).unwrap();
+		assert!(computed_root.is_eq(&root_var).unwrap().value().unwrap());
+		SMTRoot2::enforce_root(&params_var, &params_var, &leaf_vars, &root_var).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		// Only complete leaf sets are accepted
+		assert!(SMTRoot2::calculate_root(&params_var, &params_var, &leaf_vars[..3]).is_err());
+
+		// Swapping two leaves changes the root
+		let swapped = vec![
+			leaf_vars[1].clone(),
+			leaf_vars[0].clone(),
+			leaf_vars[2].clone(),
+			leaf_vars[3].clone(),
+		];
+		SMTRoot2::enforce_root(&params_var, &params_var, &swapped, &root_var).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_reject_out_of_range_index() {
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let height = SMTConfig::HEIGHT as usize;
+
+		let index_var = FieldVar::new_witness(cs.clone(), || Ok(Fq::from(7u64))).unwrap();
+		index_to_path_bits(&index_var, height).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		let index_var = FieldVar::new_witness(cs.clone(), || Ok(Fq::from(8u64))).unwrap();
+		index_to_path_bits(&index_var, height).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig5;
+	impl Config for SMTConfig5 {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 5;
+	}
+
+	#[test]
+	fn should_reject_path_of_another_height() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = Rc::new(PoseidonParameters::<Fq>::new(rounds3, mds3));
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng)];
+		let smt = SparseMerkleTree::<SMTConfig5>::new_sequential(params.clone(), params, &leaves)
+			.unwrap();
+		let mut path = smt.generate_membership_proof(0);
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let path_var =
+			PathVar::<Fq, SMTConfig5, SMTCRHGadget, SMTCRHGadget>::new_witness(cs.clone(), || {
+				Ok(path.clone())
+			})
+			.unwrap();
+
+		// A path of height 4 is rejected instead of proving a shorter tree
+		path.path.pop();
+		let res =
+			PathVar::<Fq, SMTConfig5, SMTCRHGadget, SMTCRHGadget>::new_witness(cs.clone(), || {
+				Ok(path)
+			});
+		assert!(matches!(res, Err(SynthesisError::Unsatisfiable)));
+
+		// As are index bits of another height
+		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[0])).unwrap();
+		let index_var = FieldVar::new_witness(cs.clone(), || Ok(Fq::from(0u64))).unwrap();
+		let bits = index_to_path_bits(&index_var, 4).unwrap();
+		let res = path_var.calculate_root(&leaf_var, Some(&bits));
+		assert!(matches!(res, Err(SynthesisError::Unsatisfiable)));
+	}
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig10;
+	impl Config for SMTConfig10 {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 10;
+	}
+
+	type SMTNode10 = NodeVar<Fq, SMTConfig10, SMTCRHGadget, SMTCRHGadget>;
+	type SMTPath10 = PathVar<Fq, SMTConfig10, SMTCRHGadget, SMTCRHGadget>;
+
+	#[test]
+	fn should_share_params_along_the_path() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = Rc::new(PoseidonParameters::<Fq>::new(rounds3, mds3));
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SparseMerkleTree::<SMTConfig10>::new_sequential(
+			params.clone(),
+			params.clone(),
+			&leaves,
+		)
+		.unwrap();
+		let root = smt.root();
+		let path = smt.generate_membership_proof(2);
+
+		// Parameters allocated once per level
+		let per_level_cs = ConstraintSystem::<Fq>::new_ref();
+		let leaf_var = FieldVar::new_witness(per_level_cs.clone(), || Ok(leaves[2])).unwrap();
+		let mut previous = SMTNode10::Leaf(
+			SMTCRHGadget::evaluate(
+				&PoseidonParametersVar::new_constant(per_level_cs.clone(), params.as_ref())
+					.unwrap(),
+				&leaf_var.to_bytes().unwrap(),
+			)
+			.unwrap(),
+		);
+		for (left, right) in path.path.iter() {
+			let level_params =
+				PoseidonParametersVar::new_constant(per_level_cs.clone(), params.as_ref()).unwrap();
+			let left = SMTNode10::new_witness(per_level_cs.clone(), || Ok(left.clone())).unwrap();
+			let right = SMTNode10::new_witness(per_level_cs.clone(), || Ok(right.clone())).unwrap();
+			enforce_one_of_two(&previous, &left, &right).unwrap();
+			previous = hash_inner_node_gadget::<Fq, SMTConfig10, SMTCRHGadget, SMTCRHGadget>(
+				&level_params,
+				&left,
+				&right,
+			)
+			.unwrap();
+		}
+		let root_var = SMTNode10::new_witness(per_level_cs.clone(), || Ok(root.clone())).unwrap();
+		assert!(previous.is_eq(&root_var).unwrap().value().unwrap());
+
+		// Parameters allocated once for the whole path
+		let shared_cs = ConstraintSystem::<Fq>::new_ref();
+		let leaf_var = FieldVar::new_witness(shared_cs.clone(), || Ok(leaves[2])).unwrap();
+		let params_var = Rc::new(
+			PoseidonParametersVar::new_constant(shared_cs.clone(), params.as_ref()).unwrap(),
+		);
+		let path_var = SMTPath10::new_with_params(
+			shared_cs.clone(),
+			&path,
+			AllocationMode::Witness,
+			params_var.clone(),
+			params_var.clone(),
+		)
+		.unwrap();
+		let root_var = SMTNode10::new_witness(shared_cs.clone(), || Ok(root)).unwrap();
+		assert!(path_var
+			.check_membership(&root_var, &leaf_var)
+			.unwrap()
+			.value()
+			.unwrap());
+
+		// The parameters are constants, so sharing them saves the allocations
+		// without changing the circuit
+		assert_eq!(per_level_cs.num_constraints(), shared_cs.num_constraints());
+		assert_eq!(
+			per_level_cs.num_witness_variables(),
+			shared_cs.num_witness_variables()
+		);
+		assert!(Rc::ptr_eq(&path_var.inner_params, &params_var));
+		assert!(per_level_cs.is_satisfied().unwrap());
+		assert!(shared_cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_enforce_membership_in_two_trees() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = Rc::new(PoseidonParameters::<Fq>::new(rounds3, mds3));
+
+		// The leaf sits at index 0 of tree A and index 2 of tree B
+		let leaf = Fq::rand(rng);
+		let leaves_a = vec![leaf, Fq::rand(rng)];
+		let leaves_b = vec![Fq::rand(rng), Fq::rand(rng), leaf];
+		let smt_a = SMT::new_sequential(params.clone(), params.clone(), &leaves_a).unwrap();
+		let smt_b = SMT::new_sequential(params.clone(), params.clone(), &leaves_b).unwrap();
+		let path_a = smt_a.generate_membership_proof(0);
+		let path_b = smt_b.generate_membership_proof(2);
+
+		let verify = |leaf: Fq, root_b: Node<SMTConfig>| {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let params_var = Rc::new(
+				PoseidonParametersVar::new_constant(cs.clone(), params.as_ref()).unwrap(),
+			);
+			let new_path = |path: &Path<SMTConfig>| {
+				PathVar::<Fq, SMTConfig, SMTCRHGadget, SMTCRHGadget>::new_with_params(
+					cs.clone(),
+					path,
+					AllocationMode::Witness,
+					params_var.clone(),
+					params_var.clone(),
+				)
+				.unwrap()
+			};
+			let path_a_var = new_path(&path_a);
+			let path_b_var = new_path(&path_b);
+			assert!(Rc::ptr_eq(&path_a_var.inner_params, &path_b_var.inner_params));
+
+			let root_a_var = SMTNode::new_input(cs.clone(), || Ok(smt_a.root())).unwrap();
+			let root_b_var = SMTNode::new_input(cs.clone(), || Ok(root_b)).unwrap();
+			let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaf)).unwrap();
+			enforce_membership_two(&leaf_var, &path_a_var, &root_a_var, &path_b_var, &root_b_var)
+				.unwrap();
+			cs.is_satisfied().unwrap()
+		};
+
+		assert!(verify(leaf, smt_b.root()));
+		// Both trees must hold the leaf
+		assert!(!verify(leaf, smt_a.root()));
+		assert!(!verify(leaves_b[0], smt_b.root()));
+	}
 }
@@ -0,0 +1,213 @@
+use super::Path;
+use crate::{field_hasher::constraints::FieldHasherGadget, gadget_utils::enforce_one_of_two, Vec};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::{borrow::Borrow, convert::TryInto, marker::PhantomData};
+
+/// Gadget of a fixed height `Path`, usable with any `FieldHasherGadget`
+pub struct PathVar<F: PrimeField, HG: FieldHasherGadget<F>, const N: usize> {
+	pub path: [(FpVar<F>, FpVar<F>); N],
+	hasher: PhantomData<HG>,
+}
+
+impl<F: PrimeField, HG: FieldHasherGadget<F>, const N: usize> PathVar<F, HG, N> {
+	/// Hashes `leaf` up the path, enforcing that each hash is one of the pair
+	/// of nodes above it
+	pub fn calculate_root(&self, leaf: &FpVar<F>, hasher: &HG) -> Result<FpVar<F>, SynthesisError> {
+		let mut previous_hash = leaf.clone();
+		for (left_hash, right_hash) in self.path.iter() {
+			enforce_one_of_two(&previous_hash, left_hash, right_hash)?;
+
+			previous_hash = hasher.hash_two(left_hash, right_hash)?;
+		}
+
+		Ok(previous_hash)
+	}
+
+	/// Hashes `leaf` up the path like `calculate_root`, also returning the
+	/// index of the leaf, read from the side of the previous hash at each
+	/// level. The sides are the booleans `calculate_root` already enforces,
+	/// so the index costs no extra constraint. A level whose nodes are equal
+	/// reads as a left child.
+	pub fn root_and_index(
+		&self,
+		leaf: &FpVar<F>,
+		hasher: &HG,
+	) -> Result<(FpVar<F>, FpVar<F>), SynthesisError> {
+		let mut previous_hash = leaf.clone();
+		let mut index = FpVar::zero();
+		let mut power = F::one();
+		for (left_hash, right_hash) in self.path.iter() {
+			let is_left = enforce_one_of_two(&previous_hash, left_hash, right_hash)?;
+			index += FpVar::from(is_left.not()) * power;
+			power.double_in_place();
+
+			previous_hash = hasher.hash_two(left_hash, right_hash)?;
+		}
+
+		Ok((previous_hash, index))
+	}
+
+	pub fn check_membership(
+		&self,
+		root: &FpVar<F>,
+		leaf: &FpVar<F>,
+		hasher: &HG,
+	) -> Result<Boolean<F>, SynthesisError> {
+		let computed_root = self.calculate_root(leaf, hasher)?;
+		root.is_eq(&computed_root)
+	}
+}
+
+impl<F: PrimeField, HG: FieldHasherGadget<F>, const N: usize> AllocVar<Path<F, HG::Native, N>, F>
+	for PathVar<F, HG, N>
+{
+	fn new_variable<T: Borrow<Path<F, HG::Native, N>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
+
+		let path_obj = f()?;
+		let mut path = Vec::with_capacity(N);
+		for (l, r) in path_obj.borrow().path.iter() {
+			let l_var = FpVar::new_variable(ark_relations::ns!(cs, "l_child"), || Ok(*l), mode)?;
+			let r_var = FpVar::new_variable(ark_relations::ns!(cs, "r_child"), || Ok(*r), mode)?;
+			path.push((l_var, r_var));
+		}
+
+		Ok(Self {
+			path: path
+				.try_into()
+				.map_err(|_| SynthesisError::AssignmentMissing)?,
+			hasher: PhantomData,
+		})
+	}
+}
+
+#[cfg(all(feature = "default_poseidon", feature = "default_mimc"))]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::{
+			constraints::{MiMCHasherGadget, PoseidonHasherGadget},
+			FieldHasher, MiMCHasher, PoseidonHasher,
+		},
+		setup::common::{
+			setup_mimc_220, setup_params_x5_3, Curve, MiMCRounds_220_3, PoseidonRounds_x5_3,
+		},
+	};
+	use ark_bn254::Fr;
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{test_rng, UniformRand};
+
+	/// Builds a path of height 3 for a random leaf at index 5, returning the
+	/// leaf, the path and the root
+	fn random_path<H: FieldHasher<Fr>>(hasher: &H) -> (Fr, Path<Fr, H, 3>, Fr) {
+		let rng = &mut test_rng();
+		let leaf = Fr::rand(rng);
+		let mut path = [(Fr::rand(rng), Fr::rand(rng)); 3];
+		let mut node = leaf;
+		for (i, pair) in path.iter_mut().enumerate() {
+			let sibling = Fr::rand(rng);
+			*pair = if (5 >> i) & 1 == 1 {
+				(sibling, node)
+			} else {
+				(node, sibling)
+			};
+			node = hasher.hash_two(&pair.0, &pair.1).unwrap();
+		}
+		(leaf, Path::new(path), node)
+	}
+
+	fn verify_membership<HG: FieldHasherGadget<Fr>>(hasher: HG::Native) {
+		let (leaf, path, root) = random_path(&hasher);
+		assert!(path.check_membership(&root, &leaf, &hasher).unwrap());
+
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let hasher_var = HG::from_native(cs.clone(), hasher).unwrap();
+		let path_var = PathVar::<Fr, HG, 3>::new_witness(cs.clone(), || Ok(path)).unwrap();
+		let leaf_var = FpVar::new_witness(cs.clone(), || Ok(leaf)).unwrap();
+		let root_var = FpVar::new_input(cs.clone(), || Ok(root)).unwrap();
+
+		let is_member = path_var
+			.check_membership(&root_var, &leaf_var, &hasher_var)
+			.unwrap();
+		assert!(is_member.value().unwrap());
+		is_member.enforce_equal(&Boolean::TRUE).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_calculate_root_and_index_in_one_pass() {
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let hasher = PoseidonHasher::<Fr, PoseidonRounds_x5_3>::new(params);
+		let (leaf, path, root) = random_path(&hasher);
+
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let hasher_var =
+			PoseidonHasherGadget::<Fr, PoseidonRounds_x5_3>::from_native(cs.clone(), hasher)
+				.unwrap();
+		let path_var = PathVar::<Fr, _, 3>::new_witness(cs.clone(), || Ok(path)).unwrap();
+		let leaf_var = FpVar::new_witness(cs.clone(), || Ok(leaf)).unwrap();
+
+		let before = cs.num_constraints();
+		path_var.calculate_root(&leaf_var, &hasher_var).unwrap();
+		let root_constraints = cs.num_constraints() - before;
+
+		let before = cs.num_constraints();
+		let (root_var, index_var) = path_var.root_and_index(&leaf_var, &hasher_var).unwrap();
+		let combined_constraints = cs.num_constraints() - before;
+
+		assert_eq!(root_var.value().unwrap(), root);
+		assert_eq!(index_var.value().unwrap(), Fr::from(5u64));
+		assert!(cs.is_satisfied().unwrap());
+		// Hashing the path a second time to read the index would double the cost
+		assert_eq!(combined_constraints, root_constraints);
+	}
+
+	#[test]
+	fn should_verify_membership_with_poseidon() {
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		verify_membership::<PoseidonHasherGadget<Fr, PoseidonRounds_x5_3>>(PoseidonHasher::new(
+			params,
+		));
+	}
+
+	#[cfg(feature = "test-utils")]
+	#[test]
+	fn should_verify_membership_with_dummy_hasher() {
+		use crate::field_hasher::{constraints::DummyFieldHasherGadget, DummyFieldHasher};
+
+		verify_membership::<DummyFieldHasherGadget<Fr>>(DummyFieldHasher::default());
+
+		// The hashes are sums, only the path checks are constrained
+		let (leaf, path, root) = random_path(&DummyFieldHasher::default());
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let hasher =
+			DummyFieldHasherGadget::from_native(cs.clone(), DummyFieldHasher::default()).unwrap();
+		let path_var =
+			PathVar::<Fr, DummyFieldHasherGadget<Fr>, 3>::new_witness(cs.clone(), || Ok(path))
+				.unwrap();
+		let leaf_var = FpVar::new_witness(cs.clone(), || Ok(leaf)).unwrap();
+		let root_var = FpVar::new_input(cs.clone(), || Ok(root)).unwrap();
+		let before = cs.num_constraints();
+		path_var
+			.check_membership(&root_var, &leaf_var, &hasher)
+			.unwrap()
+			.enforce_equal(&Boolean::TRUE)
+			.unwrap();
+		assert!(cs.num_constraints() - before < 30);
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_verify_membership_with_mimc() {
+		let params = setup_mimc_220::<Fr>(Curve::Bn254);
+		verify_membership::<MiMCHasherGadget<Fr, MiMCRounds_220_3>>(MiMCHasher::new(params));
+	}
+}
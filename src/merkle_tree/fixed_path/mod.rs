@@ -0,0 +1,224 @@
+use super::{Config, Node, Path as DynamicPath};
+use crate::{error::GadgetError, field_hasher::FieldHasher};
+use ark_crypto_primitives::{Error, CRH};
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::{
+	convert::TryFrom,
+	io::{Read, Write},
+	marker::PhantomData,
+};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// Membership path of a tree of height `N`, made of the `(left, right)` node
+/// pairs from the leaf level up to the children of the root. The height is
+/// part of the type, so a path can't be checked against a tree of another
+/// height.
+pub struct Path<F: PrimeField, H: FieldHasher<F>, const N: usize> {
+	pub path: [(F, F); N],
+	hasher: PhantomData<H>,
+}
+
+impl<F: PrimeField, H: FieldHasher<F>, const N: usize> Clone for Path<F, H, N> {
+	fn clone(&self) -> Self {
+		Self::new(self.path)
+	}
+}
+
+impl<F: PrimeField, H: FieldHasher<F>, const N: usize> Path<F, H, N> {
+	pub fn new(path: [(F, F); N]) -> Self {
+		Self {
+			path,
+			hasher: PhantomData,
+		}
+	}
+
+	/// Hashes `leaf` up the path, failing if a node isn't one of the pair
+	/// below it. `leaf` is the value stored at the leaf level of the tree.
+	pub fn calculate_root(&self, leaf: &F, hasher: &H) -> Result<Option<F>, Error> {
+		let mut prev = *leaf;
+		for (left, right) in self.path.iter() {
+			if &prev != left && &prev != right {
+				return Ok(None);
+			}
+			prev = hasher.hash_two(left, right)?;
+		}
+
+		Ok(Some(prev))
+	}
+
+	pub fn check_membership(&self, root: &F, leaf: &F, hasher: &H) -> Result<bool, Error> {
+		let root_hash = self.calculate_root(leaf, hasher)?;
+		Ok(root_hash.as_ref() == Some(root))
+	}
+}
+
+/// Serializes the `N` node pairs from the leaf level up, each as the left
+/// node followed by the right node in the canonical compressed encoding of
+/// `F`. The height is known from the type so there is no length prefix, and
+/// there is no index either: the position at each level is the side of the
+/// pair matching the hash of the level below.
+impl<F: PrimeField, H: FieldHasher<F>, const N: usize> CanonicalSerialize for Path<F, H, N> {
+	fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+		for (left, right) in self.path.iter() {
+			left.serialize(&mut writer)?;
+			right.serialize(&mut writer)?;
+		}
+		Ok(())
+	}
+
+	fn serialized_size(&self) -> usize {
+		self.path
+			.iter()
+			.map(|(left, right)| left.serialized_size() + right.serialized_size())
+			.sum()
+	}
+}
+
+impl<F: PrimeField, H: FieldHasher<F>, const N: usize> CanonicalDeserialize for Path<F, H, N> {
+	fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+		let mut path = [(F::zero(), F::zero()); N];
+		for pair in path.iter_mut() {
+			let left = F::deserialize(&mut reader)?;
+			let right = F::deserialize(&mut reader)?;
+			*pair = (left, right);
+		}
+		Ok(Self::new(path))
+	}
+}
+
+/// Converts a path generated by `SparseMerkleTree`, failing if its height is
+/// not `N`
+impl<F, H, P, const N: usize> TryFrom<&DynamicPath<P>> for Path<F, H, N>
+where
+	F: PrimeField,
+	H: FieldHasher<F>,
+	P: Config,
+	P::H: CRH<Output = F>,
+	P::LeafH: CRH<Output = F>,
+{
+	type Error = GadgetError;
+
+	fn try_from(dynamic: &DynamicPath<P>) -> Result<Self, Self::Error> {
+		if dynamic.path.len() != N {
+			return Err(GadgetError::PathLengthMismatch {
+				expected: N,
+				actual: dynamic.path.len(),
+			});
+		}
+
+		let to_field = |node: &Node<P>| match node {
+			Node::Leaf(leaf) => *leaf,
+			Node::Inner(inner) => *inner,
+		};
+		let mut path = [(F::zero(), F::zero()); N];
+		for (pair, (left, right)) in path.iter_mut().zip(dynamic.path.iter()) {
+			*pair = (to_field(left), to_field(right));
+		}
+
+		Ok(Self::new(path))
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::PoseidonHasher,
+		identity::CRH as IdentityCRH,
+		merkle_tree::{Rc, SparseMerkleTree},
+		poseidon::CRH as PoseidonCRH,
+		setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3},
+	};
+	use ark_bn254::Fr;
+	use ark_ff::Zero;
+	use ark_std::{test_rng, UniformRand};
+
+	type Hasher = PoseidonHasher<Fr, PoseidonRounds_x5_3>;
+
+	#[derive(Clone, PartialEq)]
+	struct Config3;
+	impl Config for Config3 {
+		type H = PoseidonCRH<Fr, PoseidonRounds_x5_3>;
+		type LeafH = IdentityCRH<Fr>;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[derive(Clone, PartialEq)]
+	struct Config4;
+	impl Config for Config4 {
+		type H = PoseidonCRH<Fr, PoseidonRounds_x5_3>;
+		type LeafH = IdentityCRH<Fr>;
+
+		const HEIGHT: u8 = 4;
+	}
+
+	#[derive(Clone, PartialEq)]
+	struct Config5;
+	impl Config for Config5 {
+		type H = PoseidonCRH<Fr, PoseidonRounds_x5_3>;
+		type LeafH = IdentityCRH<Fr>;
+
+		const HEIGHT: u8 = 5;
+	}
+
+	#[test]
+	fn should_only_verify_against_tree_of_same_height() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let hasher = Hasher::new(params.clone());
+		let leaves = vec![Fr::rand(rng), Fr::rand(rng), Fr::rand(rng)];
+
+		let tree3 = SparseMerkleTree::<Config3>::new_sequential(
+			Rc::new(params.clone()),
+			Rc::new(()),
+			&leaves,
+		)
+		.unwrap();
+		let tree4 =
+			SparseMerkleTree::<Config4>::new_sequential(Rc::new(params), Rc::new(()), &leaves)
+				.unwrap();
+		let root3 = tree3.root().inner();
+		let root4 = tree4.root().inner();
+
+		let dynamic = tree3.generate_membership_proof(1);
+		let path = Path::<Fr, Hasher, 3>::try_from(&dynamic).unwrap();
+		assert!(path.check_membership(&root3, &leaves[1], &hasher).unwrap());
+		assert!(!path.check_membership(&root4, &leaves[1], &hasher).unwrap());
+
+		// The dynamic path has 3 levels, it can't become a height 4 path
+		assert!(Path::<Fr, Hasher, 4>::try_from(&dynamic).is_err());
+	}
+
+	#[test]
+	fn should_serialize_path_round_trip() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let hasher = Hasher::new(params.clone());
+		let leaves = (0..7).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+		let tree =
+			SparseMerkleTree::<Config5>::new_sequential(Rc::new(params), Rc::new(()), &leaves)
+				.unwrap();
+		let root = tree.root().inner();
+		let path = Path::<Fr, Hasher, 5>::try_from(&tree.generate_membership_proof(6)).unwrap();
+
+		let mut bytes = Vec::new();
+		path.serialize(&mut bytes).unwrap();
+		assert_eq!(bytes.len(), path.serialized_size());
+		assert_eq!(bytes.len(), 2 * 5 * Fr::zero().serialized_size());
+
+		let decoded = Path::<Fr, Hasher, 5>::deserialize(&bytes[..]).unwrap();
+		assert_eq!(decoded.path, path.path);
+		assert!(decoded
+			.check_membership(&root, &leaves[6], &hasher)
+			.unwrap());
+
+		// A truncated encoding is missing the last node
+		assert!(Path::<Fr, Hasher, 5>::deserialize(&bytes[..bytes.len() - 1]).is_err());
+	}
+}
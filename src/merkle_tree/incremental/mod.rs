@@ -0,0 +1,175 @@
+use crate::{error::GadgetError, field_hasher::FieldHasher, Vec};
+use ark_ff::PrimeField;
+
+/// Append-only Merkle tree of height `HEIGHT`, storing only the rightmost
+/// filled node of each level (the frontier) and the empty subtree hashes.
+/// Leaves are inserted from index 0 up, and each append rehashes a single
+/// path, so it takes `HEIGHT` hashes and `O(HEIGHT)` memory however many
+/// leaves the tree holds.
+///
+/// Roots match a `SparseMerkleTree` holding the same leaves sequentially,
+/// when its empty leaf is `default_leaf` and its inner nodes are hashed with
+/// `H::hash_two`.
+pub struct IncrementalMerkleTree<F: PrimeField, H: FieldHasher<F>, const HEIGHT: usize> {
+	hasher: H,
+	empty_hashes: Vec<F>,
+	frontier: Vec<F>,
+	root: F,
+	next_index: u64,
+}
+
+impl<F: PrimeField, H: FieldHasher<F>, const HEIGHT: usize> IncrementalMerkleTree<F, H, HEIGHT> {
+	/// Creates an empty tree, whose positions all hold `default_leaf`
+	pub fn new(hasher: H, default_leaf: F) -> Result<Self, GadgetError> {
+		let mut empty_hashes = Vec::with_capacity(HEIGHT + 1);
+		let mut empty_hash = default_leaf;
+		empty_hashes.push(empty_hash);
+		for _ in 0..HEIGHT {
+			empty_hash = hasher.hash_two(&empty_hash, &empty_hash)?;
+			empty_hashes.push(empty_hash);
+		}
+
+		Ok(Self {
+			hasher,
+			frontier: empty_hashes[..HEIGHT].to_vec(),
+			root: empty_hashes[HEIGHT],
+			empty_hashes,
+			next_index: 0,
+		})
+	}
+
+	/// Inserts `leaf` at `next_index`, returning the new root. Fails once
+	/// the `2^HEIGHT` leaves are filled.
+	pub fn append(&mut self, leaf: F) -> Result<F, GadgetError> {
+		if self.next_index >= 1u64 << HEIGHT {
+			return Err(GadgetError::InvalidLeafIndex(self.next_index));
+		}
+
+		let mut index = self.next_index;
+		let mut node = leaf;
+		for level in 0..HEIGHT {
+			let (left, right) = if index % 2 == 0 {
+				self.frontier[level] = node;
+				(node, self.empty_hashes[level])
+			} else {
+				(self.frontier[level], node)
+			};
+			node = self.hasher.hash_two(&left, &right)?;
+			index /= 2;
+		}
+
+		self.root = node;
+		self.next_index += 1;
+		Ok(node)
+	}
+
+	/// Root of the leaves appended so far. `append` rehashes the path of the
+	/// new leaf against the frontier and the empty hashes, so the root is
+	/// always up to date and querying it hashes nothing.
+	pub fn root(&self) -> F {
+		self.root
+	}
+
+	/// Index the next appended leaf is inserted at, which is also the
+	/// number of leaves in the tree
+	pub fn next_index(&self) -> u64 {
+		self.next_index
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::PoseidonHasher,
+		identity::CRH as IdentityCRH,
+		merkle_tree::{Config, Rc, SparseMerkleTree},
+		poseidon::CRH as PoseidonCRH,
+		setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3},
+	};
+	use ark_bn254::Fr;
+	use ark_ff::Zero;
+	use ark_std::{test_rng, UniformRand};
+
+	#[derive(Clone, PartialEq)]
+	struct Config5;
+	impl Config for Config5 {
+		type H = PoseidonCRH<Fr, PoseidonRounds_x5_3>;
+		type LeafH = IdentityCRH<Fr>;
+
+		const HEIGHT: u8 = 5;
+	}
+
+	#[test]
+	fn should_match_sparse_tree_root() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let hasher = PoseidonHasher::<Fr, PoseidonRounds_x5_3>::new(params.clone());
+		let mut tree = IncrementalMerkleTree::<_, _, 5>::new(hasher, Fr::zero()).unwrap();
+
+		let empty = SparseMerkleTree::<Config5>::blank(Rc::new(params.clone()), Rc::new(()));
+		assert_eq!(tree.root(), empty.root().inner());
+
+		let leaves = (0..10).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+		for (i, leaf) in leaves.iter().enumerate() {
+			let root = tree.append(*leaf).unwrap();
+			assert_eq!(root, tree.root());
+			assert_eq!(tree.next_index(), i as u64 + 1);
+
+			let sparse = SparseMerkleTree::<Config5>::new_sequential(
+				Rc::new(params.clone()),
+				Rc::new(()),
+				&leaves[..=i],
+			)
+			.unwrap();
+			assert_eq!(root, sparse.root().inner());
+		}
+	}
+
+	#[test]
+	fn should_match_sparse_tree_root_until_full() {
+		#[derive(Clone, PartialEq)]
+		struct Config3;
+		impl Config for Config3 {
+			type H = PoseidonCRH<Fr, PoseidonRounds_x5_3>;
+			type LeafH = IdentityCRH<Fr>;
+
+			const HEIGHT: u8 = 3;
+		}
+
+		let rng = &mut test_rng();
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let hasher = PoseidonHasher::<Fr, PoseidonRounds_x5_3>::new(params.clone());
+		let mut tree = IncrementalMerkleTree::<_, _, 3>::new(hasher, Fr::zero()).unwrap();
+
+		// The last append fills the tree, leaving no empty subtree in the root
+		let leaves = (0..8).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+		for (i, leaf) in leaves.iter().enumerate() {
+			tree.append(*leaf).unwrap();
+			let sparse = SparseMerkleTree::<Config3>::new_sequential(
+				Rc::new(params.clone()),
+				Rc::new(()),
+				&leaves[..=i],
+			)
+			.unwrap();
+			assert_eq!(tree.root(), sparse.root().inner());
+		}
+		assert_eq!(tree.next_index(), 8);
+	}
+
+	#[test]
+	fn should_reject_append_to_full_tree() {
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let hasher = PoseidonHasher::<Fr, PoseidonRounds_x5_3>::new(params);
+		let mut tree = IncrementalMerkleTree::<_, _, 2>::new(hasher, Fr::zero()).unwrap();
+		for i in 0..4u64 {
+			tree.append(Fr::from(i)).unwrap();
+		}
+		assert!(matches!(
+			tree.append(Fr::from(4u64)),
+			Err(GadgetError::InvalidLeafIndex(4))
+		));
+		assert_eq!(tree.next_index(), 4);
+	}
+}
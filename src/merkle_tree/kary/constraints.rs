@@ -0,0 +1,150 @@
+use super::Path;
+use crate::{field_hasher::constraints::FieldHasherGadget, Vec};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::{borrow::Borrow, marker::PhantomData};
+
+/// Gadget of a k-ary `Path`, holding the position of the node among its
+/// siblings at each level
+pub struct PathVar<F: PrimeField, HG: FieldHasherGadget<F>, const ARITY: usize, const N: usize> {
+	path: Vec<Vec<FpVar<F>>>,
+	positions: Vec<FpVar<F>>,
+	hasher: PhantomData<HG>,
+}
+
+impl<F: PrimeField, HG: FieldHasherGadget<F>, const ARITY: usize, const N: usize>
+	PathVar<F, HG, ARITY, N>
+{
+	/// Hashes `leaf` up the path, enforcing that each position is below
+	/// `ARITY` and that each hash is the child at its position in the level
+	/// above it
+	pub fn calculate_root(&self, leaf: &FpVar<F>, hasher: &HG) -> Result<FpVar<F>, SynthesisError> {
+		let mut previous_hash = leaf.clone();
+		for (children, position) in self.path.iter().zip(self.positions.iter()) {
+			let is_at_position = (0..ARITY)
+				.map(|i| position.is_eq(&FpVar::Constant(F::from(i as u64))))
+				.collect::<Result<Vec<_>, _>>()?;
+			Boolean::kary_or(&is_at_position)?.enforce_equal(&Boolean::TRUE)?;
+			for (child, is_at_position) in children.iter().zip(is_at_position.iter()) {
+				child.conditional_enforce_equal(&previous_hash, is_at_position)?;
+			}
+
+			previous_hash = hasher.hash(children)?;
+		}
+
+		Ok(previous_hash)
+	}
+
+	pub fn check_membership(
+		&self,
+		root: &FpVar<F>,
+		leaf: &FpVar<F>,
+		hasher: &HG,
+	) -> Result<Boolean<F>, SynthesisError> {
+		let computed_root = self.calculate_root(leaf, hasher)?;
+		root.is_eq(&computed_root)
+	}
+
+	/// Index of the leaf, recomposed from the positions
+	pub fn index(&self) -> FpVar<F> {
+		let arity = F::from(ARITY as u64);
+		let mut index = FpVar::Constant(F::zero());
+		let mut multiplier = F::one();
+		for position in self.positions.iter() {
+			index += position * multiplier;
+			multiplier *= arity;
+		}
+		index
+	}
+}
+
+impl<F: PrimeField, HG: FieldHasherGadget<F>, const ARITY: usize, const N: usize>
+	AllocVar<Path<F, HG::Native, ARITY, N>, F> for PathVar<F, HG, ARITY, N>
+{
+	fn new_variable<T: Borrow<Path<F, HG::Native, ARITY, N>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
+
+		let path_obj = f()?;
+		let path_obj = path_obj.borrow();
+		let mut path = Vec::with_capacity(N);
+		let mut positions = Vec::with_capacity(N);
+		for (children, position) in path_obj.path.iter().zip(path_obj.positions().iter()) {
+			path.push(Vec::<FpVar<F>>::new_variable(
+				ark_relations::ns!(cs, "children"),
+				|| Ok(children.to_vec()),
+				mode,
+			)?);
+			positions.push(FpVar::new_variable(
+				ark_relations::ns!(cs, "position"),
+				|| Ok(F::from(*position as u64)),
+				mode,
+			)?);
+		}
+
+		Ok(Self {
+			path,
+			positions,
+			hasher: PhantomData,
+		})
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::{constraints::PoseidonHasherGadget, PoseidonHasher},
+		merkle_tree::kary::SparseKaryMerkleTree,
+		setup::common::{setup_params_x5_5, Curve, PoseidonRounds_x5_5},
+	};
+	use ark_bn254::Fr;
+	use ark_ff::Zero;
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{test_rng, UniformRand};
+
+	type HasherGadget = PoseidonHasherGadget<Fr, PoseidonRounds_x5_5>;
+
+	#[test]
+	fn should_verify_middle_child_of_ternary_tree() {
+		let rng = &mut test_rng();
+		let hasher = PoseidonHasher::new(setup_params_x5_5::<Fr>(Curve::Bn254));
+		let leaves: Vec<Fr> = (0..9).map(|_| Fr::rand(rng)).collect();
+		let tree =
+			SparseKaryMerkleTree::<_, _, 3, 2>::new_sequential(hasher.clone(), Fr::zero(), &leaves)
+				.unwrap();
+		let root = tree.root();
+		// Middle child of the middle node
+		let path = tree.generate_membership_proof(4).unwrap();
+
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let hasher_var = HasherGadget::from_native(cs.clone(), hasher.clone()).unwrap();
+		let path_var =
+			PathVar::<Fr, HasherGadget, 3, 2>::new_witness(cs.clone(), || Ok(path.clone()))
+				.unwrap();
+		let leaf_var = FpVar::new_witness(cs.clone(), || Ok(leaves[4])).unwrap();
+		let root_var = FpVar::new_input(cs.clone(), || Ok(root)).unwrap();
+
+		let is_member = path_var
+			.check_membership(&root_var, &leaf_var, &hasher_var)
+			.unwrap();
+		is_member.enforce_equal(&Boolean::TRUE).unwrap();
+		assert_eq!(path_var.index().value().unwrap(), Fr::from(4u64));
+		assert!(cs.is_satisfied().unwrap());
+
+		// The leaf is in the path, but not at the witnessed position
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let hasher_var = HasherGadget::from_native(cs.clone(), hasher).unwrap();
+		let path_var =
+			PathVar::<Fr, HasherGadget, 3, 2>::new_witness(cs.clone(), || Ok(path)).unwrap();
+		let leaf_var = FpVar::new_witness(cs.clone(), || Ok(leaves[3])).unwrap();
+		path_var.calculate_root(&leaf_var, &hasher_var).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+}
@@ -0,0 +1,234 @@
+use crate::{error::GadgetError, field_hasher::FieldHasher, Vec};
+use ark_crypto_primitives::Error;
+use ark_ff::PrimeField;
+use ark_std::{
+	collections::{BTreeMap, BTreeSet},
+	marker::PhantomData,
+};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// Membership path of the leaf at `index` in a tree of arity `ARITY` and
+/// height `N`. Each level holds the `ARITY` children of a node, from the leaf
+/// level up to the children of the root.
+pub struct Path<F: PrimeField, H: FieldHasher<F>, const ARITY: usize, const N: usize> {
+	pub path: [[F; ARITY]; N],
+	pub index: u64,
+	hasher: PhantomData<H>,
+}
+
+impl<F: PrimeField, H: FieldHasher<F>, const ARITY: usize, const N: usize> Clone
+	for Path<F, H, ARITY, N>
+{
+	fn clone(&self) -> Self {
+		Self::new(self.path, self.index)
+	}
+}
+
+impl<F: PrimeField, H: FieldHasher<F>, const ARITY: usize, const N: usize> Path<F, H, ARITY, N> {
+	pub fn new(path: [[F; ARITY]; N], index: u64) -> Self {
+		Self {
+			path,
+			index,
+			hasher: PhantomData,
+		}
+	}
+
+	/// Position of the node among its siblings at each level, from the leaf
+	/// level up
+	pub fn positions(&self) -> [usize; N] {
+		let mut positions = [0; N];
+		let mut index = self.index;
+		for position in positions.iter_mut() {
+			*position = (index % ARITY as u64) as usize;
+			index /= ARITY as u64;
+		}
+		positions
+	}
+
+	/// Hashes `leaf` up the path, failing if a node isn't the child at its
+	/// position in the level below it
+	pub fn calculate_root(&self, leaf: &F, hasher: &H) -> Result<Option<F>, Error> {
+		let mut prev = *leaf;
+		for (children, position) in self.path.iter().zip(self.positions().iter()) {
+			if children[*position] != prev {
+				return Ok(None);
+			}
+			prev = hasher.hash(children)?;
+		}
+
+		Ok(Some(prev))
+	}
+
+	pub fn check_membership(&self, root: &F, leaf: &F, hasher: &H) -> Result<bool, Error> {
+		let root_hash = self.calculate_root(leaf, hasher)?;
+		Ok(root_hash.as_ref() == Some(root))
+	}
+}
+
+/// Sparse Merkle tree of arity `ARITY` and height `N`, holding up to
+/// `ARITY^N` leaves. Each node is the hash of its `ARITY` children in a
+/// single `FieldHasher::hash` call, so the hasher must absorb `ARITY` inputs
+/// at once. The `Rounds` of `PoseidonHasher` keep no capacity, so its width
+/// must be at least `ARITY`, e.g. x5_5 for a ternary tree, or building the
+/// tree fails.
+pub struct SparseKaryMerkleTree<
+	F: PrimeField,
+	H: FieldHasher<F>,
+	const ARITY: usize,
+	const N: usize,
+> {
+	/// data of the tree, node `i` having children `ARITY * i + 1` to
+	/// `ARITY * i + ARITY`
+	pub tree: BTreeMap<u64, F>,
+	empty_hashes: Vec<F>,
+	hasher: H,
+}
+
+impl<F: PrimeField, H: FieldHasher<F>, const ARITY: usize, const N: usize>
+	SparseKaryMerkleTree<F, H, ARITY, N>
+{
+	/// Builds the tree over `leaves`, keyed by their index. Missing leaves are
+	/// `empty_leaf`.
+	pub fn new(hasher: H, empty_leaf: F, leaves: &BTreeMap<u64, F>) -> Result<Self, Error> {
+		assert!(ARITY >= 2 && N >= 1);
+
+		let mut empty_hashes = Vec::with_capacity(N + 1);
+		let mut empty_hash = empty_leaf;
+		empty_hashes.push(empty_hash);
+		for _ in 0..N {
+			empty_hash = hasher.hash(&[empty_hash; ARITY])?;
+			empty_hashes.push(empty_hash);
+		}
+
+		let mut smt = Self {
+			tree: BTreeMap::new(),
+			empty_hashes,
+			hasher,
+		};
+		smt.insert_batch(leaves)?;
+
+		Ok(smt)
+	}
+
+	pub fn new_sequential(hasher: H, empty_leaf: F, leaves: &[F]) -> Result<Self, Error> {
+		let pairs: BTreeMap<u64, F> = leaves
+			.iter()
+			.enumerate()
+			.map(|(i, l)| (i as u64, *l))
+			.collect();
+		Self::new(hasher, empty_leaf, &pairs)
+	}
+
+	/// Inserts `leaves` and rehashes the nodes above them
+	pub fn insert_batch(&mut self, leaves: &BTreeMap<u64, F>) -> Result<(), Error> {
+		let num_leaves = (ARITY as u64).pow(N as u32);
+		let first_leaf = (num_leaves - 1) / (ARITY as u64 - 1);
+
+		let mut level_idxs: BTreeSet<u64> = BTreeSet::new();
+		for (i, leaf) in leaves {
+			if *i >= num_leaves {
+				return Err(GadgetError::InvalidLeafIndex(*i).into());
+			}
+			let tree_index = first_leaf + i;
+			self.tree.insert(tree_index, *leaf);
+			level_idxs.insert(Self::parent(tree_index));
+		}
+
+		for level in 0..N {
+			let mut new_idxs: BTreeSet<u64> = BTreeSet::new();
+			for i in level_idxs {
+				let node = self.hasher.hash(&self.children(i, level))?;
+				self.tree.insert(i, node);
+				if i > 0 {
+					new_idxs.insert(Self::parent(i));
+				}
+			}
+			level_idxs = new_idxs;
+		}
+
+		Ok(())
+	}
+
+	pub fn root(&self) -> F {
+		self.tree.get(&0).cloned().unwrap_or(self.empty_hashes[N])
+	}
+
+	/// generate a membership proof (does not check the data point), failing
+	/// if `index` is past the last leaf
+	pub fn generate_membership_proof(&self, index: u64) -> Result<Path<F, H, ARITY, N>, Error> {
+		let num_leaves = (ARITY as u64).pow(N as u32);
+		if index >= num_leaves {
+			return Err(GadgetError::InvalidLeafIndex(index).into());
+		}
+		let mut current_node = (num_leaves - 1) / (ARITY as u64 - 1) + index;
+
+		let mut path = [[F::zero(); ARITY]; N];
+		for (level, children) in path.iter_mut().enumerate() {
+			let parent = Self::parent(current_node);
+			*children = self.children(parent, level);
+			current_node = parent;
+		}
+
+		Ok(Path::new(path, index))
+	}
+
+	/// Children of the node at `index`, which are at `level`
+	fn children(&self, index: u64, level: usize) -> [F; ARITY] {
+		let mut children = [self.empty_hashes[level]; ARITY];
+		for (j, child) in children.iter_mut().enumerate() {
+			if let Some(node) = self.tree.get(&(ARITY as u64 * index + 1 + j as u64)) {
+				*child = *node;
+			}
+		}
+		children
+	}
+
+	fn parent(index: u64) -> u64 {
+		(index - 1) / ARITY as u64
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::PoseidonHasher,
+		setup::common::{setup_params_x5_5, Curve, PoseidonRounds_x5_5},
+	};
+	use ark_bn254::Fr;
+	use ark_ff::Zero;
+	use ark_std::{test_rng, UniformRand};
+
+	type Hasher = PoseidonHasher<Fr, PoseidonRounds_x5_5>;
+
+	#[test]
+	fn should_verify_membership_in_ternary_tree() {
+		let rng = &mut test_rng();
+		let hasher = Hasher::new(setup_params_x5_5::<Fr>(Curve::Bn254));
+		let leaves: Vec<Fr> = (0..6).map(|_| Fr::rand(rng)).collect();
+		let mut tree =
+			SparseKaryMerkleTree::<_, _, 3, 2>::new_sequential(hasher.clone(), Fr::zero(), &leaves)
+				.unwrap();
+
+		let empty = hasher.hash(&[Fr::zero(); 3]).unwrap();
+		let first = hasher.hash(&leaves[..3]).unwrap();
+		let second = hasher.hash(&leaves[3..]).unwrap();
+		let root = hasher.hash(&[first, second, empty]).unwrap();
+		assert_eq!(tree.root(), root);
+
+		// Middle child of the middle node
+		let path = tree.generate_membership_proof(4).unwrap();
+		assert_eq!(path.positions(), [1, 1]);
+		assert!(path.check_membership(&root, &leaves[4], &hasher).unwrap());
+		assert!(!path.check_membership(&root, &leaves[3], &hasher).unwrap());
+		assert!(tree.generate_membership_proof(9).is_err());
+
+		// A ternary tree of height 2 has 9 leaves
+		let mut out_of_range = BTreeMap::new();
+		out_of_range.insert(9, Fr::zero());
+		assert!(tree.insert_batch(&out_of_range).is_err());
+	}
+}
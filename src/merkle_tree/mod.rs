@@ -1,15 +1,59 @@
+use crate::error::GadgetError;
 use ark_crypto_primitives::{Error, CRH};
 use ark_ff::{to_bytes, ToBytes};
+use ark_serialize::{CanonicalDeserialize, Read};
 use ark_std::{
 	borrow::Borrow,
 	collections::{BTreeMap, BTreeSet},
 	io::{Result as IoResult, Write},
-	rc::Rc,
 	vec::Vec,
 };
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Pointer through which a tree and its paths share the hasher parameters.
+/// With the `parallel` feature it is an `Arc`, so that proofs can be
+/// generated from the same tree on several threads.
+#[cfg(feature = "parallel")]
+pub use ark_std::sync::Arc as Rc;
+#[cfg(not(feature = "parallel"))]
+pub use ark_std::rc::Rc;
+
+/// Bound on the leaves, nodes and parameters of the tree builders. With the
+/// `parallel` feature it is `Send + Sync`, so that the nodes of a level can
+/// be hashed on several threads, and it holds for every type otherwise.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Send + Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync> MaybeSync for T {}
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSync for T {}
+
 #[cfg(feature = "r1cs")]
 pub mod constraints;
+pub mod fixed_path;
+pub mod incremental;
+pub mod kary;
+
+#[derive(Debug)]
+pub enum MerkleError {
+	HashFailed,
+}
+
+impl core::fmt::Display for MerkleError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		use MerkleError::*;
+		let msg = match self {
+			HashFailed => format!("failed to hash tree nodes"),
+		};
+		write!(f, "{}", msg)
+	}
+}
+
+impl ark_std::error::Error for MerkleError {}
 
 /// configuration of a Merkle tree
 pub trait Config: Clone {
@@ -80,14 +124,10 @@ impl<P: Config + PartialEq> Path<P> {
 
 		let claimed_leaf_hash = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
 
-		// Check if claimed leaf hash is the same as one of
-		// the provided hashes on level 0
-		if claimed_leaf_hash != self.path[0].0 && claimed_leaf_hash != self.path[0].1 {
-			return Ok(false);
-		}
-
+		// Check levels between leaf level and root. The first iteration checks
+		// the claimed leaf hash against the level 0 pair, and the last hash is
+		// the root, compared once after the loop.
 		let mut prev = claimed_leaf_hash;
-		// Check levels between leaf level and root.
 		for &(ref left_hash, ref right_hash) in &self.path {
 			// Check if the previous hash matches the correct current hash.
 			if &prev != left_hash && &prev != right_hash {
@@ -137,6 +177,12 @@ impl<P: Config> SparseMerkleTree<P> {
 			level_idxs.insert(parent(true_index).unwrap());
 		}
 
+		self.hash_levels(level_idxs)
+	}
+
+	/// Rehashes the inner nodes at `level_idxs`, just above the leaves, and
+	/// their ancestors up to the root
+	fn hash_levels(&mut self, mut level_idxs: BTreeSet<u64>) -> Result<(), Error> {
 		for level in 0..P::HEIGHT {
 			let mut new_idxs: BTreeSet<u64> = BTreeSet::new();
 			for i in level_idxs {
@@ -169,6 +215,48 @@ impl<P: Config> SparseMerkleTree<P> {
 		inner_params: Rc<InnerParameters<P>>,
 		leaf_params: Rc<LeafParameters<P>>,
 		leaves: &BTreeMap<u32, L>,
+	) -> Result<Self, Error> {
+		let empty_hashes = gen_empty_hashes::<P>(leaf_params.borrow(), inner_params.borrow())?;
+		Self::with_empty_hashes(inner_params, leaf_params, empty_hashes, leaves)
+	}
+
+	/// Like `new`, deriving the hashes of empty subtrees from `default_leaf`
+	/// rather than from a leaf of zero bytes
+	pub fn new_with_default_leaf<L: Default + ToBytes>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		leaves: &BTreeMap<u32, L>,
+		default_leaf: &L,
+	) -> Result<Self, Error> {
+		let empty_hashes = gen_empty_hashes_with_default_leaf::<P, _>(
+			leaf_params.borrow(),
+			inner_params.borrow(),
+			default_leaf,
+		)?;
+		Self::with_empty_hashes(inner_params, leaf_params, empty_hashes, leaves)
+	}
+
+	/// Like `new_sequential`, deriving the hashes of empty subtrees from
+	/// `default_leaf` rather than from a leaf of zero bytes
+	pub fn new_sequential_with_default_leaf<L: Default + ToBytes + Copy>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		leaves: &[L],
+		default_leaf: &L,
+	) -> Result<Self, Error> {
+		let pairs: BTreeMap<u32, L> = leaves
+			.iter()
+			.enumerate()
+			.map(|(i, l)| (i as u32, *l))
+			.collect();
+		Self::new_with_default_leaf(inner_params, leaf_params, &pairs, default_leaf)
+	}
+
+	fn with_empty_hashes<L: Default + ToBytes>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		empty_hashes: Vec<Node<P>>,
+		leaves: &BTreeMap<u32, L>,
 	) -> Result<Self, Error> {
 		let last_level_size = leaves.len().next_power_of_two();
 		let tree_size = 2 * last_level_size - 1;
@@ -177,7 +265,6 @@ impl<P: Config> SparseMerkleTree<P> {
 
 		// Initialize the merkle tree.
 		let tree: BTreeMap<u64, Node<P>> = BTreeMap::new();
-		let empty_hashes = gen_empty_hashes::<P>(leaf_params.borrow(), inner_params.borrow())?;
 
 		let mut smt = SparseMerkleTree {
 			tree,
@@ -189,26 +276,209 @@ impl<P: Config> SparseMerkleTree<P> {
 
 		Ok(smt)
 	}
+}
+
+impl<P: Config> SparseMerkleTree<P>
+where
+	Node<P>: MaybeSync,
+	InnerParameters<P>: MaybeSync,
+	LeafParameters<P>: MaybeSync,
+{
+	/// Inserts `nodes`, the hashed leaves at indices `0..nodes.len()`, and
+	/// hashes the levels above them up to the root, passing the number of
+	/// nodes hashed to `on_level` after each level. With the `parallel`
+	/// feature the nodes of each level are hashed concurrently.
+	fn insert_leaf_hashes(
+		&mut self,
+		mut nodes: Vec<Node<P>>,
+		mut on_level: impl FnMut(usize),
+	) -> Result<(), Error> {
+		if nodes.is_empty() {
+			return Ok(());
+		}
+
+		let mut level_start: u64 = (1u64 << P::HEIGHT) - 1;
+		for level in 0..P::HEIGHT as usize {
+			for (i, node) in nodes.iter().enumerate() {
+				self.tree.insert(level_start + i as u64, node.clone());
+			}
+
+			let inner_params: &InnerParameters<P> = self.inner_params.borrow();
+			let empty_hash = &self.empty_hashes[level];
+			let hash_pair = |pair: &[Node<P>]| {
+				let right = pair.get(1).unwrap_or(empty_hash);
+				hash_inner_node::<P>(inner_params, &pair[0], right).ok()
+			};
+			#[cfg(feature = "parallel")]
+			let parents = nodes.par_chunks(2).map(hash_pair).collect::<Option<Vec<_>>>();
+			#[cfg(not(feature = "parallel"))]
+			let parents = nodes.chunks(2).map(hash_pair).collect::<Option<Vec<_>>>();
+			nodes = parents.ok_or(MerkleError::HashFailed)?;
+			level_start = parent(level_start).unwrap();
+			on_level(nodes.len());
+		}
+		self.tree.insert(level_start, nodes[0].clone());
+
+		Ok(())
+	}
+
+	/// Builds the tree over `count` leaves placed at indices `0..count`,
+	/// deserializing them one at a time from `reader` so that their
+	/// serialized bytes are never held in memory. The tree is the one
+	/// `new_sequential` builds over the same leaves.
+	pub fn from_reader<L: CanonicalDeserialize + ToBytes, R: Read>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		mut reader: R,
+		count: u64,
+	) -> Result<Self, Error> {
+		if count > 1u64 << P::HEIGHT {
+			return Err(GadgetError::InvalidLeafIndex(count - 1).into());
+		}
+
+		let mut smt = Self::blank(inner_params, leaf_params);
+		let mut nodes = Vec::with_capacity(count as usize);
+		for _ in 0..count {
+			let leaf = L::deserialize(&mut reader)?;
+			nodes.push(hash_leaf::<P, _>(smt.leaf_params.borrow(), &leaf)?);
+		}
+		smt.insert_leaf_hashes(nodes, |_| {})?;
+
+		Ok(smt)
+	}
 
-	pub fn new_sequential<L: Default + ToBytes + Copy>(
+	/// Builds the tree over `leaves` placed at indices `0..leaves.len()`,
+	/// calling `progress` with the number of nodes hashed so far and the total
+	/// number of nodes to hash, once the leaves are hashed and after each
+	/// level of inner nodes. With the `parallel` feature the leaves and the
+	/// nodes of each level are hashed concurrently.
+	pub fn build_with_progress<L: ToBytes + MaybeSync>(
 		inner_params: Rc<InnerParameters<P>>,
 		leaf_params: Rc<LeafParameters<P>>,
 		leaves: &[L],
+		mut progress: impl FnMut(usize, usize),
 	) -> Result<Self, Error> {
-		let pairs: BTreeMap<u32, L> = leaves
-			.iter()
-			.enumerate()
-			.map(|(i, l)| (i as u32, *l))
-			.collect();
-		let smt = Self::new(inner_params, leaf_params, &pairs)?;
+		if leaves.len() as u64 > 1u64 << P::HEIGHT {
+			return Err(GadgetError::InvalidLeafIndex(leaves.len() as u64 - 1).into());
+		}
+		if leaves.is_empty() {
+			return Ok(Self::blank(inner_params, leaf_params));
+		}
+
+		// The leaves are contiguous, so each level has half as many nodes as
+		// the one below, rounded up
+		let mut total = leaves.len();
+		let mut level_size = leaves.len();
+		for _ in 0..P::HEIGHT {
+			level_size = (level_size + 1) / 2;
+			total += level_size;
+		}
+
+		let mut smt = Self::blank(inner_params, leaf_params);
+		let leaf_params: &LeafParameters<P> = smt.leaf_params.borrow();
+		let hash = |leaf: &L| hash_leaf::<P, _>(leaf_params, leaf).ok();
+		#[cfg(feature = "parallel")]
+		let nodes = leaves.par_iter().map(hash).collect::<Option<Vec<_>>>();
+		#[cfg(not(feature = "parallel"))]
+		let nodes = leaves.iter().map(hash).collect::<Option<Vec<_>>>();
+		let nodes = nodes.ok_or(MerkleError::HashFailed)?;
+
+		let mut completed = leaves.len();
+		progress(completed, total);
+		smt.insert_leaf_hashes(nodes, |hashed| {
+			completed += hashed;
+			progress(completed, total);
+		})?;
 
 		Ok(smt)
 	}
 
+	/// Builds the tree over `leaves` placed at indices `0..leaves.len()`, as
+	/// `build_with_progress` does without reporting progress
+	pub fn new_sequential<L: Default + ToBytes + Copy + MaybeSync>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		leaves: &[L],
+	) -> Result<Self, Error> {
+		Self::build_with_progress(inner_params, leaf_params, leaves, |_, _| {})
+	}
+}
+
+impl<P: Config> SparseMerkleTree<P> {
 	#[inline]
-	/// obtain the root hash
+	/// obtain the root hash, which is the top empty hash for a blank tree
 	pub fn root(&self) -> Node<P> {
-		self.tree.get(&0).cloned().unwrap()
+		self.tree
+			.get(&0)
+			.cloned()
+			.unwrap_or_else(|| self.empty_hashes[P::HEIGHT as usize].clone())
+	}
+
+	/// Inserted leaves with their index, in ascending order of index. Leaves
+	/// are stored hashed with `P::LeafH`.
+	pub fn leaves(&self) -> impl Iterator<Item = (u64, &LeafNode<P>)> {
+		let first_leaf = convert_index_to_last_level::<P>(0);
+		self.tree
+			.range(first_leaf..)
+			.filter_map(move |(i, node)| match node {
+				Node::Leaf(leaf) => Some((i - first_leaf, leaf)),
+				Node::Inner(_) => None,
+			})
+	}
+
+	/// Returns the leaf at `index`, if one was inserted there
+	pub fn get_leaf(&self, index: u64) -> Option<&LeafNode<P>> {
+		match self.tree.get(&convert_index_to_last_level::<P>(index)) {
+			Some(Node::Leaf(leaf)) => Some(leaf),
+			_ => None,
+		}
+	}
+
+	/// Every level of the tree, from the `2^P::HEIGHT` leaves up to the root,
+	/// with the empty hash of the level at positions never filled. Meant for
+	/// printing or diffing small trees while debugging: the levels hold
+	/// `2^(P::HEIGHT + 1) - 1` nodes in total.
+	pub fn layers(&self) -> Vec<Vec<Node<P>>> {
+		(0..=P::HEIGHT as usize)
+			.map(|level| {
+				let width = 1u64 << (P::HEIGHT as usize - level);
+				let empty_hash = &self.empty_hashes[level];
+				(width - 1..2 * width - 1)
+					.map(|i| self.tree.get(&i).unwrap_or(empty_hash).clone())
+					.collect()
+			})
+			.collect()
+	}
+
+	/// Overwrites the leaf at `index` and rehashes its path up to the root,
+	/// returning the new root. Paths generated before the update are stale
+	/// and have to be generated again.
+	pub fn update<L: ToBytes>(
+		&mut self,
+		index: u64,
+		leaf: &L,
+	) -> Result<InnerNode<P>, GadgetError> {
+		if index >= 1u64 << P::HEIGHT {
+			return Err(GadgetError::InvalidLeafIndex(index));
+		}
+
+		let mut current_node = convert_index_to_last_level::<P>(index);
+		let leaf_hash = hash_leaf::<P, _>(self.leaf_params.borrow(), leaf)?;
+		self.tree.insert(current_node, leaf_hash);
+
+		let mut level = 0;
+		while let Some(parent_node) = parent(current_node) {
+			let empty_hash = &self.empty_hashes[level];
+			let left = self.tree.get(&left_child(parent_node)).unwrap_or(empty_hash);
+			let right = self.tree.get(&right_child(parent_node)).unwrap_or(empty_hash);
+			let node = hash_inner_node::<P>(self.inner_params.borrow(), left, right)?;
+			self.tree.insert(parent_node, node);
+
+			current_node = parent_node;
+			level += 1;
+		}
+
+		Ok(self.root().inner())
 	}
 
 	/// generate a membership proof (does not check the data point)
@@ -349,9 +619,31 @@ pub fn gen_empty_hashes<P: Config>(
 	leaf_params: &LeafParameters<P>,
 	inner_params: &InnerParameters<P>,
 ) -> Result<Vec<Node<P>>, Error> {
-	let mut empty_hashes = Vec::with_capacity(P::HEIGHT as usize);
+	let empty_leaf = hash_empty::<P>(leaf_params)?;
+	empty_hashes_from::<P>(empty_leaf, inner_params)
+}
+
+/// Like `gen_empty_hashes`, with empty positions holding `default_leaf`
+pub fn gen_empty_hashes_with_default_leaf<P: Config, L: ToBytes>(
+	leaf_params: &LeafParameters<P>,
+	inner_params: &InnerParameters<P>,
+	default_leaf: &L,
+) -> Result<Vec<Node<P>>, Error> {
+	let empty_leaf = hash_leaf::<P, _>(leaf_params, default_leaf)?;
+	empty_hashes_from::<P>(empty_leaf, inner_params)
+}
+
+/// Hashes the empty subtrees level by level, from the leaf up to the root.
+/// This is a loop rather than a recursion, so tall trees don't grow the
+/// stack, and it keeps a single node per level: `P::HEIGHT + 1` nodes in
+/// total.
+fn empty_hashes_from<P: Config>(
+	empty_leaf: Node<P>,
+	inner_params: &InnerParameters<P>,
+) -> Result<Vec<Node<P>>, Error> {
+	let mut empty_hashes = Vec::with_capacity(P::HEIGHT as usize + 1);
 
-	let mut empty_hash = hash_empty::<P>(leaf_params)?;
+	let mut empty_hash = empty_leaf;
 	empty_hashes.push(empty_hash.clone());
 
 	for _ in 1..=P::HEIGHT {
@@ -365,7 +657,7 @@ pub fn gen_empty_hashes<P: Config>(
 #[cfg(all(feature = "default_poseidon", feature = "default_mimc"))]
 #[cfg(test)]
 mod test {
-	use super::{gen_empty_hashes, hash_inner_node, hash_leaf, Config, SparseMerkleTree};
+	use super::{gen_empty_hashes, hash_inner_node, hash_leaf, Config, Rc, SparseMerkleTree};
 	use crate::{
 		poseidon::{sbox::PoseidonSbox, PoseidonParameters, Rounds, CRH as PoseidonCRH},
 		utils::{get_mds_poseidon_bls381_x5_3, get_rounds_poseidon_bls381_x5_3},
@@ -373,7 +665,8 @@ mod test {
 	use ark_bls12_381::Fq;
 	use ark_crypto_primitives::crh::CRH;
 	use ark_ff::{ToBytes, UniformRand};
-	use ark_std::{borrow::Borrow, collections::BTreeMap, rc::Rc, test_rng};
+	use ark_serialize::CanonicalSerialize;
+	use ark_std::{borrow::Borrow, collections::BTreeMap, test_rng, vec::Vec};
 
 	#[derive(Default, Clone)]
 	struct PoseidonRounds3;
@@ -461,6 +754,284 @@ mod test {
 		assert!(res);
 	}
 
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct TallSMTConfig;
+	impl Config for TallSMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 32;
+	}
+
+	#[test]
+	fn should_build_tall_tree_empty_hashes() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params3 = PoseidonParameters::<Fq>::new(rounds3, mds3);
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let empty_hashes =
+			gen_empty_hashes::<TallSMTConfig>(leaf_params.borrow(), inner_params.borrow()).unwrap();
+		assert_eq!(empty_hashes.len(), TallSMTConfig::HEIGHT as usize + 1);
+
+		// A blank tree has the top empty hash as its root
+		let blank =
+			SparseMerkleTree::<TallSMTConfig>::blank(inner_params.clone(), leaf_params.clone());
+		assert_eq!(blank.root(), empty_hashes[TallSMTConfig::HEIGHT as usize]);
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = create_merkle_tree::<_, TallSMTConfig>(inner_params, leaf_params, &leaves);
+		assert_ne!(smt.root(), blank.root());
+		let proof = smt.generate_membership_proof(2);
+		assert!(proof.check_membership(&smt.root(), &leaves[2]).unwrap());
+	}
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct LargeSMTConfig;
+	impl Config for LargeSMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 11;
+	}
+
+	#[test]
+	fn should_build_same_tree_sequentially_and_from_map() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params3 = PoseidonParameters::<Fq>::new(rounds3, mds3);
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let leaves: Vec<Fq> = (0..1024).map(|_| Fq::rand(rng)).collect();
+		let smt = SparseMerkleTree::<LargeSMTConfig>::new_sequential(
+			inner_params.clone(),
+			leaf_params.clone(),
+			&leaves,
+		)
+		.unwrap();
+		let expected =
+			create_merkle_tree::<_, LargeSMTConfig>(inner_params, leaf_params, &leaves);
+
+		assert_eq!(smt.root(), expected.root());
+		assert_eq!(smt.tree, expected.tree);
+	}
+
+	#[test]
+	fn should_report_progress_while_building() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = Rc::new(PoseidonParameters::<Fq>::new(rounds3, mds3));
+
+		let leaves: Vec<Fq> = (0..5).map(|_| Fq::rand(rng)).collect();
+		let mut calls = Vec::new();
+		let smt = SparseMerkleTree::<SMTConfig>::build_with_progress(
+			params.clone(),
+			params.clone(),
+			&leaves,
+			|completed, total| calls.push((completed, total)),
+		)
+		.unwrap();
+		let expected = create_merkle_tree::<_, SMTConfig>(params.clone(), params, &leaves);
+		assert_eq!(smt.root(), expected.root());
+		assert_eq!(smt.tree, expected.tree);
+
+		// The leaves, then the 3, 2 and 1 nodes of the levels above them
+		assert_eq!(calls, vec![(5, 11), (8, 11), (10, 11), (11, 11)]);
+		assert!(calls.windows(2).all(|pair| pair[0].0 < pair[1].0));
+	}
+
+	#[test]
+	fn should_rebuild_tree_from_reader() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = Rc::new(PoseidonParameters::<Fq>::new(rounds3, mds3));
+
+		let leaves: Vec<Fq> = (0..16).map(|_| Fq::rand(rng)).collect();
+		let mut bytes = Vec::new();
+		for leaf in leaves.iter() {
+			leaf.serialize(&mut bytes).unwrap();
+		}
+
+		let smt = SparseMerkleTree::<LargeSMTConfig>::from_reader::<Fq, _>(
+			params.clone(),
+			params.clone(),
+			&bytes[..],
+			16,
+		)
+		.unwrap();
+		let expected = SparseMerkleTree::<LargeSMTConfig>::new_sequential(
+			params.clone(),
+			params.clone(),
+			&leaves,
+		)
+		.unwrap();
+		assert_eq!(smt.root(), expected.root());
+		assert_eq!(smt.tree, expected.tree);
+
+		// Running out of bytes fails
+		let res = SparseMerkleTree::<LargeSMTConfig>::from_reader::<Fq, _>(
+			params.clone(),
+			params.clone(),
+			&bytes[..],
+			17,
+		);
+		assert!(res.is_err());
+
+		// As do more leaves than the tree holds
+		let res = SparseMerkleTree::<SMTConfig>::from_reader::<Fq, _>(
+			params.clone(),
+			params,
+			&bytes[..],
+			16,
+		);
+		assert!(res.is_err());
+	}
+
+	#[test]
+	fn should_iterate_leaves_by_index() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params3 = PoseidonParameters::<Fq>::new(rounds3, mds3);
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let mut pairs = BTreeMap::new();
+		pairs.insert(6u32, leaves[2]);
+		pairs.insert(1u32, leaves[0]);
+		pairs.insert(4u32, leaves[1]);
+		let smt =
+			SparseMerkleTree::<SMTConfig>::new(inner_params, leaf_params.clone(), &pairs).unwrap();
+
+		let hashed: Vec<_> = leaves
+			.iter()
+			.map(|leaf| hash_leaf::<SMTConfig, _>(leaf_params.borrow(), leaf).unwrap().leaf())
+			.collect();
+		let iterated: Vec<_> = smt.leaves().map(|(i, leaf)| (i, *leaf)).collect();
+		assert_eq!(iterated, vec![(1, hashed[0]), (4, hashed[1]), (6, hashed[2])]);
+
+		assert_eq!(smt.get_leaf(4), Some(&hashed[1]));
+		assert_eq!(smt.get_leaf(0), None);
+		assert_eq!(smt.get_leaf(1 << SMTConfig::HEIGHT), None);
+	}
+
+	#[test]
+	fn should_list_layers_from_leaves_to_root() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let inner_params = Rc::new(PoseidonParameters::<Fq>::new(rounds3, mds3));
+		let leaf_params = inner_params.clone();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt =
+			create_merkle_tree::<_, SMTConfig>(inner_params.clone(), leaf_params.clone(), &leaves);
+		let empty_hashes =
+			gen_empty_hashes::<SMTConfig>(inner_params.borrow(), leaf_params.borrow()).unwrap();
+
+		let layers = smt.layers();
+		assert_eq!(layers.len(), SMTConfig::HEIGHT as usize + 1);
+		for (level, layer) in layers.iter().enumerate() {
+			assert_eq!(layer.len(), 8 >> level);
+		}
+		assert_eq!(layers[3], vec![smt.root()]);
+
+		// Inserted leaves, then the empty leaf hash
+		let leaf_hash = hash_leaf::<SMTConfig, _>(leaf_params.borrow(), &leaves[2]).unwrap();
+		assert_eq!(layers[0][2], leaf_hash);
+		assert_eq!(layers[0][3], empty_hashes[0]);
+		assert_eq!(layers[1][3], empty_hashes[1]);
+		// Each node hashes its two children in the layer below
+		for pair in layers.windows(2) {
+			let (below, layer) = (&pair[0], &pair[1]);
+			for (i, node) in layer.iter().enumerate() {
+				let (left, right) = (&below[2 * i], &below[2 * i + 1]);
+				let hash = hash_inner_node::<SMTConfig>(inner_params.borrow(), left, right).unwrap();
+				assert_eq!(node, &hash);
+			}
+		}
+	}
+
+	#[test]
+	fn should_update_leaf_and_refresh_proofs() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params3 = PoseidonParameters::<Fq>::new(rounds3, mds3);
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let mut leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let mut smt =
+			create_merkle_tree::<_, SMTConfig>(inner_params.clone(), leaf_params.clone(), &leaves);
+		let stale_proof = smt.generate_membership_proof(1);
+
+		leaves[0] = Fq::rand(rng);
+		let new_root = smt.update(0, &leaves[0]).unwrap();
+		assert_eq!(smt.root().inner(), new_root);
+
+		let expected = create_merkle_tree::<_, SMTConfig>(inner_params, leaf_params, &leaves);
+		assert_eq!(smt.root(), expected.root());
+
+		// Leaf 1 shares its whole path with leaf 0, so its old proof is stale
+		assert!(!stale_proof.check_membership(&smt.root(), &leaves[1]).unwrap());
+		let proof = smt.generate_membership_proof(1);
+		assert!(proof.check_membership(&smt.root(), &leaves[1]).unwrap());
+		let proof = smt.generate_membership_proof(0);
+		assert!(proof.check_membership(&smt.root(), &leaves[0]).unwrap());
+
+		assert!(smt.update(1 << SMTConfig::HEIGHT, &leaves[0]).is_err());
+	}
+
+	#[test]
+	fn should_build_tree_with_default_leaf() {
+		let rng = &mut test_rng();
+		let rounds3 = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds3 = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params3 = PoseidonParameters::<Fq>::new(rounds3, mds3);
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let default_a = Fq::rand(rng);
+		let default_b = Fq::rand(rng);
+		let smt_a = SparseMerkleTree::<SMTConfig>::new_sequential_with_default_leaf(
+			inner_params.clone(),
+			leaf_params.clone(),
+			&leaves,
+			&default_a,
+		)
+		.unwrap();
+		let smt_b = SparseMerkleTree::<SMTConfig>::new_sequential_with_default_leaf(
+			inner_params.clone(),
+			leaf_params.clone(),
+			&leaves,
+			&default_b,
+		)
+		.unwrap();
+		assert_ne!(smt_a.root(), smt_b.root());
+
+		// The default leaf fills the empty positions
+		let mut filled = leaves.clone();
+		filled.resize(1 << SMTConfig::HEIGHT, default_a);
+		let smt_filled = create_merkle_tree::<_, SMTConfig>(inner_params, leaf_params, &filled);
+		assert_eq!(smt_a.root(), smt_filled.root());
+
+		for (i, leaf) in leaves.iter().enumerate() {
+			let proof_a = smt_a.generate_membership_proof(i as u64);
+			let proof_b = smt_b.generate_membership_proof(i as u64);
+			assert!(proof_a.check_membership(&smt_a.root(), leaf).unwrap());
+			assert!(proof_b.check_membership(&smt_b.root(), leaf).unwrap());
+			assert!(!proof_a.check_membership(&smt_b.root(), leaf).unwrap());
+		}
+	}
 
 	use ark_ed_on_bn254::Fq as Bn254Fq;
 	use crate::mimc::Rounds as MiMCRounds;
@@ -1,5 +1,5 @@
 use super::{MiMCParameters, Rounds, CRH};
-use crate::utils::to_field_var_elements;
+use crate::utils::{full_chunk_size, to_field_var_elements_chunked, Endianness};
 use ark_crypto_primitives::crh::constraints::{CRHGadget as CRHGadgetTrait, TwoToOneCRHGadget};
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
@@ -126,7 +126,8 @@ impl<F: PrimeField, P: Rounds> CRHGadgetTrait<CRH<F, P>, F> for CRHGadget<F, P>
 		parameters: &Self::ParametersVar,
 		input: &[UInt8<F>],
 	) -> Result<Self::OutputVar, SynthesisError> {
-		let f_var_inputs: Vec<FpVar<F>> = to_field_var_elements(input)?;
+		let f_var_inputs: Vec<FpVar<F>> =
+			to_field_var_elements_chunked(input, full_chunk_size::<F>(), Endianness::Little)?;
 		if f_var_inputs.len() > P::WIDTH {
 			panic!(
 				"incorrect input length {:?} for width {:?}",
@@ -253,6 +254,33 @@ mod test {
 		assert_eq!(res, res_var.value().unwrap());
 	}
 
+	#[test]
+	fn test_mimc_native_equality_with_seeded_params() {
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let params = MiMCParameters::<Fq>::from_seed(
+			Fq::from(0),
+			MiMCRounds220_3::ROUNDS,
+			MiMCRounds220_3::WIDTH,
+			b"mimcsponge",
+		);
+
+		let params_var =
+			MiMCParametersVar::new_variable(cs.clone(), || Ok(&params), AllocationMode::Constant)
+				.unwrap();
+
+		let aligned_inp = to_bytes![Fq::from(3u128), Fq::from(1u128), Fq::from(2u128)].unwrap();
+		let aligned_inp_var =
+			Vec::<UInt8<Fq>>::new_input(cs.clone(), || Ok(aligned_inp.clone())).unwrap();
+
+		let res = MiMC220_3::evaluate(&params, &aligned_inp).unwrap();
+		let res_var =
+			<MiMC220Gadget_3 as CRHGadgetTrait<_, _>>::evaluate(&params_var, &aligned_inp_var)
+				.unwrap();
+		assert_eq!(res, res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+	}
+
 	#[test]
 	fn test_mimc_against_circom_fixture() {
 		// > require('circomlib').mimcsponge.multiHash([1,2], 0, 0)
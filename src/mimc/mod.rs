@@ -1,7 +1,8 @@
-use crate::utils::{to_field_elements};
+use crate::utils::{full_chunk_size, to_field_elements_chunked, Endianness};
 use ark_crypto_primitives::{crh::TwoToOneCRH, Error, CRH as CRHTrait};
 use ark_ff::{fields::PrimeField, BigInteger};
 use ark_std::{error::Error as ArkError, marker::PhantomData, rand::Rng, vec::Vec};
+use blake2::{Blake2b, Digest};
 
 #[cfg(feature = "r1cs")]
 pub mod constraints;
@@ -67,11 +68,33 @@ impl<F: PrimeField> MiMCParameters<F> {
 		}
 	}
 
+	/// Builds parameters whose round keys are derived from `seed`, for
+	/// `width` inputs and outputs. The keys are obtained by iteratively
+	/// hashing the seed with Blake2b, `h_1 = H(seed)` and `h_{i+1} = H(h_i)`,
+	/// each digest being reduced modulo the field order.
+	pub fn from_seed(k: F, rounds: usize, width: usize, seed: &[u8]) -> Self {
+		let mut round_keys = Vec::with_capacity(num_round_keys(rounds));
+		let mut digest = Blake2b::digest(seed);
+		for _ in 0..num_round_keys(rounds) {
+			round_keys.push(F::from_le_bytes_mod_order(&digest));
+			digest = Blake2b::digest(&digest);
+		}
+
+		Self::new(k, rounds, width, width, round_keys)
+	}
+
 	pub fn create_round_keys<R: Rng>(_rng: &mut R) -> Vec<F> {
 		todo!();
 	}
 }
 
+/// Number of round keys used by a Feistel permutation of `rounds` rounds.
+/// The first and last rounds have no constant, so the keys are indexed from
+/// the second round to the one before last.
+pub fn num_round_keys(rounds: usize) -> usize {
+	rounds.saturating_sub(2)
+}
+
 pub struct CRH<F: PrimeField, P: Rounds> {
 	field: PhantomData<F>,
 	rounds: PhantomData<P>,
@@ -164,7 +187,8 @@ impl<F: PrimeField, P: Rounds> CRHTrait for CRH<F, P> {
 	fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
 		let eval_time = start_timer!(|| "PoseidonCRH::Eval");
 
-		let f_inputs: Vec<F> = to_field_elements(input)?;
+		let f_inputs: Vec<F> =
+			to_field_elements_chunked(input, full_chunk_size::<F>(), Endianness::Little)?;
 
 		if f_inputs.len() > P::WIDTH {
 			panic!(
@@ -245,4 +269,37 @@ mod test {
 		let mimc_res = <MiMC220 as CRHTrait>::evaluate(&params, &inp).unwrap();
 		println!("{:?}", mimc_res);
 	}
+
+	#[test]
+	fn should_derive_round_keys_from_seed() {
+		let params = MiMCParameters::<Fq>::from_seed(
+			Fq::zero(),
+			MiMCRounds220::ROUNDS,
+			MiMCRounds220::WIDTH,
+			b"mimc",
+		);
+		assert_eq!(params.round_keys.len(), num_round_keys(MiMCRounds220::ROUNDS));
+		assert_eq!(params.num_inputs, MiMCRounds220::WIDTH);
+
+		let same = MiMCParameters::<Fq>::from_seed(
+			Fq::zero(),
+			MiMCRounds220::ROUNDS,
+			MiMCRounds220::WIDTH,
+			b"mimc",
+		);
+		let other = MiMCParameters::<Fq>::from_seed(
+			Fq::zero(),
+			MiMCRounds220::ROUNDS,
+			MiMCRounds220::WIDTH,
+			b"mimd",
+		);
+		assert_eq!(params.round_keys, same.round_keys);
+		assert_ne!(params.round_keys, other.round_keys);
+
+		// Every round reads its key without going out of bounds
+		let inp = to_bytes![Fq::zero(), Fq::from(1u128), Fq::from(2u128)].unwrap();
+		let res = <MiMC220 as CRHTrait>::evaluate(&params, &inp).unwrap();
+		assert_eq!(res, <MiMC220 as CRHTrait>::evaluate(&same, &inp).unwrap());
+		assert_ne!(res, <MiMC220 as CRHTrait>::evaluate(&other, &inp).unwrap());
+	}
 }
@@ -0,0 +1,189 @@
+use super::{Parameters, Window, CRH};
+use crate::Vec;
+use ark_crypto_primitives::crh::{CRHGadget as CRHGadgetTrait, TwoToOneCRHGadget};
+use ark_ec::ProjectiveCurve;
+use ark_ff::Field;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::{borrow::Borrow, marker::PhantomData};
+
+type ConstraintF<C> = <<C as ProjectiveCurve>::BaseField as Field>::BasePrimeField;
+
+pub struct PedersenParametersVar<C: ProjectiveCurve, GG: CurveVar<C, ConstraintF<C>>>
+where
+	for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+	params: Parameters<C>,
+	group_var: PhantomData<GG>,
+}
+
+impl<C, GG> Clone for PedersenParametersVar<C, GG>
+where
+	C: ProjectiveCurve,
+	GG: CurveVar<C, ConstraintF<C>>,
+	for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+	fn clone(&self) -> Self {
+		Self {
+			params: self.params.clone(),
+			group_var: PhantomData,
+		}
+	}
+}
+
+pub struct CRHGadget<C: ProjectiveCurve, GG: CurveVar<C, ConstraintF<C>>, W: Window>
+where
+	for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+	group: PhantomData<*const C>,
+	group_var: PhantomData<*const GG>,
+	window: PhantomData<*const W>,
+}
+
+impl<C, GG, W> CRHGadgetTrait<CRH<C, W>, ConstraintF<C>> for CRHGadget<C, GG, W>
+where
+	C: ProjectiveCurve,
+	GG: CurveVar<C, ConstraintF<C>>,
+	W: Window,
+	for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+	type OutputVar = GG;
+	type ParametersVar = PedersenParametersVar<C, GG>;
+
+	fn evaluate(
+		parameters: &Self::ParametersVar,
+		input: &[UInt8<ConstraintF<C>>],
+	) -> Result<Self::OutputVar, SynthesisError> {
+		let input_size_bits = W::WINDOW_SIZE * W::NUM_WINDOWS;
+		if input.len() * 8 > input_size_bits {
+			return Err(SynthesisError::Unsatisfiable);
+		}
+		assert_eq!(parameters.params.generators.len(), W::NUM_WINDOWS);
+
+		// Pad the input to the full window size
+		let mut padded_input = input.to_vec();
+		padded_input.resize(input_size_bits / 8, UInt8::constant(0u8));
+
+		let mut input_in_bits = Vec::with_capacity(input_size_bits);
+		for byte in &padded_input {
+			input_in_bits.extend(byte.to_bits_le()?);
+		}
+		let input_in_bits = input_in_bits.chunks(W::WINDOW_SIZE);
+
+		GG::precomputed_base_multiscalar_mul_le(&parameters.params.generators, input_in_bits)
+	}
+}
+
+impl<C, GG, W> TwoToOneCRHGadget<CRH<C, W>, ConstraintF<C>> for CRHGadget<C, GG, W>
+where
+	C: ProjectiveCurve,
+	GG: CurveVar<C, ConstraintF<C>>,
+	W: Window,
+	for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+	type OutputVar = GG;
+	type ParametersVar = PedersenParametersVar<C, GG>;
+
+	fn evaluate(
+		parameters: &Self::ParametersVar,
+		left_input: &[UInt8<ConstraintF<C>>],
+		right_input: &[UInt8<ConstraintF<C>>],
+	) -> Result<Self::OutputVar, SynthesisError> {
+		assert_eq!(left_input.len(), right_input.len());
+		let chained: Vec<_> = left_input
+			.iter()
+			.chain(right_input.iter())
+			.cloned()
+			.collect();
+
+		<Self as CRHGadgetTrait<_, _>>::evaluate(parameters, &chained)
+	}
+}
+
+impl<C, GG> AllocVar<Parameters<C>, ConstraintF<C>> for PedersenParametersVar<C, GG>
+where
+	C: ProjectiveCurve,
+	GG: CurveVar<C, ConstraintF<C>>,
+	for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+	fn new_variable<T: Borrow<Parameters<C>>>(
+		_cs: impl Into<Namespace<ConstraintF<C>>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		_mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		// Generators are fixed bases, so they stay native constants
+		let params = f()?.borrow().clone();
+		Ok(Self {
+			params,
+			group_var: PhantomData,
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_crypto_primitives::crh::{TwoToOneCRH, CRH as CRHTrait};
+	use ark_ec::ProjectiveCurve;
+	use ark_ed_on_bls12_381::{constraints::EdwardsVar, EdwardsProjective, Fq};
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{test_rng, UniformRand};
+
+	#[derive(Clone)]
+	struct TestWindow;
+
+	impl Window for TestWindow {
+		const NUM_WINDOWS: usize = 64;
+		const WINDOW_SIZE: usize = 4;
+	}
+
+	type TestCRH = CRH<EdwardsProjective, TestWindow>;
+	type TestCRHGadget = CRHGadget<EdwardsProjective, EdwardsVar, TestWindow>;
+
+	#[test]
+	fn should_match_native_pedersen() {
+		let rng = &mut test_rng();
+		let params = TestCRH::setup_with_domain(b"arkworks-gadgets-pedersen");
+
+		// Aligned and unaligned input
+		for len in [32usize, 7].iter() {
+			let input: Vec<u8> = (0..*len).map(|_| u8::rand(rng)).collect();
+			let res = <TestCRH as CRHTrait>::evaluate(&params, &input).unwrap();
+
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let input_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(input)).unwrap();
+			let params_var =
+				PedersenParametersVar::new_constant(cs.clone(), params.clone()).unwrap();
+			let res_var =
+				<TestCRHGadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &input_var)
+					.unwrap();
+
+			assert_eq!(res, res_var.value().unwrap().into_affine());
+			assert!(cs.is_satisfied().unwrap());
+		}
+	}
+
+	#[test]
+	fn should_match_native_two_to_one_pedersen() {
+		let rng = &mut test_rng();
+		let params = TestCRH::setup_with_domain(b"arkworks-gadgets-pedersen");
+
+		let left: Vec<u8> = (0..16).map(|_| u8::rand(rng)).collect();
+		let right: Vec<u8> = (0..16).map(|_| u8::rand(rng)).collect();
+		let res = <TestCRH as TwoToOneCRH>::evaluate(&params, &left, &right).unwrap();
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let left_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(left)).unwrap();
+		let right_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(right)).unwrap();
+		let params_var = PedersenParametersVar::new_constant(cs.clone(), params).unwrap();
+		let res_var = <TestCRHGadget as TwoToOneCRHGadget<_, _>>::evaluate(
+			&params_var,
+			&left_var,
+			&right_var,
+		)
+		.unwrap();
+
+		assert_eq!(res, res_var.value().unwrap().into_affine());
+		assert!(cs.is_satisfied().unwrap());
+	}
+}
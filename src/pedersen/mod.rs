@@ -0,0 +1,236 @@
+use crate::{error::GadgetError, Vec};
+use ark_crypto_primitives::{crh::TwoToOneCRH, Error, CRH as CRHTrait};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::Zero;
+use ark_std::{marker::PhantomData, rand::Rng};
+use blake2::{Blake2s, Digest};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+pub trait Window: Clone {
+	const WINDOW_SIZE: usize;
+	const NUM_WINDOWS: usize;
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct Parameters<C: ProjectiveCurve> {
+	pub generators: Vec<Vec<C>>,
+}
+
+pub struct CRH<C: ProjectiveCurve, W: Window> {
+	group: PhantomData<C>,
+	window: PhantomData<W>,
+}
+
+impl<C: ProjectiveCurve, W: Window> CRH<C, W> {
+	pub fn create_generators<R: Rng>(rng: &mut R) -> Vec<Vec<C>> {
+		let mut generators_powers = Vec::new();
+		for _ in 0..W::NUM_WINDOWS {
+			generators_powers.push(Self::generator_powers(W::WINDOW_SIZE, rng));
+		}
+		generators_powers
+	}
+
+	pub fn generator_powers<R: Rng>(num_powers: usize, rng: &mut R) -> Vec<C> {
+		powers_of(C::rand(rng), num_powers)
+	}
+
+	/// Deterministically derives the parameters from a domain separation
+	/// string, so that independent parties end up with the same generators.
+	/// The base of each window comes from `generate_pedersen_generators`.
+	pub fn setup_with_domain(domain: &[u8]) -> Parameters<C> {
+		Parameters {
+			generators: generate_pedersen_generators::<C>(domain, W::NUM_WINDOWS)
+				.into_iter()
+				.map(|base| powers_of(base, W::WINDOW_SIZE))
+				.collect(),
+		}
+	}
+}
+
+/// `base, 2 * base, 4 * base, ...`, `num_powers` of them
+fn powers_of<C: ProjectiveCurve>(mut base: C, num_powers: usize) -> Vec<C> {
+	let mut powers = Vec::with_capacity(num_powers);
+	for _ in 0..num_powers {
+		powers.push(base);
+		base.double_in_place();
+	}
+	powers
+}
+
+/// Derives `count` nothing-up-my-sleeve generators from `domain` by
+/// try-and-increment. The Blake2s digest of the domain, the index of the
+/// generator and a counter is decoded as a point, incrementing the counter
+/// until it is on the curve, then multiplied by the cofactor to land in the
+/// prime order subgroup. As the points come out of a hash, nobody knows the
+/// discrete logarithms between them.
+pub fn generate_pedersen_generators<C: ProjectiveCurve>(domain: &[u8], count: usize) -> Vec<C> {
+	(0..count as u64)
+		.map(|index| {
+			let mut counter = 0u64;
+			loop {
+				let digest = Blake2s::new()
+					.chain(domain)
+					.chain(&index.to_le_bytes())
+					.chain(&counter.to_le_bytes())
+					.finalize();
+				if let Some(point) = C::Affine::from_random_bytes(&digest) {
+					let generator = point.mul_by_cofactor_to_projective();
+					if !generator.is_zero() {
+						return generator;
+					}
+				}
+				counter += 1;
+			}
+		})
+		.collect()
+}
+
+impl<C: ProjectiveCurve, W: Window> CRHTrait for CRH<C, W> {
+	type Output = C::Affine;
+	type Parameters = Parameters<C>;
+
+	const INPUT_SIZE_BITS: usize = W::WINDOW_SIZE * W::NUM_WINDOWS;
+
+	fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+		Ok(Parameters {
+			generators: Self::create_generators(rng),
+		})
+	}
+
+	fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+		if (input.len() * 8) > Self::INPUT_SIZE_BITS {
+			return Err(GadgetError::InputTooLong {
+				len: input.len(),
+				max: Self::INPUT_SIZE_BITS / 8,
+			}
+			.into());
+		}
+		assert_eq!(parameters.generators.len(), W::NUM_WINDOWS);
+
+		// Pad the input to the full window size
+		let mut padded_input = input.to_vec();
+		padded_input.resize(Self::INPUT_SIZE_BITS / 8, 0u8);
+
+		// Compute sum of h_i^{m_i} for all i.
+		let bits = bytes_to_bits(&padded_input);
+		let result = bits
+			.chunks(W::WINDOW_SIZE)
+			.zip(&parameters.generators)
+			.map(|(bits, generator_powers)| {
+				let mut encoded = C::zero();
+				for (bit, base) in bits.iter().zip(generator_powers.iter()) {
+					if *bit {
+						encoded += base;
+					}
+				}
+				encoded
+			})
+			.sum::<C>();
+
+		Ok(result.into())
+	}
+}
+
+impl<C: ProjectiveCurve, W: Window> TwoToOneCRH for CRH<C, W> {
+	type Output = C::Affine;
+	type Parameters = Parameters<C>;
+
+	const LEFT_INPUT_SIZE_BITS: usize = W::WINDOW_SIZE * W::NUM_WINDOWS / 2;
+	const RIGHT_INPUT_SIZE_BITS: usize = Self::LEFT_INPUT_SIZE_BITS;
+
+	fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+		<Self as CRHTrait>::setup(rng)
+	}
+
+	/// Concatenates the inputs and hashes them. Left and right inputs
+	/// must be of equal length, and fail to hash when longer than
+	/// `LEFT_INPUT_SIZE_BITS`.
+	fn evaluate(
+		parameters: &Self::Parameters,
+		left_input: &[u8],
+		right_input: &[u8],
+	) -> Result<Self::Output, Error> {
+		assert_eq!(left_input.len(), right_input.len());
+		let chained: Vec<_> = left_input
+			.iter()
+			.chain(right_input.iter())
+			.cloned()
+			.collect();
+
+		<Self as CRHTrait>::evaluate(parameters, &chained)
+	}
+}
+
+pub fn bytes_to_bits(bytes: &[u8]) -> Vec<bool> {
+	let mut bits = Vec::with_capacity(bytes.len() * 8);
+	for byte in bytes {
+		for i in 0..8 {
+			let bit = (*byte >> i) & 1;
+			bits.push(bit == 1)
+		}
+	}
+	bits
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_ed_on_bls12_381::{EdwardsAffine, EdwardsProjective};
+
+	#[derive(Clone)]
+	struct TestWindow;
+
+	impl Window for TestWindow {
+		const NUM_WINDOWS: usize = 64;
+		const WINDOW_SIZE: usize = 4;
+	}
+
+	type TestCRH = CRH<EdwardsProjective, TestWindow>;
+
+	#[test]
+	fn should_derive_same_params_from_domain() {
+		let params_a = TestCRH::setup_with_domain(b"arkworks-gadgets-pedersen");
+		let params_b = TestCRH::setup_with_domain(b"arkworks-gadgets-pedersen");
+		let params_c = TestCRH::setup_with_domain(b"another-domain");
+
+		assert_eq!(params_a.generators, params_b.generators);
+		assert_ne!(params_a.generators, params_c.generators);
+	}
+
+	#[test]
+	fn should_reject_oversized_input() {
+		let params = TestCRH::setup_with_domain(b"arkworks-gadgets-pedersen");
+		let max = TestCRH::INPUT_SIZE_BITS / 8;
+
+		assert!(<TestCRH as CRHTrait>::evaluate(&params, &vec![1u8; max]).is_ok());
+		let err = <TestCRH as CRHTrait>::evaluate(&params, &vec![1u8; max + 1]).unwrap_err();
+		assert_eq!(err.to_string(), "33 inputs exceed the maximum of 32");
+
+		let half = vec![1u8; max / 2 + 1];
+		assert!(<TestCRH as TwoToOneCRH>::evaluate(&params, &half, &half).is_err());
+	}
+
+	#[test]
+	fn should_generate_distinct_generators_on_curve() {
+		let generators =
+			generate_pedersen_generators::<EdwardsProjective>(b"arkworks-gadgets-pedersen", 16);
+		assert_eq!(
+			generators,
+			generate_pedersen_generators::<EdwardsProjective>(b"arkworks-gadgets-pedersen", 16)
+		);
+		assert_ne!(
+			generators,
+			generate_pedersen_generators::<EdwardsProjective>(b"another-domain", 16)
+		);
+
+		for (i, generator) in generators.iter().enumerate() {
+			let affine: EdwardsAffine = generator.into_affine();
+			assert!(affine.is_on_curve());
+			assert!(affine.is_in_correct_subgroup_assuming_on_curve());
+			assert!(!generator.is_zero());
+			assert!(generators[i + 1..].iter().all(|other| other != generator));
+		}
+	}
+}
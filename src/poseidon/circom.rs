@@ -0,0 +1,82 @@
+//! Poseidon hash of two field elements matching circomlib's `poseidon(2)`
+//! on BN254.
+//!
+//! The round constants and MDS matrix are the `poseidon_bn254_x5_3` ones,
+//! which circomlib also uses. The differences with `CRH` are in how inputs
+//! enter the state:
+//! - `CRH` reads its input bytes into field elements, zero-padding them, and
+//!   places them from the first element of the state.
+//! - circomlib takes field elements as they are and places them after a zero
+//!   capacity element, so the state is `[0, left, right]`.
+//!
+//! Both return the first element of the permuted state, so `hash_two(l, r)`
+//! equals `CRH::evaluate` over the bytes of `[0, l, r]`, which is how the
+//! digest can be computed in a circuit with `CRHGadget`. Trees hashed with
+//! `hash_two` don't match trees hashed with `PoseidonHasher`.
+use super::{sbox::PoseidonSbox, PoseidonError, PoseidonParameters, Rounds, CRH};
+use crate::utils::{get_mds_poseidon_bn254_x5_3, get_rounds_poseidon_bn254_x5_3};
+use ark_crypto_primitives::Error;
+use ark_ff::PrimeField;
+use ark_std::vec;
+
+#[derive(Default, Clone)]
+pub struct CircomRounds;
+
+impl Rounds for CircomRounds {
+	const FULL_ROUNDS: usize = 8;
+	const PARTIAL_ROUNDS: usize = 57;
+	const SBOX: PoseidonSbox = PoseidonSbox::Exponentiation(5);
+	const WIDTH: usize = 3;
+}
+
+/// Parameters of circomlib's `poseidon(2)`, to be used over the BN254 scalar
+/// field
+pub fn circom_params<F: PrimeField>() -> PoseidonParameters<F> {
+	PoseidonParameters::from_rounds::<CircomRounds>(
+		get_rounds_poseidon_bn254_x5_3(),
+		get_mds_poseidon_bn254_x5_3(),
+	)
+}
+
+/// Hashes `left` and `right` the way circomlib's `poseidon(2)` does
+pub fn hash_two<F: PrimeField>(
+	params: &PoseidonParameters<F>,
+	left: F,
+	right: F,
+) -> Result<F, Error> {
+	let state = CRH::<F, CircomRounds>::permute(params, vec![F::zero(), left, right])?;
+	Ok(state.get(0).cloned().ok_or(PoseidonError::InvalidInputs)?)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::field_hasher::{FieldHasher, PoseidonHasher};
+	use ark_bn254::Fr;
+	use ark_crypto_primitives::CRH as CRHTrait;
+	use ark_ff::{to_bytes, Zero};
+	use ark_std::str::FromStr;
+
+	#[test]
+	fn should_match_circomlib_poseidon() {
+		let params = circom_params::<Fr>();
+		let left = Fr::from(1u64);
+		let right = Fr::from(2u64);
+
+		// circomlibjs: poseidon([1, 2])
+		let expected = Fr::from_str(
+			"7853200120776062878684798364095072458815029376092732009249414926327459813530",
+		)
+		.unwrap();
+		let res = hash_two(&params, left, right).unwrap();
+		assert_eq!(res, expected);
+
+		let bytes = to_bytes![Fr::zero(), left, right].unwrap();
+		let crh_res = <CRH<Fr, CircomRounds> as CRHTrait>::evaluate(&params, &bytes).unwrap();
+		assert_eq!(crh_res, expected);
+
+		// The default parameterization puts the inputs first in the state
+		let hasher = PoseidonHasher::<Fr, CircomRounds>::new(params);
+		assert_ne!(hasher.hash_two(&left, &right).unwrap(), expected);
+	}
+}
@@ -1,5 +1,5 @@
 use super::{sbox::constraints::SboxConstraints, PoseidonParameters, Rounds, CRH};
-use crate::utils::to_field_var_elements;
+use crate::utils::{full_chunk_size, safe_chunk_size, to_field_var_elements_chunked, Endianness};
 use ark_crypto_primitives::crh::constraints::{CRHGadget as CRHGadgetTrait, TwoToOneCRHGadget};
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
@@ -20,17 +20,42 @@ pub struct PoseidonParametersVar<F: PrimeField> {
 	pub mds_matrix: Vec<Vec<FpVar<F>>>,
 }
 
+impl<F: PrimeField> PoseidonParametersVar<F> {
+	/// Fails with `SynthesisError::Unsatisfiable` unless the MDS matrix is a
+	/// `P::WIDTH` x `P::WIDTH` matrix, so that parameters of another width
+	/// aren't used to hash with `P`
+	pub fn check_width<P: Rounds>(&self) -> Result<(), SynthesisError> {
+		if self.mds_matrix.len() != P::WIDTH
+			|| self.mds_matrix.iter().any(|row| row.len() != P::WIDTH)
+		{
+			return Err(SynthesisError::Unsatisfiable);
+		}
+		Ok(())
+	}
+}
+
 pub struct CRHGadget<F: PrimeField, P: Rounds> {
 	field: PhantomData<F>,
 	params: PhantomData<P>,
 }
 
 impl<F: PrimeField, P: Rounds> CRHGadget<F, P> {
-	fn permute(
+	/// Runs the Poseidon permutation over a full state, for sponges and
+	/// duplexes built outside of `evaluate`. The state must hold `P::WIDTH`
+	/// elements, capacity first, and the returned state holds as many. Matches
+	/// the native `CRH::permute`, failing as well when there are fewer round
+	/// keys than the rounds read.
+	pub fn permute(
 		parameters: &PoseidonParametersVar<F>,
 		mut state: Vec<FpVar<F>>,
 	) -> Result<Vec<FpVar<F>>, SynthesisError> {
+		parameters.check_width::<P>()?;
 		let width = P::WIDTH;
+		if state.len() != width
+			|| parameters.round_keys.len() < width * (P::FULL_ROUNDS + P::PARTIAL_ROUNDS)
+		{
+			return Err(SynthesisError::Unsatisfiable);
+		}
 
 		let mut round_keys_offset = 0;
 
@@ -39,7 +64,7 @@ impl<F: PrimeField, P: Rounds> CRHGadget<F, P> {
 			// Substitution (S-box) layer
 			for i in 0..width {
 				state[i] += &parameters.round_keys[round_keys_offset];
-				state[i] = P::SBOX.synthesize_sbox(&state[i])?;
+				state[i] = P::FULL_SBOX.synthesize_sbox(&state[i])?;
 				round_keys_offset += 1;
 			}
 			// Apply linear layer
@@ -55,7 +80,7 @@ impl<F: PrimeField, P: Rounds> CRHGadget<F, P> {
 			}
 			// apply Sbox to only 1 element of the state.
 			// Here the last one is chosen but the choice is arbitrary.
-			state[0] = P::SBOX.synthesize_sbox(&state[0])?;
+			state[0] = P::PARTIAL_SBOX.synthesize_sbox(&state[0])?;
 			// Linear layer
 			state = Self::apply_linear_layer(&state, &parameters.mds_matrix);
 		}
@@ -65,7 +90,7 @@ impl<F: PrimeField, P: Rounds> CRHGadget<F, P> {
 			// Substitution (S-box) layer
 			for i in 0..width {
 				state[i] += &parameters.round_keys[round_keys_offset];
-				state[i] = P::SBOX.synthesize_sbox(&state[i])?;
+				state[i] = P::FULL_SBOX.synthesize_sbox(&state[i])?;
 				round_keys_offset += 1;
 			}
 			// Linear layer
@@ -75,6 +100,7 @@ impl<F: PrimeField, P: Rounds> CRHGadget<F, P> {
 		Ok(state)
 	}
 
+	/// Multiplies the state by the MDS matrix
 	fn apply_linear_layer(state: &Vec<FpVar<F>>, mds_matrix: &Vec<Vec<FpVar<F>>>) -> Vec<FpVar<F>> {
 		let mut new_state: Vec<FpVar<F>> = Vec::new();
 		for i in 0..state.len() {
@@ -87,6 +113,72 @@ impl<F: PrimeField, P: Rounds> CRHGadget<F, P> {
 		}
 		new_state
 	}
+
+	/// Gadget of `CRH::evaluate_with`
+	pub fn evaluate_with(
+		parameters: &PoseidonParametersVar<F>,
+		input: &[UInt8<F>],
+		endianness: Endianness,
+	) -> Result<FpVar<F>, SynthesisError> {
+		let f_var_inputs: Vec<FpVar<F>> =
+			to_field_var_elements_chunked(input, full_chunk_size::<F>(), endianness)?;
+		Self::hash_elements(parameters, &f_var_inputs)
+	}
+
+	/// Hashes `bits`, read in little endian order, without repacking them
+	/// into bytes. They are packed into field elements of `safe_chunk_size`
+	/// bytes, so that no element wraps around the modulus, and the digest
+	/// matches hashing the same bits as bytes chunked that way.
+	///
+	/// `evaluate` packs bytes into `full_chunk_size` chunks instead, so its
+	/// digest only matches this one for inputs within a single safe chunk.
+	/// On those full chunks the two sides of `evaluate` also diverge: the
+	/// native `CRH::evaluate` fails on a chunk that isn't smaller than the
+	/// modulus, while the gadget reduces it modulo the field.
+	pub fn evaluate_bits(
+		parameters: &PoseidonParametersVar<F>,
+		bits: &[Boolean<F>],
+	) -> Result<FpVar<F>, SynthesisError> {
+		let f_var_inputs = bits
+			.chunks(safe_chunk_size::<F>() * 8)
+			.map(Boolean::le_bits_to_fp_var)
+			.collect::<Result<Vec<_>, _>>()?;
+		Self::hash_elements(parameters, &f_var_inputs)
+	}
+
+	/// Gadget of `CRH::hash_elements`, absorbing `inputs` into the rate of a
+	/// zero state and permuting it after each block of the rate. Fails with
+	/// `SynthesisError::Unsatisfiable` for inputs longer than the rate when
+	/// `P::CAPACITY` is 0.
+	pub fn hash_elements(
+		parameters: &PoseidonParametersVar<F>,
+		inputs: &[FpVar<F>],
+	) -> Result<FpVar<F>, SynthesisError> {
+		let rate = P::WIDTH - P::CAPACITY;
+		let mut state = vec![FpVar::zero(); P::WIDTH];
+		if inputs.len() > rate {
+			if P::CAPACITY == 0 || rate == 0 {
+				return Err(SynthesisError::Unsatisfiable);
+			}
+			state[0] = FpVar::constant(F::from(inputs.len() as u64));
+		}
+
+		let mut offset = 0;
+		loop {
+			state[P::CAPACITY..]
+				.iter_mut()
+				.zip(&inputs[offset..])
+				.for_each(|(b, l_b)| *b += l_b);
+			state = Self::permute(&parameters, state)?;
+
+			offset += rate;
+			if offset >= inputs.len() {
+				break;
+			}
+		}
+
+		state.get(0).cloned().ok_or(SynthesisError::AssignmentMissing)
+	}
 }
 
 // https://github.com/arkworks-rs/r1cs-std/blob/master/src/bits/uint8.rs#L343
@@ -98,23 +190,7 @@ impl<F: PrimeField, P: Rounds> CRHGadgetTrait<CRH<F, P>, F> for CRHGadget<F, P>
 		parameters: &Self::ParametersVar,
 		input: &[UInt8<F>],
 	) -> Result<Self::OutputVar, SynthesisError> {
-		let f_var_inputs: Vec<FpVar<F>> = to_field_var_elements(input)?;
-		if f_var_inputs.len() > P::WIDTH {
-			panic!(
-				"incorrect input length {:?} for width {:?}",
-				f_var_inputs.len(),
-				P::WIDTH,
-			);
-		}
-
-		let mut buffer = vec![FpVar::zero(); P::WIDTH];
-		buffer
-			.iter_mut()
-			.zip(f_var_inputs)
-			.for_each(|(b, l_b)| *b = l_b);
-
-		let result = Self::permute(&parameters, buffer);
-		result.map(|x| x.get(0).cloned().ok_or(SynthesisError::AssignmentMissing))?
+		Self::evaluate_with(parameters, input, Endianness::Little)
 	}
 }
 
@@ -165,17 +241,56 @@ impl<F: PrimeField> AllocVar<PoseidonParameters<F>, F> for PoseidonParametersVar
 	}
 }
 
+/// Round-trips `params` through `to_bytes` and `from_bytes`, allocates the
+/// result as constants and asserts that the gadget hashes `input` to the
+/// native `CRH::evaluate` of the original parameters. Lets contributors of a
+/// new constant set catch misordered round keys or MDS entries.
+#[cfg(any(test, feature = "test-utils"))]
+pub fn assert_params_round_trip<F: PrimeField, P: Rounds>(
+	params: &PoseidonParameters<F>,
+	input: &[u8],
+) {
+	use ark_crypto_primitives::CRH as CRHTrait;
+	use ark_relations::r1cs::ConstraintSystem;
+
+	let decoded = PoseidonParameters::<F>::from_bytes(&params.to_bytes()).unwrap();
+	assert_eq!(decoded.round_keys, params.round_keys, "round keys changed");
+	assert_eq!(decoded.mds_matrix, params.mds_matrix, "mds matrix changed");
+
+	let cs = ConstraintSystem::<F>::new_ref();
+	let params_var = PoseidonParametersVar::new_variable(
+		cs.clone(),
+		|| Ok(&decoded),
+		AllocationMode::Constant,
+	)
+	.unwrap();
+	let input_var = Vec::<UInt8<F>>::new_witness(cs.clone(), || Ok(input.to_vec())).unwrap();
+
+	let res = <CRH<F, P> as CRHTrait>::evaluate(params, input).unwrap();
+	let res_var =
+		<CRHGadget<F, P> as CRHGadgetTrait<_, _>>::evaluate(&params_var, &input_var).unwrap();
+	assert_eq!(res, res_var.value().unwrap(), "gadget and native hashes differ");
+	assert!(cs.is_satisfied().unwrap());
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
-	use ark_crypto_primitives::crh::CRH as CRHTrait;
+	use ark_crypto_primitives::crh::{self, CRH as CRHTrait};
 	use ark_ed_on_bls12_381::Fq;
 	use ark_ff::{to_bytes, Zero};
 	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{rand::Rng, test_rng, UniformRand};
 
 	use crate::{
-		poseidon::sbox::PoseidonSbox,
-		utils::{get_mds_poseidon_bls381_x5_3, get_rounds_poseidon_bls381_x5_3},
+		error::GadgetError,
+		poseidon::{sbox::PoseidonSbox, Poseidon},
+		setup::common::PoseidonRounds3Capacity1,
+		utils::{
+			get_mds_poseidon_bls381_x5_3,
+			get_rounds_poseidon_bls381_x5_3,
+			to_field_elements_chunked,
+		},
 	};
 
 	#[derive(Default, Clone)]
@@ -234,4 +349,511 @@ mod test {
 		.unwrap();
 		assert_eq!(res, res_var.value().unwrap());
 	}
+
+	#[test]
+	fn test_poseidon_native_equality_for_all_lengths() {
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+
+		for len in 1..=64 {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+			let mut inp: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+			// Keep the most significant byte of full chunks small, so every chunk
+			// is a canonical field element
+			for i in (31..len).step_by(32) {
+				inp[i] &= 0x1f;
+			}
+			let inp_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(inp.clone())).unwrap();
+
+			let res = PoseidonCRH3::evaluate(&params, &inp).unwrap();
+			let res_var =
+				<PoseidonCRH3Gadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_var)
+					.unwrap();
+			assert_eq!(res, res_var.value().unwrap(), "length {}", len);
+			assert!(cs.is_satisfied().unwrap());
+		}
+	}
+
+	#[test]
+	fn test_poseidon_bits_native_equality() {
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::from_rounds::<PoseidonRounds3>(rounds, mds);
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+		// Spanning two safe chunks of 31 bytes
+		let inp: Vec<u8> = (0..40).map(|_| rng.gen()).collect();
+		let inp_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(inp.clone())).unwrap();
+		let bits = inp_var.to_bits_le().unwrap();
+
+		let chunks = to_field_elements_chunked(&inp, safe_chunk_size::<Fq>(), Endianness::Little)
+			.unwrap();
+		let res = Poseidon::hash(&params, &chunks).unwrap();
+		let res_var = PoseidonCRH3Gadget::evaluate_bits(&params_var, &bits).unwrap();
+		assert_eq!(res, res_var.value().unwrap());
+
+		// Within a single chunk, the bits hash like the bytes do
+		let res_var = PoseidonCRH3Gadget::evaluate_bits(&params_var, &bits[..16 * 8]).unwrap();
+		let bytes_res_var =
+			<PoseidonCRH3Gadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_var[..16])
+				.unwrap();
+		assert_eq!(res_var.value().unwrap(), bytes_res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+
+		// One bit more than three chunks needs a fourth element, past the rate
+		let too_many = vec![Boolean::FALSE; safe_chunk_size::<Fq>() * 8 * 3 + 1];
+		assert!(PoseidonCRH3Gadget::evaluate_bits(&params_var, &too_many).is_err());
+	}
+
+	#[test]
+	fn test_poseidon_native_equality_big_endian() {
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let rounds = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+		let inp: Vec<u8> = vec![1, 2, 3, 4, 5, 6];
+		let inp_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(inp.clone())).unwrap();
+
+		let res_le = PoseidonCRH3::evaluate_with(&params, &inp, Endianness::Little).unwrap();
+		let res_be = PoseidonCRH3::evaluate_with(&params, &inp, Endianness::Big).unwrap();
+		assert_eq!(res_le, PoseidonCRH3::evaluate(&params, &inp).unwrap());
+		assert_ne!(res_le, res_be);
+
+		let res_var =
+			PoseidonCRH3Gadget::evaluate_with(&params_var, &inp_var, Endianness::Big).unwrap();
+		assert_eq!(res_be, res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn test_poseidon_capacity() {
+		#[derive(Default, Clone)]
+		struct PoseidonRounds3Capacity2;
+
+		impl Rounds for PoseidonRounds3Capacity2 {
+			const CAPACITY: usize = 2;
+			const FULL_ROUNDS: usize = 8;
+			const PARTIAL_ROUNDS: usize = 57;
+			const SBOX: PoseidonSbox = PoseidonSbox::Exponentiation(5);
+			const WIDTH: usize = 3;
+		}
+
+		type CRHCapacity1 = CRH<Fq, PoseidonRounds3Capacity1>;
+		type CRHCapacity1Gadget = CRHGadget<Fq, PoseidonRounds3Capacity1>;
+		type CRHCapacity2 = CRH<Fq, PoseidonRounds3Capacity2>;
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let rounds = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+		// A rate sized input fits, and lands after the capacity element
+		let inp = to_bytes![Fq::from(1u128), Fq::from(2u128)].unwrap();
+		let res = CRHCapacity1::evaluate(&params, &inp).unwrap();
+		assert_ne!(res, PoseidonCRH3::evaluate(&params, &inp).unwrap());
+		let padded = to_bytes![Fq::zero(), Fq::from(1u128), Fq::from(2u128)].unwrap();
+		assert_eq!(res, PoseidonCRH3::evaluate(&params, &padded).unwrap());
+
+		let inp_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(inp.clone())).unwrap();
+		let res_var =
+			<CRHCapacity1Gadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_var).unwrap();
+		assert_eq!(res, res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+
+		let single = to_bytes![Fq::from(1u128)].unwrap();
+		assert_ne!(
+			CRHCapacity2::evaluate(&params, &single).unwrap(),
+			CRHCapacity1::evaluate(&params, &single).unwrap()
+		);
+	}
+
+	#[test]
+	fn test_poseidon_sponge_native_equality_for_long_inputs() {
+		let rounds = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+		type CRHCapacity1 = CRH<Fq, PoseidonRounds3Capacity1>;
+		type CRHCapacity1Gadget = CRHGadget<Fq, PoseidonRounds3Capacity1>;
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+		// Ten elements take five blocks of the rate of two
+		let elements = (0..10u128).map(Fq::from).collect::<Vec<_>>();
+		let inp = to_bytes![elements].unwrap();
+		let inp_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(inp.clone())).unwrap();
+
+		let res = CRHCapacity1::evaluate(&params, &inp).unwrap();
+		let res_var =
+			<CRHCapacity1Gadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_var).unwrap();
+		assert_eq!(res, res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+
+		// Every block counts, not only the last one
+		let mut other = elements.clone();
+		other[0] += Fq::from(1u128);
+		let other_res = CRHCapacity1::evaluate(&params, &to_bytes![other].unwrap()).unwrap();
+		assert_ne!(res, other_res);
+
+		// The length keeps a long input apart from its zero-padded extension
+		let mut padded = elements.clone();
+		padded.push(Fq::zero());
+		let padded_res = CRHCapacity1::evaluate(&params, &to_bytes![padded].unwrap()).unwrap();
+		assert_ne!(res, padded_res);
+
+		// Without capacity, inputs longer than the width are rejected
+		assert!(PoseidonCRH3::evaluate(&params, &inp).is_err());
+		assert!(
+			<PoseidonCRH3Gadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_var).is_err()
+		);
+	}
+
+	#[test]
+	fn test_poseidon_permute_native_equality() {
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+		let state = (0..3).map(|_| Fq::rand(rng)).collect::<Vec<_>>();
+		let state_var = Vec::<FpVar<Fq>>::new_witness(cs.clone(), || Ok(state.clone())).unwrap();
+
+		let res = PoseidonCRH3::permute(&params, state.clone()).unwrap();
+		let res_var = PoseidonCRH3Gadget::permute(&params_var, state_var).unwrap();
+		assert_eq!(res, res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+
+		// The state must span the whole width
+		let short_var =
+			Vec::<FpVar<Fq>>::new_witness(cs.clone(), || Ok(state[..2].to_vec())).unwrap();
+		assert!(PoseidonCRH3Gadget::permute(&params_var, short_var).is_err());
+		assert!(PoseidonCRH3::permute(&params, state[..2].to_vec()).is_err());
+
+		// Parameters built with `new` aren't checked, so a missing round key must
+		// fail the permutation instead of panicking
+		let mut short_keys = params.clone();
+		short_keys.round_keys.pop();
+		let short_keys_var = PoseidonParametersVar::new_constant(cs.clone(), &short_keys).unwrap();
+		let state_var = Vec::<FpVar<Fq>>::new_witness(cs, || Ok(state.clone())).unwrap();
+		assert!(PoseidonCRH3Gadget::permute(&short_keys_var, state_var).is_err());
+		assert!(matches!(
+			PoseidonCRH3::permute(&short_keys, state),
+			Err(GadgetError::RoundKeysMismatch {
+				expected: 195,
+				actual: 194
+			})
+		));
+	}
+
+	#[test]
+	fn test_poseidon_mixed_sbox_native_equality() {
+		#[derive(Default, Clone)]
+		struct PoseidonRounds3Mixed;
+
+		impl Rounds for PoseidonRounds3Mixed {
+			const FULL_ROUNDS: usize = 8;
+			const FULL_SBOX: PoseidonSbox = PoseidonSbox::Inverse;
+			const PARTIAL_ROUNDS: usize = 57;
+			const SBOX: PoseidonSbox = PoseidonSbox::Exponentiation(5);
+			const WIDTH: usize = 3;
+		}
+
+		type CRHMixed = CRH<Fq, PoseidonRounds3Mixed>;
+		type CRHMixedGadget = CRHGadget<Fq, PoseidonRounds3Mixed>;
+
+		let rng = &mut test_rng();
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let rounds = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::from_rounds::<PoseidonRounds3Mixed>(rounds, mds);
+		assert_eq!(params.sbox, PoseidonSbox::Inverse);
+		assert_eq!(params.partial_sbox, PoseidonSbox::Exponentiation(5));
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+		let inputs = [Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let inp = to_bytes![inputs].unwrap();
+		let inp_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(inp.clone())).unwrap();
+
+		let res = CRHMixed::evaluate(&params, &inp).unwrap();
+		let res_var =
+			<CRHMixedGadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_var).unwrap();
+		assert_eq!(res, res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+
+		// The runtime hasher reads both S-boxes from the parameters
+		assert_eq!(res, crate::poseidon::Poseidon::hash(&params, &inputs).unwrap());
+		// And the mix differs from using exponent 5 everywhere
+		assert_ne!(res, PoseidonCRH3::evaluate(&params, &inp).unwrap());
+	}
+
+	#[cfg(feature = "poseidon_bls381_x5_5")]
+	#[test]
+	fn test_poseidon_rejects_params_of_other_width() {
+		use crate::utils::{get_mds_poseidon_bls381_x5_5, get_rounds_poseidon_bls381_x5_5};
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let rounds = get_rounds_poseidon_bls381_x5_5::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_5::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+		assert_eq!(
+			params_var.check_width::<PoseidonRounds3>(),
+			Err(SynthesisError::Unsatisfiable)
+		);
+
+		let inp = to_bytes![Fq::zero(), Fq::from(1u128)].unwrap();
+		let inp_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(inp)).unwrap();
+		let res = <PoseidonCRH3Gadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_var);
+		assert_eq!(res.err(), Some(SynthesisError::Unsatisfiable));
+	}
+
+	#[test]
+	fn test_poseidon_linear_layer_has_no_constraints() {
+		let rng = &mut test_rng();
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let rounds = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds.clone());
+		let params_var = PoseidonParametersVar::new_witness(cs.clone(), || Ok(&params)).unwrap();
+
+		let state: Vec<Fq> = (0..3).map(|_| Fq::rand(rng)).collect();
+		let state_var = Vec::<FpVar<Fq>>::new_witness(cs.clone(), || Ok(state.clone())).unwrap();
+
+		let before = cs.num_constraints();
+		let res_var = PoseidonCRH3Gadget::apply_linear_layer(&state_var, &params_var.mds_matrix);
+		assert_eq!(cs.num_constraints(), before);
+
+		for (row, res) in mds.iter().zip(res_var) {
+			let expected = row
+				.iter()
+				.zip(state.iter())
+				.fold(Fq::zero(), |acc, (m, x)| acc + *m * x);
+			assert_eq!(res.value().unwrap(), expected);
+		}
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn test_poseidon_two_to_one_native_equality() {
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bls381_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bls381_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+
+		for _ in 0..10 {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+			let left = to_bytes![Fq::rand(rng)].unwrap();
+			let right = to_bytes![Fq::rand(rng)].unwrap();
+			let left_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(left.clone())).unwrap();
+			let right_var =
+				Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(right.clone())).unwrap();
+
+			// Not imported, as it would make `CRHTrait::evaluate` calls ambiguous
+			let res = <PoseidonCRH3 as crh::TwoToOneCRH>::evaluate(&params, &left, &right).unwrap();
+			let res_var = <PoseidonCRH3Gadget as TwoToOneCRHGadget<_, _>>::evaluate(
+				&params_var,
+				&left_var,
+				&right_var,
+			)
+			.unwrap();
+			assert_eq!(res, res_var.value().unwrap());
+			assert!(cs.is_satisfied().unwrap());
+
+			// Swapping the children changes the digest
+			let swapped =
+				<PoseidonCRH3 as crh::TwoToOneCRH>::evaluate(&params, &right, &left).unwrap();
+			assert_ne!(res, swapped);
+		}
+	}
+
+	#[cfg(all(feature = "poseidon_bn254_x5_2", feature = "poseidon_bn254_x5_3"))]
+	#[test]
+	fn test_poseidon_bn254_x5_2_single_element() {
+		use crate::{
+			setup::common::{PoseidonRounds_x5_2, PoseidonRounds_x5_3},
+			utils::{
+				get_mds_poseidon_bn254_x5_2, get_mds_poseidon_bn254_x5_3,
+				get_rounds_poseidon_bn254_x5_2, get_rounds_poseidon_bn254_x5_3,
+			},
+		};
+		use ark_ed_on_bn254::Fq as Bn254Fr;
+
+		/// Constraints of hashing one element with `P`, checking it against
+		/// the native hash
+		fn hash_one<P: Rounds>(params: &PoseidonParameters<Bn254Fr>) -> usize {
+			let rng = &mut test_rng();
+			let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+			let params_var = PoseidonParametersVar::new_constant(cs.clone(), params).unwrap();
+
+			let input = to_bytes![Bn254Fr::rand(rng)].unwrap();
+			let input_var =
+				Vec::<UInt8<Bn254Fr>>::new_witness(cs.clone(), || Ok(input.clone())).unwrap();
+
+			let before = cs.num_constraints();
+			let res = <CRH<Bn254Fr, P> as crh::CRH>::evaluate(params, &input).unwrap();
+			let res_var =
+				<CRHGadget<Bn254Fr, P> as CRHGadgetTrait<_, _>>::evaluate(&params_var, &input_var)
+					.unwrap();
+			assert_eq!(res, res_var.value().unwrap());
+			assert!(cs.is_satisfied().unwrap());
+			cs.num_constraints() - before
+		}
+
+		let params2 = PoseidonParameters::<Bn254Fr>::from_rounds::<PoseidonRounds_x5_2>(
+			get_rounds_poseidon_bn254_x5_2(),
+			get_mds_poseidon_bn254_x5_2(),
+		);
+		let params3 = PoseidonParameters::<Bn254Fr>::from_rounds::<PoseidonRounds_x5_3>(
+			get_rounds_poseidon_bn254_x5_3(),
+			get_mds_poseidon_bn254_x5_3(),
+		);
+		let constraints2 = hash_one::<PoseidonRounds_x5_2>(&params2);
+		let constraints3 = hash_one::<PoseidonRounds_x5_3>(&params3);
+		assert!(constraints2 < constraints3, "{} >= {}", constraints2, constraints3);
+
+		// The capacity leaves room for a single element per permutation
+		assert_eq!(PoseidonRounds_x5_2::WIDTH - PoseidonRounds_x5_2::CAPACITY, 1);
+	}
+
+	#[cfg(feature = "poseidon_ed_on_bn254_x5_3")]
+	#[test]
+	fn test_poseidon_ed_on_bn254_native_equality() {
+		use crate::utils::{
+			get_mds_poseidon_ed_on_bn254_x5_3, get_rounds_poseidon_ed_on_bn254_x5_3,
+		};
+		use ark_ed_on_bn254::Fq as BabyJubjubFq;
+
+		type PoseidonCRH3BabyJubjub = CRH<BabyJubjubFq, PoseidonRounds3>;
+		type PoseidonCRH3BabyJubjubGadget = CRHGadget<BabyJubjubFq, PoseidonRounds3>;
+
+		let cs = ConstraintSystem::<BabyJubjubFq>::new_ref();
+
+		let rounds = get_rounds_poseidon_ed_on_bn254_x5_3::<BabyJubjubFq>();
+		let mds = get_mds_poseidon_ed_on_bn254_x5_3::<BabyJubjubFq>();
+
+		let params = PoseidonParameters::<BabyJubjubFq>::new(rounds, mds);
+		let params_var = PoseidonParametersVar::new_variable(
+			cs.clone(),
+			|| Ok(&params),
+			AllocationMode::Constant,
+		)
+		.unwrap();
+
+		let inp = to_bytes![
+			BabyJubjubFq::zero(),
+			BabyJubjubFq::from(1u128),
+			BabyJubjubFq::from(2u128)
+		]
+		.unwrap();
+		let inp_var = Vec::<UInt8<BabyJubjubFq>>::new_input(cs.clone(), || Ok(inp.clone())).unwrap();
+
+		let res = PoseidonCRH3BabyJubjub::evaluate(&params, &inp).unwrap();
+		let res_var = <PoseidonCRH3BabyJubjubGadget as CRHGadgetTrait<_, _>>::evaluate(
+			&params_var,
+			&inp_var,
+		)
+		.unwrap();
+		assert_eq!(res, res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	// x^5 is not a permutation of the BLS12-377 scalar field, since 5 divides
+	// its multiplicative order, so x^17 is used instead
+	#[cfg(feature = "poseidon_bls377_x17_3")]
+	#[test]
+	fn test_poseidon_bls377_native_equality() {
+		use crate::utils::{get_mds_poseidon_bls377_x17_3, get_rounds_poseidon_bls377_x17_3};
+		use ark_bls12_377::Fr as Bls377Fr;
+
+		#[derive(Default, Clone)]
+		struct PoseidonRounds17_3;
+
+		impl Rounds for PoseidonRounds17_3 {
+			const FULL_ROUNDS: usize = 8;
+			const PARTIAL_ROUNDS: usize = 33;
+			const SBOX: PoseidonSbox = PoseidonSbox::Exponentiation(17);
+			const WIDTH: usize = 3;
+		}
+
+		type PoseidonCRH3Bls377 = CRH<Bls377Fr, PoseidonRounds17_3>;
+		type PoseidonCRH3Bls377Gadget = CRHGadget<Bls377Fr, PoseidonRounds17_3>;
+
+		let cs = ConstraintSystem::<Bls377Fr>::new_ref();
+
+		let rounds = get_rounds_poseidon_bls377_x17_3::<Bls377Fr>();
+		let mds = get_mds_poseidon_bls377_x17_3::<Bls377Fr>();
+
+		let params = PoseidonParameters::<Bls377Fr>::new(rounds, mds);
+		let params_var = PoseidonParametersVar::new_variable(
+			cs.clone(),
+			|| Ok(&params),
+			AllocationMode::Constant,
+		)
+		.unwrap();
+
+		let inp = to_bytes![
+			Bls377Fr::zero(),
+			Bls377Fr::from(1u128),
+			Bls377Fr::from(2u128)
+		]
+		.unwrap();
+		let inp_var = Vec::<UInt8<Bls377Fr>>::new_input(cs.clone(), || Ok(inp.clone())).unwrap();
+
+		let res = PoseidonCRH3Bls377::evaluate(&params, &inp).unwrap();
+		let res_var =
+			<PoseidonCRH3Bls377Gadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_var)
+				.unwrap();
+		assert_eq!(res, res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[cfg(feature = "poseidon_bw6_761_x5_3")]
+	#[test]
+	fn test_poseidon_bw6_761_native_equality() {
+		use crate::utils::{get_mds_poseidon_bw6_761_x5_3, get_rounds_poseidon_bw6_761_x5_3};
+		use ark_bw6_761::Fr as Bw6Fr;
+
+		type PoseidonCRH3Bw6 = CRH<Bw6Fr, PoseidonRounds3>;
+		type PoseidonCRH3Bw6Gadget = CRHGadget<Bw6Fr, PoseidonRounds3>;
+
+		let cs = ConstraintSystem::<Bw6Fr>::new_ref();
+
+		let rounds = get_rounds_poseidon_bw6_761_x5_3::<Bw6Fr>();
+		let mds = get_mds_poseidon_bw6_761_x5_3::<Bw6Fr>();
+
+		let params = PoseidonParameters::<Bw6Fr>::new(rounds, mds);
+		let params_var = PoseidonParametersVar::new_variable(
+			cs.clone(),
+			|| Ok(&params),
+			AllocationMode::Constant,
+		)
+		.unwrap();
+
+		let inp = to_bytes![Bw6Fr::zero(), Bw6Fr::from(1u128), Bw6Fr::from(2u128)].unwrap();
+		let inp_var = Vec::<UInt8<Bw6Fr>>::new_input(cs.clone(), || Ok(inp.clone())).unwrap();
+
+		let res = PoseidonCRH3Bw6::evaluate(&params, &inp).unwrap();
+		let res_var =
+			<PoseidonCRH3Bw6Gadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_var)
+				.unwrap();
+		assert_eq!(res, res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+	}
 }
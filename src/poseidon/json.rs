@@ -0,0 +1,169 @@
+//! Poseidon parameters read at runtime from JSON, so that they can be
+//! swapped without recompiling. The constants are big endian hex strings,
+//! like the ones in `utils`:
+//!
+//! ```json
+//! {
+//!   "width": 3,
+//!   "full_rounds": 8,
+//!   "partial_rounds": 57,
+//!   "alpha": 5,
+//!   "round_keys": ["0x0ee9a592ba9a9518d05986d656f40c2114c4993c11bb2993...", ...],
+//!   "mds_matrix": [["0x109b7f411ba0e4c9b2b70caf5c36a7b194be7c11ad24378b...", ...], ...]
+//! }
+//! ```
+//!
+//! `alpha` is the exponent of the S-box, 3, 5 or 17, or -1 for the inverse
+//! S-box.
+use super::{sbox::PoseidonSbox, PoseidonBuilder, PoseidonParameters};
+use crate::utils::fr_from_bytes_be;
+use ark_crypto_primitives::Error;
+use ark_ff::PrimeField;
+use ark_std::{error::Error as ArkError, io::Read, vec::Vec};
+use serde::Deserialize;
+
+#[derive(Debug)]
+pub enum JsonError {
+	Malformed(String),
+	InvalidAlpha(i64),
+}
+
+impl core::fmt::Display for JsonError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		use JsonError::*;
+		let msg = match self {
+			Malformed(err) => format!("malformed parameters file: {}", err),
+			InvalidAlpha(alpha) => format!("sbox exponent {} is not supported", alpha),
+		};
+		write!(f, "{}", msg)
+	}
+}
+
+impl ArkError for JsonError {}
+
+#[derive(Deserialize)]
+struct ParametersFile {
+	width: usize,
+	full_rounds: usize,
+	partial_rounds: usize,
+	alpha: i64,
+	round_keys: Vec<String>,
+	mds_matrix: Vec<Vec<String>>,
+}
+
+/// Parses a big endian hex constant, rejecting values that aren't below the
+/// modulus with `FieldBytesError::NonCanonical` instead of reducing them
+fn parse_constant<F: PrimeField>(s: &str) -> Result<F, Error> {
+	let bytes = hex::decode(s.trim_start_matches("0x"))
+		.map_err(|_| JsonError::Malformed(format!("{} is not a hex constant", s)))?;
+	Ok(fr_from_bytes_be(&bytes)?)
+}
+
+impl<F: PrimeField> PoseidonParameters<F> {
+	/// Reads parameters in the format of the `json` module, validating them
+	/// as `PoseidonBuilder::build` does
+	pub fn from_json<R: Read>(reader: R) -> Result<Self, Error> {
+		let file: ParametersFile =
+			serde_json::from_reader(reader).map_err(|e| JsonError::Malformed(e.to_string()))?;
+
+		let sbox = match file.alpha {
+			-1 => PoseidonSbox::Inverse,
+			3 | 5 | 17 => PoseidonSbox::Exponentiation(file.alpha as usize),
+			alpha => return Err(JsonError::InvalidAlpha(alpha).into()),
+		};
+		let round_keys = file
+			.round_keys
+			.iter()
+			.map(|s| parse_constant(s))
+			.collect::<Result<Vec<F>, _>>()?;
+		let mds = file
+			.mds_matrix
+			.iter()
+			.map(|row| row.iter().map(|s| parse_constant(s)).collect())
+			.collect::<Result<Vec<Vec<F>>, _>>()?;
+
+		let params = PoseidonBuilder::new()
+			.width(file.width)
+			.full_rounds(file.full_rounds)
+			.partial_rounds(file.partial_rounds)
+			.sbox(sbox)
+			.mds(mds)
+			.round_keys(round_keys)
+			.build()?;
+		Ok(params)
+	}
+}
+
+#[cfg(all(feature = "r1cs", feature = "default_poseidon"))]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{poseidon::CRH, setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3}};
+	use ark_bn254::Fr;
+	use ark_crypto_primitives::crh::CRH as CRHTrait;
+	use ark_ff::{to_bytes, BigInteger};
+
+	fn to_hex(value: &Fr) -> String {
+		format!("\"0x{}\"", hex::encode(value.into_repr().to_bytes_be()))
+	}
+
+	/// Writes `params` as a parameters file, with the given first round key
+	fn to_json(params: &PoseidonParameters<Fr>, first_round_key: &str) -> String {
+		let mut round_keys: Vec<String> = params.round_keys.iter().map(to_hex).collect();
+		round_keys[0] = first_round_key.to_string();
+		let mds: Vec<String> = params
+			.mds_matrix
+			.iter()
+			.map(|row| {
+				let row: Vec<String> = row.iter().map(to_hex).collect();
+				format!("[{}]", row.join(","))
+			})
+			.collect();
+		format!(
+			r#"{{"width": 3, "full_rounds": 8, "partial_rounds": 57, "alpha": 5,
+			"round_keys": [{}], "mds_matrix": [{}]}}"#,
+			round_keys.join(","),
+			mds.join(",")
+		)
+	}
+
+	#[test]
+	fn should_load_parameters_from_json() {
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let json = to_json(&params, &to_hex(&params.round_keys[0]));
+		let loaded = PoseidonParameters::<Fr>::from_json(json.as_bytes()).unwrap();
+		assert_eq!(loaded.round_keys, params.round_keys);
+		assert_eq!(loaded.mds_matrix, params.mds_matrix);
+
+		let input = to_bytes![Fr::from(1u64), Fr::from(2u64)].unwrap();
+		assert_eq!(
+			CRH::<Fr, PoseidonRounds_x5_3>::evaluate(&loaded, &input).unwrap(),
+			CRH::<Fr, PoseidonRounds_x5_3>::evaluate(&params, &input).unwrap()
+		);
+		let inputs = vec![Fr::from(1u64), Fr::from(2u64)];
+		assert_eq!(
+			crate::poseidon::Poseidon::hash(&loaded, &inputs).unwrap(),
+			crate::poseidon::Poseidon::hash(&params, &inputs).unwrap()
+		);
+	}
+
+	#[test]
+	fn should_reject_invalid_json() {
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		// The modulus of the bn254 scalar field
+		let modulus = "\"0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001\"";
+		let json = to_json(&params, modulus);
+		assert!(PoseidonParameters::<Fr>::from_json(json.as_bytes()).is_err());
+
+		let json = to_json(&params, "\"not hex\"");
+		assert!(PoseidonParameters::<Fr>::from_json(json.as_bytes()).is_err());
+
+		// One round key too few
+		let mut short = params.clone();
+		short.round_keys.pop();
+		let json = to_json(&short, &to_hex(&params.round_keys[0]));
+		assert!(PoseidonParameters::<Fr>::from_json(json.as_bytes()).is_err());
+
+		assert!(PoseidonParameters::<Fr>::from_json("{}".as_bytes()).is_err());
+	}
+}
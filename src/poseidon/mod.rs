@@ -1,17 +1,31 @@
-use crate::{poseidon::sbox::PoseidonSbox, utils::{to_field_elements, from_field_elements}};
+use crate::{
+	error::GadgetError,
+	poseidon::sbox::PoseidonSbox,
+	utils::{from_field_elements, full_chunk_size, to_field_elements_chunked, Endianness},
+};
 use ark_crypto_primitives::{crh::TwoToOneCRH, Error, CRH as CRHTrait};
-use ark_ff::{fields::PrimeField, BigInteger};
-use ark_std::{error::Error as ArkError, marker::PhantomData, rand::Rng, vec::Vec};
+use ark_ff::{fields::PrimeField, BigInteger, Field, Zero};
 use ark_serialize::Read;
+use ark_std::{error::Error as ArkError, marker::PhantomData, rand::Rng, vec::Vec};
 
 pub mod sbox;
 
+#[cfg(feature = "circom-compat")]
+pub mod circom;
+
 #[cfg(feature = "r1cs")]
 pub mod constraints;
 
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "plonk")]
+pub mod plonk;
+
 #[derive(Debug)]
 pub enum PoseidonError {
 	InvalidSboxSize(usize),
+	UnsupportedSbox,
 	ApplySboxFailed,
 	InvalidInputs,
 }
@@ -21,6 +35,7 @@ impl core::fmt::Display for PoseidonError {
 		use PoseidonError::*;
 		let msg = match self {
 			InvalidSboxSize(s) => format!("sbox is not supported: {}", s),
+			UnsupportedSbox => format!("sbox is not supported by this construction"),
 			ApplySboxFailed => format!("failed to apply sbox"),
 			InvalidInputs => format!("invalid inputs"),
 		};
@@ -30,6 +45,12 @@ impl core::fmt::Display for PoseidonError {
 
 impl ArkError for PoseidonError {}
 
+impl From<PoseidonError> for GadgetError {
+	fn from(err: PoseidonError) -> Self {
+		GadgetError::Ark(err.into())
+	}
+}
+
 // Choice is arbitrary
 pub const PADDING_CONST: u64 = 101;
 pub const ZERO_CONST: u64 = 0;
@@ -43,6 +64,18 @@ pub trait Rounds: Default + Clone {
 	const PARTIAL_ROUNDS: usize;
 	/// The S-box to apply in the sub words layer.
 	const SBOX: PoseidonSbox;
+	/// The S-box of the full rounds, `SBOX` unless overridden
+	const FULL_SBOX: PoseidonSbox = Self::SBOX;
+	/// The S-box of the partial rounds, `SBOX` unless overridden
+	const PARTIAL_SBOX: PoseidonSbox = Self::SBOX;
+	/// Number of state elements reserved as capacity, at the start of the
+	/// state. Inputs are absorbed into the remaining `WIDTH - CAPACITY` rate
+	/// elements. Defaults to 0, as the existing parameter sets hash inputs
+	/// filling the whole state, e.g. the three secrets of a mixer leaf with
+	/// a width of 3. Without capacity, inputs longer than the width can't be
+	/// absorbed safely and fail to hash, so parameter sets meant for long
+	/// inputs must set it to at least 1.
+	const CAPACITY: usize = 0;
 }
 
 /// The Poseidon permutation.
@@ -52,21 +85,152 @@ pub struct PoseidonParameters<F> {
 	pub round_keys: Vec<F>,
 	/// The MDS matrix to apply in the mix layer.
 	pub mds_matrix: Vec<Vec<F>>,
+	/// Number of full SBox rounds, only read by the runtime `Poseidon` hasher
+	pub full_rounds: usize,
+	/// Number of partial rounds, only read by the runtime `Poseidon` hasher
+	pub partial_rounds: usize,
+	/// The size of the permutation, in field elements.
+	pub width: usize,
+	/// Number of state elements reserved as capacity, only read by the
+	/// runtime `Poseidon` hasher
+	pub capacity: usize,
+	/// The S-box of the full rounds, only read by the runtime `Poseidon`
+	/// hasher
+	pub sbox: PoseidonSbox,
+	/// The S-box of the partial rounds, only read by the runtime `Poseidon`
+	/// hasher
+	pub partial_sbox: PoseidonSbox,
 }
 
 impl<F: PrimeField> PoseidonParameters<F> {
+	/// Builds the parameters without validating them, use `new_checked` for
+	/// parameters that don't come from a trusted source. The round counts are
+	/// left at zero, so these parameters can only be used with `CRH`, use
+	/// `with_rounds` or `from_rounds` to hash with `Poseidon`.
 	pub fn new(round_keys: Vec<F>, mds_matrix: Vec<Vec<F>>) -> Self {
+		Self::with_rounds(round_keys, mds_matrix, 0, 0, PoseidonSbox::default())
+	}
+
+	/// Builds the parameters along with the round counts and S-box read by
+	/// the runtime `Poseidon` hasher, using `sbox` in every round. The width
+	/// is the size of the MDS matrix.
+	pub fn with_rounds(
+		round_keys: Vec<F>,
+		mds_matrix: Vec<Vec<F>>,
+		full_rounds: usize,
+		partial_rounds: usize,
+		sbox: PoseidonSbox,
+	) -> Self {
+		let width = mds_matrix.len();
 		Self {
 			round_keys,
 			mds_matrix,
+			full_rounds,
+			partial_rounds,
+			width,
+			capacity: 0,
+			sbox,
+			partial_sbox: sbox,
 		}
 	}
 
-	pub fn generate<R: Rng>(rng: &mut R) -> Self {
-		Self {
-			round_keys: Self::create_round_keys(rng),
-			mds_matrix: Self::create_mds(rng),
+	/// Builds the parameters, taking the runtime fields from `P`
+	pub fn from_rounds<P: Rounds>(round_keys: Vec<F>, mds_matrix: Vec<Vec<F>>) -> Self {
+		let mut params = Self::with_rounds(
+			round_keys,
+			mds_matrix,
+			P::FULL_ROUNDS,
+			P::PARTIAL_ROUNDS,
+			P::FULL_SBOX,
+		);
+		params.partial_sbox = P::PARTIAL_SBOX;
+		params.capacity = P::CAPACITY;
+		params
+	}
+
+	/// Checks that the runtime fields are consistent with the round keys and
+	/// the MDS matrix
+	fn check_rounds(&self) -> Result<(), GadgetError> {
+		if self.width <= self.capacity || self.full_rounds % 2 != 0 {
+			return Err(GadgetError::InvalidRounds);
+		}
+		let expected = self.width * (self.full_rounds + self.partial_rounds);
+		if self.round_keys.len() != expected {
+			return Err(GadgetError::RoundKeysMismatch {
+				expected,
+				actual: self.round_keys.len(),
+			});
+		}
+		Self::check_mds_shape(&self.mds_matrix, self.width)
+	}
+
+	/// Checks that the MDS matrix is a `width` x `width` matrix
+	fn check_mds_shape(mds_matrix: &[Vec<F>], width: usize) -> Result<(), GadgetError> {
+		if mds_matrix.len() != width {
+			return Err(GadgetError::WidthMismatch {
+				expected: width,
+				actual: mds_matrix.len(),
+			});
+		}
+		if mds_matrix.iter().any(|row| row.len() != width) {
+			return Err(GadgetError::InvalidMdsShape);
+		}
+		Ok(())
+	}
+
+	/// Builds the parameters, checking that the MDS matrix is a `WIDTH` x
+	/// `WIDTH` matrix with a nonzero determinant, and that there is a round
+	/// key for each element of the state in every round, which is how many
+	/// the permutation reads.
+	pub fn new_checked<P: Rounds>(
+		round_keys: Vec<F>,
+		mds_matrix: Vec<Vec<F>>,
+	) -> Result<Self, GadgetError> {
+		Self::check_mds_shape(&mds_matrix, P::WIDTH)?;
+		let expected = P::WIDTH * (P::FULL_ROUNDS + P::PARTIAL_ROUNDS);
+		if round_keys.len() != expected {
+			return Err(GadgetError::RoundKeysMismatch {
+				expected,
+				actual: round_keys.len(),
+			});
+		}
+		if Self::determinant(&mds_matrix).is_zero() {
+			return Err(GadgetError::SingularMds);
 		}
+
+		Ok(Self::from_rounds::<P>(round_keys, mds_matrix))
+	}
+
+	/// Determinant of a square matrix, computed with Gaussian elimination
+	fn determinant(matrix: &[Vec<F>]) -> F {
+		let n = matrix.len();
+		let mut m = matrix.to_vec();
+		let mut det = F::one();
+		for col in 0..n {
+			let pivot = match (col..n).find(|&row| !m[row][col].is_zero()) {
+				Some(pivot) => pivot,
+				None => return F::zero(),
+			};
+			if pivot != col {
+				m.swap(pivot, col);
+				det = -det;
+			}
+			det *= m[col][col];
+
+			let inv = m[col][col].inverse().unwrap();
+			for row in (col + 1)..n {
+				let factor = m[row][col] * inv;
+				for k in col..n {
+					let sub = factor * m[col][k];
+					m[row][k] -= sub;
+				}
+			}
+		}
+		det
+	}
+
+	pub fn generate<R: Rng>(rng: &mut R) -> Self {
+		Self::new(Self::create_round_keys(rng), Self::create_mds(rng))
 	}
 
 	pub fn create_mds<R: Rng>(_rng: &mut R) -> Vec<Vec<F>> {
@@ -77,9 +241,24 @@ impl<F: PrimeField> PoseidonParameters<F> {
 		todo!();
 	}
 
+	/// Serializes the parameters, runtime fields included, as
+	/// `PARAMS_VERSION`, the round counts, width and capacity, the full and
+	/// partial S-boxes, each as a big-endian `u32`, followed by the round
+	/// keys and the MDS matrix.
 	pub fn to_bytes(&self) -> Vec<u8> {
 		let max_elt_size = F::BigInt::NUM_LIMBS * 8;
-		let mut buf: Vec<u8> = vec![];
+		let mut buf: Vec<u8> = vec![PARAMS_VERSION];
+		let header = [
+			self.full_rounds as u32,
+			self.partial_rounds as u32,
+			self.width as u32,
+			self.capacity as u32,
+			sbox_to_u32(self.sbox),
+			sbox_to_u32(self.partial_sbox),
+		];
+		for value in header.iter() {
+			buf.extend_from_slice(&value.to_be_bytes());
+		}
 		// serialize length of round keys and round keys, packing them together
 		let round_key_len = self.round_keys.len() * max_elt_size;
 		buf.extend_from_slice(&(round_key_len as u32).to_be_bytes());
@@ -92,7 +271,7 @@ impl<F: PrimeField> PoseidonParameters<F> {
 			if !stored {
 				// the number of bytes to read for each inner mds matrix vec
 				let inner_vec_len = self.mds_matrix[i].len() * max_elt_size;
-				buf.extend_from_slice(&(inner_vec_len as u32).to_be_bytes());				
+				buf.extend_from_slice(&(inner_vec_len as u32).to_be_bytes());
 				stored = true;
 			}
 
@@ -101,7 +280,35 @@ impl<F: PrimeField> PoseidonParameters<F> {
 		buf
 	}
 
+	/// Reads parameters written by `to_bytes`. Bytes written before the
+	/// runtime fields were serialized start with the length of the round
+	/// keys, whose first byte is 0, and are read with the runtime fields
+	/// left at zero, as `new` leaves them.
 	pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, Error> {
+		match bytes.first() {
+			Some(&PARAMS_VERSION) => bytes = &bytes[1..],
+			Some(0) => return Self::read_keys_and_mds(bytes),
+			_ => return Err(PoseidonError::InvalidInputs.into()),
+		}
+
+		let full_rounds = read_u32(&mut bytes)? as usize;
+		let partial_rounds = read_u32(&mut bytes)? as usize;
+		let width = read_u32(&mut bytes)? as usize;
+		let capacity = read_u32(&mut bytes)? as usize;
+		let sbox = sbox_from_u32(read_u32(&mut bytes)?);
+		let partial_sbox = sbox_from_u32(read_u32(&mut bytes)?);
+
+		let mut params = Self::read_keys_and_mds(bytes)?;
+		params.full_rounds = full_rounds;
+		params.partial_rounds = partial_rounds;
+		params.width = width;
+		params.capacity = capacity;
+		params.sbox = sbox;
+		params.partial_sbox = partial_sbox;
+		Ok(params)
+	}
+
+	fn read_keys_and_mds(mut bytes: &[u8]) -> Result<Self, Error> {
 		let mut round_key_len = [0u8; 4];
 		bytes.read(&mut round_key_len)?;
 
@@ -109,7 +316,8 @@ impl<F: PrimeField> PoseidonParameters<F> {
 		let mut round_keys_buf = vec![0u8; round_key_len_usize];
 		bytes.read(&mut round_keys_buf)?;
 
-		let round_keys = to_field_elements::<F>(&round_keys_buf)?;
+		let round_keys =
+			to_field_elements_chunked(&round_keys_buf, full_chunk_size::<F>(), Endianness::Little)?;
 		let mut mds_matrix_inner_vec_len = [0u8; 4];
 		bytes.read(&mut mds_matrix_inner_vec_len)?;
 
@@ -119,14 +327,116 @@ impl<F: PrimeField> PoseidonParameters<F> {
 			let mut inner_vec_buf = vec![0u8; inner_vec_len_usize];
 			bytes.read(&mut inner_vec_buf)?;
 
-			let inner_vec = to_field_elements::<F>(&inner_vec_buf)?;
+			let inner_vec = to_field_elements_chunked::<F>(
+				&inner_vec_buf,
+				full_chunk_size::<F>(),
+				Endianness::Little,
+			)?;
 			mds_matrix.push(inner_vec);
 		}
 
-		Ok(Self {
-			round_keys,
-			mds_matrix,
-		})
+		Ok(Self::new(round_keys, mds_matrix))
+	}
+}
+
+/// Version of the layout written by `PoseidonParameters::to_bytes`
+pub const PARAMS_VERSION: u8 = 1;
+
+/// Encodes an S-box as its exponent, with 0 for the inverse S-box
+fn sbox_to_u32(sbox: PoseidonSbox) -> u32 {
+	match sbox {
+		PoseidonSbox::Exponentiation(alpha) => alpha as u32,
+		PoseidonSbox::Inverse => 0,
+	}
+}
+
+fn sbox_from_u32(value: u32) -> PoseidonSbox {
+	match value {
+		0 => PoseidonSbox::Inverse,
+		alpha => PoseidonSbox::Exponentiation(alpha as usize),
+	}
+}
+
+fn read_u32(bytes: &mut &[u8]) -> Result<u32, Error> {
+	let mut buf = [0u8; 4];
+	bytes.read_exact(&mut buf)?;
+	Ok(u32::from_be_bytes(buf))
+}
+
+/// Builds `PoseidonParameters` without a `Rounds` type, checking in `build`
+/// that the round keys and MDS matrix match the width and round counts
+#[derive(Default, Clone)]
+pub struct PoseidonBuilder<F> {
+	width: usize,
+	full_rounds: usize,
+	partial_rounds: usize,
+	sbox: PoseidonSbox,
+	partial_sbox: Option<PoseidonSbox>,
+	mds: Vec<Vec<F>>,
+	round_keys: Vec<F>,
+}
+
+impl<F: PrimeField> PoseidonBuilder<F> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn width(mut self, width: usize) -> Self {
+		self.width = width;
+		self
+	}
+
+	pub fn full_rounds(mut self, full_rounds: usize) -> Self {
+		self.full_rounds = full_rounds;
+		self
+	}
+
+	pub fn partial_rounds(mut self, partial_rounds: usize) -> Self {
+		self.partial_rounds = partial_rounds;
+		self
+	}
+
+	/// Sets the S-box of every round, unless `partial_sbox` overrides the one
+	/// of the partial rounds
+	pub fn sbox(mut self, sbox: PoseidonSbox) -> Self {
+		self.sbox = sbox;
+		self
+	}
+
+	pub fn partial_sbox(mut self, sbox: PoseidonSbox) -> Self {
+		self.partial_sbox = Some(sbox);
+		self
+	}
+
+	pub fn mds(mut self, mds: Vec<Vec<F>>) -> Self {
+		self.mds = mds;
+		self
+	}
+
+	pub fn round_keys(mut self, round_keys: Vec<F>) -> Self {
+		self.round_keys = round_keys;
+		self
+	}
+
+	/// Fails unless there are `width * (full_rounds + partial_rounds)` round
+	/// keys, the full rounds are even and the MDS matrix is an invertible
+	/// `width` x `width` matrix
+	pub fn build(self) -> Result<PoseidonParameters<F>, GadgetError> {
+		let mut params = PoseidonParameters::with_rounds(
+			self.round_keys,
+			self.mds,
+			self.full_rounds,
+			self.partial_rounds,
+			self.sbox,
+		);
+		params.partial_sbox = self.partial_sbox.unwrap_or(self.sbox);
+		params.width = self.width;
+
+		params.check_rounds()?;
+		if PoseidonParameters::determinant(&params.mds_matrix).is_zero() {
+			return Err(GadgetError::SingularMds);
+		}
+		Ok(params)
 	}
 }
 
@@ -135,64 +445,165 @@ pub struct CRH<F: PrimeField, P: Rounds> {
 	rounds: PhantomData<P>,
 }
 
-impl<F: PrimeField, P: Rounds> CRH<F, P> {
-	fn permute(params: &PoseidonParameters<F>, mut state: Vec<F>) -> Result<Vec<F>, PoseidonError> {
-		let width = P::WIDTH;
-
-		let mut round_keys_offset = 0;
-
-		// full Sbox rounds
-		for _ in 0..(P::FULL_ROUNDS / 2) {
-			// Sbox layer
-			for i in 0..width {
-				state[i] += params.round_keys[round_keys_offset];
-				state[i] = P::SBOX.apply_sbox(state[i])?;
-				round_keys_offset += 1;
-			}
-			// linear layer
-			state = Self::apply_linear_layer(&state, &params.mds_matrix);
+/// Runs the Poseidon permutation over `state` with the given round counts and
+/// S-boxes, shared by the `Rounds` based and the runtime hashers. Fails with
+/// `GadgetError::RoundKeysMismatch` when `params` holds fewer round keys than
+/// the rounds read, as parameters built with `new` aren't checked.
+fn permute_rounds<F: PrimeField>(
+	params: &PoseidonParameters<F>,
+	mut state: Vec<F>,
+	width: usize,
+	full_rounds: usize,
+	partial_rounds: usize,
+	full_sbox: PoseidonSbox,
+	partial_sbox: PoseidonSbox,
+) -> Result<Vec<F>, GadgetError> {
+	let expected = width * (full_rounds + partial_rounds);
+	if params.round_keys.len() < expected {
+		return Err(GadgetError::RoundKeysMismatch {
+			expected,
+			actual: params.round_keys.len(),
+		});
+	}
+	let mut round_keys_offset = 0;
+
+	// full Sbox rounds
+	for _ in 0..(full_rounds / 2) {
+		// Sbox layer
+		for i in 0..width {
+			state[i] += params.round_keys[round_keys_offset];
+			state[i] = full_sbox.apply_sbox(state[i])?;
+			round_keys_offset += 1;
 		}
+		// linear layer
+		state = apply_linear_layer(&state, &params.mds_matrix);
+	}
 
-		// middle partial Sbox rounds
-		for _ in 0..P::PARTIAL_ROUNDS {
-			for i in 0..width {
-				state[i] += params.round_keys[round_keys_offset];
-				round_keys_offset += 1;
-			}
-			// partial Sbox layer, apply Sbox to only 1 element of the state.
-			// Here the last one is chosen but the choice is arbitrary.
-			state[0] = P::SBOX.apply_sbox(state[0])?;
-			// linear layer
-			state = Self::apply_linear_layer(&state, &params.mds_matrix);
+	// middle partial Sbox rounds
+	for _ in 0..partial_rounds {
+		for i in 0..width {
+			state[i] += params.round_keys[round_keys_offset];
+			round_keys_offset += 1;
 		}
+		// partial Sbox layer, apply Sbox to only 1 element of the state.
+		// Here the last one is chosen but the choice is arbitrary.
+		state[0] = partial_sbox.apply_sbox(state[0])?;
+		// linear layer
+		state = apply_linear_layer(&state, &params.mds_matrix);
+	}
 
-		// last full Sbox rounds
-		for _ in 0..(P::FULL_ROUNDS / 2) {
-			// Sbox layer
-			for i in 0..width {
-				state[i] += params.round_keys[round_keys_offset];
-				state[i] = P::SBOX.apply_sbox(state[i])?;
-				round_keys_offset += 1;
-			}
-			// linear layer
-			state = Self::apply_linear_layer(&state, &params.mds_matrix);
+	// last full Sbox rounds
+	for _ in 0..(full_rounds / 2) {
+		// Sbox layer
+		for i in 0..width {
+			state[i] += params.round_keys[round_keys_offset];
+			state[i] = full_sbox.apply_sbox(state[i])?;
+			round_keys_offset += 1;
 		}
+		// linear layer
+		state = apply_linear_layer(&state, &params.mds_matrix);
+	}
+
+	// Finally the current_state becomes the output
+	Ok(state)
+}
 
-		// Finally the current_state becomes the output
-		Ok(state)
+fn apply_linear_layer<F: PrimeField>(state: &Vec<F>, mds: &Vec<Vec<F>>) -> Vec<F> {
+	let mut new_state: Vec<F> = Vec::new();
+	for i in 0..state.len() {
+		let mut sc = F::zero();
+		for j in 0..state.len() {
+			let mij = mds[i][j];
+			sc += mij * state[j];
+		}
+		new_state.push(sc);
 	}
+	new_state
+}
+
+impl<F: PrimeField, P: Rounds> CRH<F, P> {
+	/// Runs the Poseidon permutation over a state of `P::WIDTH` elements,
+	/// capacity first, returning the permuted state
+	pub fn permute(params: &PoseidonParameters<F>, state: Vec<F>) -> Result<Vec<F>, GadgetError> {
+		if state.len() != P::WIDTH {
+			return Err(PoseidonError::InvalidInputs.into());
+		}
+		permute_rounds(
+			params,
+			state,
+			P::WIDTH,
+			P::FULL_ROUNDS,
+			P::PARTIAL_ROUNDS,
+			P::FULL_SBOX,
+			P::PARTIAL_SBOX,
+		)
+	}
+
+	/// Like `CRH::evaluate`, reading the input into field elements with the
+	/// given byte order. `CRH::evaluate` uses `Endianness::Little`.
+	///
+	/// The input is read in `full_chunk_size` chunks, as it is expected to
+	/// hold serialized field elements, so a chunk that is not smaller than
+	/// the modulus is an error.
+	///
+	/// Inputs longer than the `WIDTH - CAPACITY` elements of the rate are
+	/// absorbed like a sponge, see `hash_elements`, and are rejected when
+	/// `P::CAPACITY` is 0.
+	pub fn evaluate_with(
+		parameters: &PoseidonParameters<F>,
+		input: &[u8],
+		endianness: Endianness,
+	) -> Result<F, Error> {
+		let eval_time = start_timer!(|| "PoseidonCRH::Eval");
+
+		let f_inputs: Vec<F> =
+			to_field_elements_chunked(input, full_chunk_size::<F>(), endianness)?;
+		let result = Self::hash_elements(parameters, &f_inputs)?;
+
+		end_timer!(eval_time);
+
+		Ok(result)
+	}
+
+	/// Absorbs field elements into the rate of a zero state, like
+	/// `evaluate_with` absorbs the elements its bytes pack into, without
+	/// going through their byte encoding.
+	///
+	/// Inputs fitting in the rate take a single permutation. Longer inputs
+	/// are absorbed a block of the rate at a time, the last one zero-padded,
+	/// starting from a state holding their length in the capacity, so that
+	/// they don't collide with inputs of another length. They are rejected
+	/// without capacity, where the next block can be picked to reach any
+	/// state.
+	pub fn hash_elements(parameters: &PoseidonParameters<F>, inputs: &[F]) -> Result<F, Error> {
+		let rate = P::WIDTH - P::CAPACITY;
+		let mut state = vec![F::zero(); P::WIDTH];
+		if inputs.len() > rate {
+			if P::CAPACITY == 0 || rate == 0 {
+				return Err(GadgetError::InputTooLong {
+					len: inputs.len(),
+					max: rate,
+				}
+				.into());
+			}
+			state[0] = F::from(inputs.len() as u64);
+		}
+
+		let mut offset = 0;
+		loop {
+			state[P::CAPACITY..]
+				.iter_mut()
+				.zip(&inputs[offset..])
+				.for_each(|(p, v)| *p += v);
+			state = Self::permute(&parameters, state)?;
 
-	fn apply_linear_layer(state: &Vec<F>, mds: &Vec<Vec<F>>) -> Vec<F> {
-		let mut new_state: Vec<F> = Vec::new();
-		for i in 0..state.len() {
-			let mut sc = F::zero();
-			for j in 0..state.len() {
-				let mij = mds[i][j];
-				sc += mij * state[j];
+			offset += rate;
+			if offset >= inputs.len() {
+				break;
 			}
-			new_state.push(sc);
 		}
-		new_state
+
+		Ok(state.get(0).cloned().ok_or(PoseidonError::InvalidInputs)?)
 	}
 }
 
@@ -200,7 +611,8 @@ impl<F: PrimeField, P: Rounds> CRHTrait for CRH<F, P> {
 	type Output = F;
 	type Parameters = PoseidonParameters<F>;
 
-	const INPUT_SIZE_BITS: usize = F::BigInt::NUM_LIMBS * 8 * P::WIDTH * 8;
+	// A single block of the rate, longer inputs need a nonzero capacity
+	const INPUT_SIZE_BITS: usize = F::BigInt::NUM_LIMBS * 8 * (P::WIDTH - P::CAPACITY) * 8;
 
 	// Not sure what's the purpose of this function of we are going to pass
 	// parameters
@@ -209,27 +621,7 @@ impl<F: PrimeField, P: Rounds> CRHTrait for CRH<F, P> {
 	}
 
 	fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
-		let eval_time = start_timer!(|| "PoseidonCRH::Eval");
-
-		let f_inputs: Vec<F> = to_field_elements(input)?;
-
-		if f_inputs.len() > P::WIDTH {
-			panic!(
-				"incorrect input length {:?} for width {:?} -- input bits {:?}",
-				f_inputs.len(),
-				P::WIDTH,
-				input.len()
-			);
-		}
-
-		let mut buffer = vec![F::zero(); P::WIDTH];
-		buffer.iter_mut().zip(f_inputs).for_each(|(p, v)| *p = v);
-
-		let result = Self::permute(&parameters, buffer)?;
-
-		end_timer!(eval_time);
-
-		Ok(result.get(0).cloned().ok_or(PoseidonError::InvalidInputs)?)
+		Self::evaluate_with(parameters, input, Endianness::Little)
 	}
 }
 
@@ -263,12 +655,147 @@ impl<F: PrimeField, P: Rounds> TwoToOneCRH for CRH<F, P> {
 	}
 }
 
+/// Poseidon hasher reading the width, round counts and S-box from its
+/// parameters instead of a `Rounds` type, for parameters only known at
+/// runtime. Digests match `CRH<F, P>` for parameters built with
+/// `PoseidonParameters::from_rounds::<P>`.
+pub struct Poseidon<F: PrimeField> {
+	field: PhantomData<F>,
+}
+
+impl<F: PrimeField> Poseidon<F> {
+	/// Like `CRH::evaluate_with`, failing instead of panicking on inputs
+	/// wider than the parameters
+	pub fn evaluate_with(
+		parameters: &PoseidonParameters<F>,
+		input: &[u8],
+		endianness: Endianness,
+	) -> Result<F, GadgetError> {
+		let eval_time = start_timer!(|| "Poseidon::Eval");
+
+		let f_inputs: Vec<F> =
+			to_field_elements_chunked(input, full_chunk_size::<F>(), endianness)?;
+		let result = Self::hash(parameters, &f_inputs)?;
+
+		end_timer!(eval_time);
+
+		Ok(result)
+	}
+
+	/// Hashes up to `width - capacity` field elements, zero-padding the state
+	pub fn hash(parameters: &PoseidonParameters<F>, inputs: &[F]) -> Result<F, GadgetError> {
+		parameters.check_rounds()?;
+		let max = parameters.width - parameters.capacity;
+		if inputs.len() > max {
+			return Err(GadgetError::InputTooLong {
+				len: inputs.len(),
+				max,
+			});
+		}
+
+		let mut buffer = vec![F::zero(); parameters.width];
+		buffer[parameters.capacity..]
+			.iter_mut()
+			.zip(inputs)
+			.for_each(|(p, v)| *p = *v);
+
+		let result = permute_rounds(
+			parameters,
+			buffer,
+			parameters.width,
+			parameters.full_rounds,
+			parameters.partial_rounds,
+			parameters.sbox,
+			parameters.partial_sbox,
+		)?;
+
+		Ok(result[0])
+	}
+}
+
+impl<F: PrimeField> CRHTrait for Poseidon<F> {
+	type Output = F;
+	type Parameters = PoseidonParameters<F>;
+
+	// The width is only known at runtime, inputs of up to `width - capacity`
+	// field elements are accepted by `evaluate`
+	const INPUT_SIZE_BITS: usize = F::BigInt::NUM_LIMBS * 8 * 8;
+
+	fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+		Ok(Self::Parameters::generate(rng))
+	}
+
+	fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+		Ok(Self::evaluate_with(parameters, input, Endianness::Little)?)
+	}
+}
+
+/// Security level, in bits, targeted by `hash_to_field`
+const HASH_TO_FIELD_SECURITY: usize = 128;
+
+/// Derives `count` field elements from arbitrary bytes with the runtime
+/// `Poseidon` hasher, for secrets that must be close to uniform.
+///
+/// The input is absorbed in chunks smaller than the modulus, after its
+/// length. Each output then reduces `L = ceil((log2(p) + 128) / 8)` bytes
+/// modulo `p`, taken from the low `log2(p) / 16` bytes of squeezed elements.
+/// The wide reduction is at most `p / 2^(8L) < 2^-128` from uniform and each
+/// truncated element adds at most `2^-(log2(p) / 2)`, which keeps the
+/// outputs within `2^-128` of uniform for the 254 and 255 bit scalar fields
+/// of the supported curves.
+///
+/// The parameters must hold their runtime fields, see
+/// `PoseidonParameters::from_rounds`, and have a rate of at least 2.
+pub fn hash_to_field<F: PrimeField>(
+	params: &PoseidonParameters<F>,
+	input: &[u8],
+	count: usize,
+) -> Result<Vec<F>, Error> {
+	if params.width < params.capacity + 2 {
+		return Err(PoseidonError::InvalidInputs.into());
+	}
+	let modulus_bits = F::size_in_bits();
+
+	// Absorb
+	let chunk_size = (modulus_bits - 1) / 8;
+	let mut state = Poseidon::hash(params, &[F::from(input.len() as u64)])?;
+	let elements: Vec<F> = input
+		.chunks(chunk_size)
+		.map(F::from_le_bytes_mod_order)
+		.collect();
+	for chunk in elements.chunks(params.width - params.capacity - 1) {
+		let mut inputs = vec![state];
+		inputs.extend_from_slice(chunk);
+		state = Poseidon::hash(params, &inputs)?;
+	}
+
+	// Squeeze
+	let wide_len = (modulus_bits + HASH_TO_FIELD_SECURITY + 7) / 8;
+	let squeeze_len = modulus_bits / 16;
+	let squeezes_per_output = (wide_len + squeeze_len - 1) / squeeze_len;
+	let mut counter = 0u64;
+	let mut outputs = Vec::with_capacity(count);
+	for _ in 0..count {
+		let mut wide = Vec::with_capacity(squeezes_per_output * squeeze_len);
+		for _ in 0..squeezes_per_output {
+			let squeezed = Poseidon::hash(params, &[state, F::from(counter)])?;
+			wide.extend_from_slice(&squeezed.into_repr().to_bytes_le()[..squeeze_len]);
+			counter += 1;
+		}
+		wide.truncate(wide_len);
+		outputs.push(F::from_le_bytes_mod_order(&wide));
+	}
+
+	Ok(outputs)
+}
+
 #[cfg(all(feature = "poseidon_bn254_x5_5", feature = "poseidon_bn254_x5_3"))]
 #[cfg(test)]
 mod test {
 	use super::*;
 	use ark_ed_on_bn254::Fq;
-	use ark_ff::{to_bytes, Zero};
+	use ark_ff::{to_bytes, FpParameters, Zero};
+	use ark_std::rand::RngCore;
 
 	use crate::utils::{
 		get_mds_poseidon_bn254_x5_3, get_mds_poseidon_bn254_x5_5, get_results_poseidon_bn254_x5_3,
@@ -309,6 +836,120 @@ mod test {
 		assert_eq!(bytes, new_params.to_bytes());
 	}
 
+	#[test]
+	fn should_hash_with_parameters_read_from_bytes() {
+		let rounds = get_rounds_poseidon_bn254_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bn254_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::from_rounds::<PoseidonRounds3>(rounds, mds);
+		let inputs = [Fq::from(1u64), Fq::from(2u64)];
+		let expected = Poseidon::hash(&params, &inputs).unwrap();
+
+		let bytes = params.to_bytes();
+		let decoded = PoseidonParameters::<Fq>::from_bytes(&bytes).unwrap();
+		assert_eq!(decoded.full_rounds, 8);
+		assert_eq!(decoded.partial_rounds, 57);
+		assert_eq!((decoded.width, decoded.capacity), (3, 0));
+		assert_eq!(decoded.sbox, PoseidonSbox::Exponentiation(5));
+		assert_eq!(decoded.partial_sbox, PoseidonSbox::Exponentiation(5));
+		assert_eq!(Poseidon::hash(&decoded, &inputs).unwrap(), expected);
+
+		// Bytes without the version and runtime fields still read, as `new`
+		// would build them
+		let header_size = 1 + 6 * 4;
+		let unversioned = PoseidonParameters::<Fq>::from_bytes(&bytes[header_size..]).unwrap();
+		assert_eq!(unversioned.round_keys, params.round_keys);
+		assert_eq!(unversioned.mds_matrix, params.mds_matrix);
+		assert_eq!(unversioned.full_rounds, 0);
+		assert!(Poseidon::hash(&unversioned, &inputs).is_err());
+
+		assert!(PoseidonParameters::<Fq>::from_bytes(&[2]).is_err());
+	}
+
+	#[test]
+	fn should_check_mds_matrix() {
+		let rounds = get_rounds_poseidon_bn254_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bn254_x5_3::<Fq>();
+		assert!(
+			PoseidonParameters::<Fq>::new_checked::<PoseidonRounds3>(rounds.clone(), mds).is_ok()
+		);
+
+		// The last row is the sum of the first two
+		let one = Fq::from(1u64);
+		let two = Fq::from(2u64);
+		let singular = vec![vec![one, two, one], vec![two, one, two], vec![
+			one + two,
+			two + one,
+			one + two,
+		]];
+		assert!(
+			PoseidonParameters::<Fq>::new_checked::<PoseidonRounds3>(rounds.clone(), singular)
+				.is_err()
+		);
+
+		let mds = get_mds_poseidon_bn254_x5_3::<Fq>();
+		assert!(PoseidonParameters::<Fq>::new_checked::<PoseidonRounds5>(rounds, mds).is_err());
+	}
+
+	#[test]
+	fn should_build_x5_3_parameters() {
+		let rounds = get_rounds_poseidon_bn254_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bn254_x5_3::<Fq>();
+		let expected =
+			PoseidonParameters::<Fq>::from_rounds::<PoseidonRounds3>(rounds.clone(), mds.clone());
+
+		let builder = PoseidonBuilder::new()
+			.width(3)
+			.full_rounds(8)
+			.partial_rounds(57)
+			.sbox(PoseidonSbox::Exponentiation(5))
+			.mds(mds.clone());
+		let params = builder.clone().round_keys(rounds.clone()).build().unwrap();
+		assert_eq!(params.round_keys, expected.round_keys);
+		assert_eq!(params.mds_matrix, expected.mds_matrix);
+		assert_eq!(params.full_rounds, expected.full_rounds);
+		assert_eq!(params.partial_rounds, expected.partial_rounds);
+		assert_eq!(params.width, expected.width);
+		assert_eq!(params.sbox, expected.sbox);
+		assert_eq!(params.partial_sbox, expected.partial_sbox);
+
+		let inputs = [Fq::zero(), Fq::from(1u128), Fq::from(2u128)];
+		assert_eq!(
+			Poseidon::hash(&params, &inputs).unwrap(),
+			get_results_poseidon_bn254_x5_3::<Fq>()[0]
+		);
+
+		let err = builder
+			.clone()
+			.round_keys(rounds[1..].to_vec())
+			.build()
+			.err()
+			.unwrap();
+		assert!(matches!(err, GadgetError::RoundKeysMismatch {
+			expected: 195,
+			actual: 194
+		}));
+		let err = builder.width(5).round_keys(rounds).build().err().unwrap();
+		assert!(matches!(err, GadgetError::RoundKeysMismatch {
+			expected: 325,
+			actual: 195
+		}));
+
+		let rounds = get_rounds_poseidon_bn254_x5_5::<Fq>();
+		let err = PoseidonBuilder::new()
+			.width(5)
+			.full_rounds(8)
+			.partial_rounds(60)
+			.mds(mds)
+			.round_keys(rounds)
+			.build()
+			.err()
+			.unwrap();
+		assert!(matches!(err, GadgetError::WidthMismatch {
+			expected: 5,
+			actual: 3
+		}));
+	}
+
 	#[test]
 	fn test_width_3_bn_254() {
 		let rounds = get_rounds_poseidon_bn254_x5_3::<Fq>();
@@ -321,6 +962,9 @@ mod test {
 
 		let poseidon_res = <PoseidonCRH3 as CRHTrait>::evaluate(&params, &inp).unwrap();
 		assert_eq!(res[0], poseidon_res);
+
+		#[cfg(feature = "r1cs")]
+		constraints::assert_params_round_trip::<Fq, PoseidonRounds3>(&params, &inp);
 	}
 
 	#[test]
@@ -342,5 +986,74 @@ mod test {
 
 		let poseidon_res = <PoseidonCRH5 as CRHTrait>::evaluate(&params, &inp).unwrap();
 		assert_eq!(res[0], poseidon_res);
+
+		#[cfg(feature = "r1cs")]
+		constraints::assert_params_round_trip::<Fq, PoseidonRounds5>(&params, &inp);
+	}
+
+	#[test]
+	fn should_match_const_rounds_at_runtime() {
+		let rounds = get_rounds_poseidon_bn254_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bn254_x5_3::<Fq>();
+		let res = get_results_poseidon_bn254_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::with_rounds(
+			rounds,
+			mds,
+			8,
+			57,
+			PoseidonSbox::Exponentiation(5),
+		);
+		assert_eq!(params.width, 3);
+
+		let inp = to_bytes![Fq::zero(), Fq::from(1u128), Fq::from(2u128)].unwrap();
+		let runtime_res = <Poseidon<Fq> as CRHTrait>::evaluate(&params, &inp).unwrap();
+		let const_res = <PoseidonCRH3 as CRHTrait>::evaluate(&params, &inp).unwrap();
+		assert_eq!(runtime_res, const_res);
+		assert_eq!(runtime_res, res[0]);
+
+		// Round counts that don't match the round keys are rejected
+		let mut wrong = params.clone();
+		wrong.partial_rounds = 60;
+		assert!(<Poseidon<Fq> as CRHTrait>::evaluate(&wrong, &inp).is_err());
+
+		// As are inputs wider than the state
+		let too_wide = to_bytes![Fq::zero(), Fq::zero(), Fq::zero(), Fq::zero()].unwrap();
+		assert!(<Poseidon<Fq> as CRHTrait>::evaluate(&params, &too_wide).is_err());
+	}
+
+	#[test]
+	fn should_hash_to_field_deterministically() {
+		let rounds = get_rounds_poseidon_bn254_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bn254_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::from_rounds::<PoseidonRounds3>(rounds, mds);
+
+		let rng = &mut ark_std::test_rng();
+		let mut seen = Vec::new();
+		for len in 0..64usize {
+			let mut seed = vec![0u8; len];
+			rng.fill_bytes(&mut seed);
+
+			let outputs = hash_to_field(&params, &seed, 3).unwrap();
+			assert_eq!(outputs.len(), 3);
+			assert_eq!(outputs, hash_to_field(&params, &seed, 3).unwrap());
+			// A shorter squeeze is a prefix of a longer one
+			assert_eq!(outputs[..1], hash_to_field(&params, &seed, 1).unwrap()[..]);
+			for output in outputs.iter() {
+				assert!(output.into_repr() < <Fq as PrimeField>::Params::MODULUS);
+				assert!(!seen.contains(output));
+				seen.push(*output);
+			}
+		}
+
+		// Trailing zeros change the output
+		let short = hash_to_field(&params, &[1u8], 1).unwrap();
+		let padded = hash_to_field(&params, &[1u8, 0u8], 1).unwrap();
+		assert_ne!(short, padded);
+
+		// Parameters without their runtime fields are rejected
+		let rounds = get_rounds_poseidon_bn254_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bn254_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+		assert!(hash_to_field(&params, &[1u8], 1).is_err());
 	}
 }
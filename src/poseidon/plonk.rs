@@ -0,0 +1,263 @@
+use super::{sbox::PoseidonSbox, PoseidonError, PoseidonParameters, Rounds};
+use ark_ec::TEModelParameters;
+use ark_ff::PrimeField;
+use ark_plonk::constraint_system::{StandardComposer, Variable};
+use ark_std::{marker::PhantomData, vec::Vec};
+
+/// Poseidon gadget built with the arithmetic gates of a PLONK composer. It
+/// mirrors the R1CS `CRHGadget`, with the parameters embedded as selector
+/// constants.
+pub struct PoseidonGadget<F: PrimeField, P: Rounds> {
+	field: PhantomData<F>,
+	params: PhantomData<P>,
+}
+
+impl<F: PrimeField, P: Rounds> PoseidonGadget<F, P> {
+	pub fn permute<E: TEModelParameters<BaseField = F>>(
+		composer: &mut StandardComposer<F, E>,
+		parameters: &PoseidonParameters<F>,
+		mut state: Vec<Variable>,
+	) -> Result<Vec<Variable>, PoseidonError> {
+		let width = P::WIDTH;
+		if state.len() != width
+			|| parameters.round_keys.len() < width * (P::FULL_ROUNDS + P::PARTIAL_ROUNDS)
+		{
+			return Err(PoseidonError::InvalidInputs);
+		}
+
+		let mut round_keys_offset = 0;
+
+		// full Sbox rounds
+		for _ in 0..(P::FULL_ROUNDS / 2) {
+			// Sbox layer
+			for i in 0..width {
+				let rk = parameters.round_keys[round_keys_offset];
+				state[i] = Self::add_constant(composer, state[i], rk);
+				state[i] = Self::synthesize_sbox(composer, state[i], P::FULL_SBOX)?;
+				round_keys_offset += 1;
+			}
+			// linear layer
+			state = Self::apply_linear_layer(composer, &state, &parameters.mds_matrix);
+		}
+
+		// middle partial Sbox rounds
+		for _ in 0..P::PARTIAL_ROUNDS {
+			for i in 0..width {
+				let rk = parameters.round_keys[round_keys_offset];
+				state[i] = Self::add_constant(composer, state[i], rk);
+				round_keys_offset += 1;
+			}
+			// partial Sbox layer, apply Sbox to only 1 element of the state.
+			state[0] = Self::synthesize_sbox(composer, state[0], P::PARTIAL_SBOX)?;
+			// linear layer
+			state = Self::apply_linear_layer(composer, &state, &parameters.mds_matrix);
+		}
+
+		// last full Sbox rounds
+		for _ in 0..(P::FULL_ROUNDS / 2) {
+			// Sbox layer
+			for i in 0..width {
+				let rk = parameters.round_keys[round_keys_offset];
+				state[i] = Self::add_constant(composer, state[i], rk);
+				state[i] = Self::synthesize_sbox(composer, state[i], P::FULL_SBOX)?;
+				round_keys_offset += 1;
+			}
+			// linear layer
+			state = Self::apply_linear_layer(composer, &state, &parameters.mds_matrix);
+		}
+
+		Ok(state)
+	}
+
+	/// Hashes up to `WIDTH - CAPACITY` field elements, the rate, into the
+	/// state after its capacity, padding it with zeros, and returns the first
+	/// element of the permuted state. Fails on longer inputs, which the R1CS
+	/// gadget would absorb in several blocks.
+	pub fn evaluate<E: TEModelParameters<BaseField = F>>(
+		composer: &mut StandardComposer<F, E>,
+		parameters: &PoseidonParameters<F>,
+		inputs: &[Variable],
+	) -> Result<Variable, PoseidonError> {
+		if inputs.len() > P::WIDTH - P::CAPACITY {
+			return Err(PoseidonError::InvalidInputs);
+		}
+
+		let mut buffer = vec![composer.zero_var(); P::WIDTH];
+		buffer[P::CAPACITY..]
+			.iter_mut()
+			.zip(inputs)
+			.for_each(|(b, l_b)| *b = *l_b);
+
+		let result = Self::permute(composer, parameters, buffer)?;
+		result.get(0).cloned().ok_or(PoseidonError::InvalidInputs)
+	}
+
+	fn add_constant<E: TEModelParameters<BaseField = F>>(
+		composer: &mut StandardComposer<F, E>,
+		x: Variable,
+		c: F,
+	) -> Variable {
+		let zero = composer.zero_var();
+		composer.arithmetic_gate(|gate| {
+			gate.witness(x, zero, None)
+				.add(F::one(), F::zero())
+				.constant(c)
+		})
+	}
+
+	fn mul<E: TEModelParameters<BaseField = F>>(
+		composer: &mut StandardComposer<F, E>,
+		a: Variable,
+		b: Variable,
+	) -> Variable {
+		composer.arithmetic_gate(|gate| gate.witness(a, b, None).mul(F::one()))
+	}
+
+	fn synthesize_sbox<E: TEModelParameters<BaseField = F>>(
+		composer: &mut StandardComposer<F, E>,
+		x: Variable,
+		sbox: PoseidonSbox,
+	) -> Result<Variable, PoseidonError> {
+		match sbox {
+			PoseidonSbox::Exponentiation(val) => {
+				let res = match val {
+					3 => {
+						let sqr = Self::mul(composer, x, x);
+						Self::mul(composer, sqr, x)
+					}
+					5 => {
+						let sqr = Self::mul(composer, x, x);
+						let quad = Self::mul(composer, sqr, sqr);
+						Self::mul(composer, quad, x)
+					}
+					17 => {
+						let sqr = Self::mul(composer, x, x);
+						let quad = Self::mul(composer, sqr, sqr);
+						let eighth = Self::mul(composer, quad, quad);
+						let sixteenth = Self::mul(composer, eighth, eighth);
+						Self::mul(composer, sixteenth, x)
+					}
+					n => return Err(PoseidonError::InvalidSboxSize(n)),
+				};
+				Ok(res)
+			}
+			PoseidonSbox::Inverse => {
+				// Witness the inverse and enforce x * x^-1 = 1
+				let inverse = composer
+					.value_of_var(x)
+					.inverse()
+					.ok_or(PoseidonError::ApplySboxFailed)?;
+				let inverse_var = composer.add_input(inverse);
+				let zero = composer.zero_var();
+				composer.arithmetic_gate(|gate| {
+					gate.witness(x, inverse_var, Some(zero))
+						.mul(F::one())
+						.constant(-F::one())
+				});
+				Ok(inverse_var)
+			}
+		}
+	}
+
+	fn apply_linear_layer<E: TEModelParameters<BaseField = F>>(
+		composer: &mut StandardComposer<F, E>,
+		state: &Vec<Variable>,
+		mds_matrix: &Vec<Vec<F>>,
+	) -> Vec<Variable> {
+		let mut new_state: Vec<Variable> = Vec::new();
+		for i in 0..state.len() {
+			// Accumulate the row with one addition gate per element
+			let mut sc = composer.zero_var();
+			for j in 0..state.len() {
+				let mij = mds_matrix[i][j];
+				sc = composer
+					.arithmetic_gate(|gate| gate.witness(sc, state[j], None).add(F::one(), mij));
+			}
+			new_state.push(sc);
+		}
+		new_state
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		poseidon::CRH,
+		setup::common::PoseidonRounds3Capacity1,
+		utils::{get_mds_poseidon_bn254_x5_3, get_rounds_poseidon_bn254_x5_3},
+	};
+	use ark_crypto_primitives::crh::CRH as CRHTrait;
+	use ark_ed_on_bn254::{EdwardsParameters, Fq};
+	use ark_ff::to_bytes;
+	use ark_std::{test_rng, UniformRand};
+
+	#[derive(Default, Clone)]
+	struct PoseidonRounds3;
+
+	impl Rounds for PoseidonRounds3 {
+		const FULL_ROUNDS: usize = 8;
+		const PARTIAL_ROUNDS: usize = 57;
+		const SBOX: PoseidonSbox = PoseidonSbox::Exponentiation(5);
+		const WIDTH: usize = 3;
+	}
+
+	type PoseidonCRH3 = CRH<Fq, PoseidonRounds3>;
+	type PoseidonGadget3 = PoseidonGadget<Fq, PoseidonRounds3>;
+
+	#[test]
+	fn test_plonk_poseidon_native_equality() {
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bn254_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bn254_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+
+		let inputs = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let inp = to_bytes![inputs].unwrap();
+		let res = <PoseidonCRH3 as CRHTrait>::evaluate(&params, &inp).unwrap();
+
+		let mut composer = StandardComposer::<Fq, EdwardsParameters>::new();
+		let input_vars: Vec<Variable> = inputs.iter().map(|x| composer.add_input(*x)).collect();
+		let res_var = PoseidonGadget3::evaluate(&mut composer, &params, &input_vars).unwrap();
+
+		assert_eq!(res, composer.value_of_var(res_var));
+		composer.check_circuit_satisfied();
+	}
+
+	#[test]
+	fn test_plonk_poseidon_accepts_up_to_rate_inputs() {
+		let rng = &mut test_rng();
+		let rounds = get_rounds_poseidon_bn254_x5_3::<Fq>();
+		let mds = get_mds_poseidon_bn254_x5_3::<Fq>();
+		let params = PoseidonParameters::<Fq>::new(rounds, mds);
+
+		// The rate of two elements fills the state after the capacity
+		let inputs = vec![Fq::rand(rng), Fq::rand(rng)];
+		let inp = to_bytes![inputs].unwrap();
+		let res = <CRH<Fq, PoseidonRounds3Capacity1> as CRHTrait>::evaluate(&params, &inp).unwrap();
+
+		let mut composer = StandardComposer::<Fq, EdwardsParameters>::new();
+		let input_vars: Vec<Variable> = inputs.iter().map(|x| composer.add_input(*x)).collect();
+		let res_var = PoseidonGadget::<Fq, PoseidonRounds3Capacity1>::evaluate(
+			&mut composer,
+			&params,
+			&input_vars,
+		)
+		.unwrap();
+		assert_eq!(res, composer.value_of_var(res_var));
+		composer.check_circuit_satisfied();
+
+		// One more element would overwrite the capacity
+		let extra = composer.add_input(Fq::rand(rng));
+		let too_long = [input_vars[0], input_vars[1], extra];
+		assert!(matches!(
+			PoseidonGadget::<Fq, PoseidonRounds3Capacity1>::evaluate(
+				&mut composer,
+				&params,
+				&too_long
+			),
+			Err(PoseidonError::InvalidInputs)
+		));
+	}
+}
@@ -11,6 +11,13 @@ pub enum PoseidonSbox {
 	Inverse,
 }
 
+/// Defaults to x^5, which most of the shipped parameters use
+impl Default for PoseidonSbox {
+	fn default() -> Self {
+		PoseidonSbox::Exponentiation(5)
+	}
+}
+
 impl PoseidonSbox {
 	pub fn apply_sbox<F: PrimeField>(&self, elem: F) -> Result<F, PoseidonError> {
 		match self {
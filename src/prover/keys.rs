@@ -0,0 +1,266 @@
+use ark_crypto_primitives::{Error, SNARK};
+use ark_ec::PairingEngine;
+use ark_ff::{to_bytes, PrimeField};
+use ark_groth16::{Groth16, ProvingKey, VerifyingKey};
+use ark_relations::r1cs::{
+	ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisError, SynthesisMode,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{
+	marker::PhantomData,
+	rand::{CryptoRng, RngCore},
+};
+use blake2::{Blake2s, Digest};
+use std::{
+	fs::{self, File},
+	io::{BufReader, BufWriter, Read, Write},
+	path::{Path, PathBuf},
+	process,
+};
+
+/// Size of a circuit fingerprint, a Blake2s digest
+pub const FINGERPRINT_SIZE: usize = 32;
+
+/// Hashes the constraint matrices of `circuit`, which only depend on its
+/// shape (e.g. the height of its tree), not on its witness. Circuits of the
+/// same fingerprint share their keys.
+pub fn circuit_fingerprint<F: PrimeField, C: ConstraintSynthesizer<F>>(
+	circuit: C,
+) -> Result<[u8; FINGERPRINT_SIZE], Error> {
+	// Synthesized like the Groth16 setup does
+	let cs = ConstraintSystem::<F>::new_ref();
+	cs.set_optimization_goal(OptimizationGoal::Constraints);
+	cs.set_mode(SynthesisMode::Setup);
+	circuit.generate_constraints(cs.clone())?;
+	cs.finalize();
+	let matrices = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+
+	let mut hasher = Blake2s::new();
+	let sizes = [
+		matrices.num_instance_variables,
+		matrices.num_witness_variables,
+		matrices.num_constraints,
+	];
+	for size in sizes.iter() {
+		hasher.update(&(*size as u64).to_le_bytes());
+	}
+	for matrix in [&matrices.a, &matrices.b, &matrices.c].iter() {
+		for row in matrix.iter() {
+			hasher.update(&(row.len() as u64).to_le_bytes());
+			for (coeff, index) in row {
+				hasher.update(&to_bytes![coeff]?);
+				hasher.update(&(*index as u64).to_le_bytes());
+			}
+		}
+	}
+
+	let mut fingerprint = [0u8; FINGERPRINT_SIZE];
+	fingerprint.copy_from_slice(&hasher.finalize());
+	Ok(fingerprint)
+}
+
+/// Runs the Groth16 setup of a circuit once and caches its keys in a file,
+/// prefixed with the fingerprint of the circuit they were generated for.
+/// Later runs reload the keys, unless the circuit changed shape or the file
+/// can't be read, in which case the setup runs again and overwrites the file.
+/// The file is replaced at once, so a concurrent run never reads it halfway
+/// written.
+///
+/// The cached keys are read without checking that their points are in the
+/// right subgroups, so the file must be as trusted as the setup itself.
+pub struct KeyManager<E: PairingEngine> {
+	path: PathBuf,
+	engine: PhantomData<E>,
+}
+
+impl<E: PairingEngine> KeyManager<E> {
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self {
+			path: path.into(),
+			engine: PhantomData,
+		}
+	}
+
+	/// Returns the keys of `circuit`, from the cache if its fingerprint
+	/// matches, or from a new setup otherwise
+	pub fn load_or_setup<C, R>(
+		&self,
+		circuit: C,
+		rng: &mut R,
+	) -> Result<(ProvingKey<E>, VerifyingKey<E>), Error>
+	where
+		C: ConstraintSynthesizer<E::Fr> + Clone,
+		R: RngCore + CryptoRng,
+	{
+		let fingerprint = circuit_fingerprint(circuit.clone())?;
+		if let Some(pk) = self.load(&fingerprint)? {
+			let vk = pk.vk.clone();
+			return Ok((pk, vk));
+		}
+
+		let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit, rng)?;
+		self.store(&fingerprint, &pk)?;
+		Ok((pk, vk))
+	}
+
+	/// Reads the cached proving key, which holds the verifying key, if the
+	/// cache exists, was written for `fingerprint` and deserializes
+	fn load(&self, fingerprint: &[u8; FINGERPRINT_SIZE]) -> Result<Option<ProvingKey<E>>, Error> {
+		let file = match File::open(&self.path) {
+			Ok(file) => file,
+			Err(_) => return Ok(None),
+		};
+		let mut reader = BufReader::new(file);
+
+		let mut cached_fingerprint = [0u8; FINGERPRINT_SIZE];
+		if reader.read_exact(&mut cached_fingerprint).is_err() || cached_fingerprint != *fingerprint
+		{
+			return Ok(None);
+		}
+		Ok(ProvingKey::deserialize_unchecked(&mut reader).ok())
+	}
+
+	/// Writes the keys to a temporary file next to the cache, then renames
+	/// it over the cache
+	fn store(&self, fingerprint: &[u8; FINGERPRINT_SIZE], pk: &ProvingKey<E>) -> Result<(), Error> {
+		let mut tmp_path = self.path.clone().into_os_string();
+		tmp_path.push(format!(".{}.tmp", process::id()));
+		let tmp_path = PathBuf::from(tmp_path);
+
+		let res = write_keys(&tmp_path, fingerprint, pk)
+			.and_then(|_| fs::rename(&tmp_path, &self.path).map_err(Error::from));
+		if res.is_err() {
+			let _ = fs::remove_file(&tmp_path);
+		}
+		res
+	}
+}
+
+fn write_keys<E: PairingEngine>(
+	path: &Path,
+	fingerprint: &[u8; FINGERPRINT_SIZE],
+	pk: &ProvingKey<E>,
+) -> Result<(), Error> {
+	let mut writer = BufWriter::new(File::create(path)?);
+	writer.write_all(fingerprint)?;
+	pk.serialize_unchecked(&mut writer)?;
+	writer.flush()?;
+	Ok(())
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::{
+			constraints::{FieldHasherGadget, PoseidonHasherGadget},
+			PoseidonHasher,
+		},
+		merkle_tree::fixed_path::{constraints::PathVar, Path},
+		setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3},
+	};
+	use ark_bn254::{Bn254, Fr};
+	use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+	use ark_relations::r1cs::ConstraintSystemRef;
+	use ark_std::{test_rng, UniformRand};
+
+	type Hasher = PoseidonHasher<Fr, PoseidonRounds_x5_3>;
+	type HasherGadget = PoseidonHasherGadget<Fr, PoseidonRounds_x5_3>;
+
+	/// Hashes a leaf up a path of height `HEIGHT` to a public root
+	#[derive(Clone)]
+	struct RootCircuit<const HEIGHT: usize> {
+		hasher: Hasher,
+		leaf: Fr,
+		path: Path<Fr, Hasher, HEIGHT>,
+	}
+
+	impl<const HEIGHT: usize> RootCircuit<HEIGHT> {
+		fn random() -> Self {
+			let rng = &mut test_rng();
+			Self {
+				hasher: Hasher::new(setup_params_x5_3(Curve::Bn254)),
+				leaf: Fr::rand(rng),
+				path: Path::new([(Fr::rand(rng), Fr::rand(rng)); HEIGHT]),
+			}
+		}
+	}
+
+	impl<const HEIGHT: usize> ConstraintSynthesizer<Fr> for RootCircuit<HEIGHT> {
+		fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+			let root = FpVar::new_input(cs.clone(), || Ok(Fr::from(0u64)))?;
+			let hasher = HasherGadget::from_native(cs.clone(), self.hasher)?;
+			let leaf = FpVar::new_witness(cs.clone(), || Ok(self.leaf))?;
+			let path = PathVar::<Fr, HasherGadget, HEIGHT>::new_witness(cs, || Ok(self.path))?;
+			path.calculate_root(&leaf, &hasher)?.enforce_equal(&root)
+		}
+	}
+
+	/// Cache file for `test`, unique to this process
+	fn cache_path(test: &str) -> PathBuf {
+		let name = format!("arkworks-gadgets-{}-{}.bin", test, process::id());
+		std::env::temp_dir().join(name)
+	}
+
+	#[test]
+	fn should_cache_keys_per_circuit_shape() {
+		let rng = &mut test_rng();
+		let path = cache_path("cache-keys");
+		let _ = fs::remove_file(&path);
+
+		let circuit = RootCircuit::<2>::random();
+		let (pk, vk) = KeyManager::<Bn254>::new(&path)
+			.load_or_setup(circuit.clone(), rng)
+			.unwrap();
+		assert!(path.exists());
+
+		// Another manager reloads the same keys instead of running the setup
+		let (cached_pk, cached_vk) = KeyManager::<Bn254>::new(&path)
+			.load_or_setup(circuit, rng)
+			.unwrap();
+		assert_eq!(cached_pk, pk);
+		assert_eq!(cached_vk, vk);
+
+		// The witness doesn't change the fingerprint, the height does
+		assert_eq!(
+			circuit_fingerprint(RootCircuit::<2>::random()).unwrap(),
+			circuit_fingerprint(RootCircuit::<2> {
+				leaf: Fr::rand(rng),
+				..RootCircuit::random()
+			})
+			.unwrap()
+		);
+		let taller = RootCircuit::<3>::random();
+		assert_ne!(
+			circuit_fingerprint(taller.clone()).unwrap(),
+			circuit_fingerprint(RootCircuit::<2>::random()).unwrap()
+		);
+		let (taller_pk, _) = KeyManager::<Bn254>::new(&path)
+			.load_or_setup(taller, rng)
+			.unwrap();
+		assert_ne!(taller_pk, pk);
+		assert!(taller_pk.a_query.len() > pk.a_query.len());
+
+		fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn should_setup_again_on_corrupt_cache() {
+		let rng = &mut test_rng();
+		let path = cache_path("corrupt-cache");
+		let circuit = RootCircuit::<2>::random();
+
+		// The fingerprint matches, but the keys were cut short
+		let mut contents = circuit_fingerprint(circuit.clone()).unwrap().to_vec();
+		contents.extend_from_slice(&[1u8; 16]);
+		fs::write(&path, &contents).unwrap();
+
+		let manager = KeyManager::<Bn254>::new(&path);
+		let (pk, _) = manager.load_or_setup(circuit.clone(), rng).unwrap();
+		let (cached_pk, _) = manager.load_or_setup(circuit, rng).unwrap();
+		assert_eq!(cached_pk, pk);
+
+		fs::remove_file(&path).unwrap();
+	}
+}
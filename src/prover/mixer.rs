@@ -0,0 +1,301 @@
+use super::{generate_witness, PoseidonPath, PoseidonPathVar, PoseidonTree, WitnessAssignment};
+use crate::{
+	identity::constraints::Params as IdentityParams,
+	leaf::{
+		mixer::{
+			constraints::{MixerLeafGadget, PrivateVar},
+			MixerLeaf, Private,
+		},
+		LeafCreation,
+	},
+	merkle_tree::{constraints::NodeVar, Rc},
+	poseidon::{
+		constraints::{CRHGadget as PoseidonCRHGadget, PoseidonParametersVar},
+		PoseidonParameters, Rounds, CRH as PoseidonCRH,
+	},
+	Vec,
+};
+use ark_crypto_primitives::{Error, SNARK};
+use ark_ec::PairingEngine;
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{
+	marker::PhantomData,
+	rand::{CryptoRng, RngCore},
+};
+
+pub type MixerTree<F, H, const HEIGHT: usize> = PoseidonTree<F, H, HEIGHT>;
+pub type MixerPath<F, H, const HEIGHT: usize> = PoseidonPath<F, H, HEIGHT>;
+
+type Leaf<F, H> = MixerLeaf<F, PoseidonCRH<F, H>>;
+type LeafGadget<F, H> = MixerLeafGadget<F, PoseidonCRH<F, H>, PoseidonCRHGadget<F, H>, Leaf<F, H>>;
+
+/// Circuit proving knowledge of the secrets behind a leaf of the tree with
+/// the given root, and that the nullifier hash is derived from them.
+///
+/// Public inputs are allocated in the following order:
+/// 1. `root`
+/// 2. `nullifier_hash`
+#[derive(Clone)]
+pub struct MixerProverCircuit<F: PrimeField, H: Rounds, const HEIGHT: usize> {
+	params: PoseidonParameters<F>,
+	secrets: Private<F>,
+	path: MixerPath<F, H, HEIGHT>,
+	root: F,
+	nullifier_hash: F,
+}
+
+impl<F: PrimeField, H: Rounds, const HEIGHT: usize> ConstraintSynthesizer<F>
+	for MixerProverCircuit<F, H, HEIGHT>
+{
+	fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+		// Public inputs
+		let root_var = FpVar::<F>::new_input(cs.clone(), || Ok(self.root))?;
+		let nullifier_hash_var = FpVar::<F>::new_input(cs.clone(), || Ok(self.nullifier_hash))?;
+
+		// Constants, shared by the leaf and the path hashes
+		let params_var = Rc::new(PoseidonParametersVar::new_constant(
+			cs.clone(),
+			self.params,
+		)?);
+
+		// Private inputs
+		let secrets_var = PrivateVar::new_witness(cs.clone(), || Ok(self.secrets))?;
+		let path_var = PoseidonPathVar::<F, H, HEIGHT>::new_with_params(
+			cs.clone(),
+			&self.path,
+			AllocationMode::Witness,
+			params_var.clone(),
+			Rc::new(IdentityParams::default()),
+		)?;
+
+		// Creating the leaf and checking the membership inside the tree
+		let (leaf, nullifier) =
+			LeafGadget::<F, H>::create_leaf_and_nullifier(&params_var, &secrets_var)?;
+		let is_member = path_var.check_membership(&NodeVar::Inner(root_var), &leaf)?;
+
+		// Enforcing constraints
+		is_member.enforce_equal(&Boolean::TRUE)?;
+		nullifier.enforce_equal(&nullifier_hash_var)?;
+
+		Ok(())
+	}
+}
+
+/// Groth16 prover for the mixer, using a single Poseidon instance `H` for
+/// the leaf, the nullifier and the tree hashing.
+pub struct MixerProver<E: PairingEngine, H: Rounds, const HEIGHT: usize> {
+	params: PoseidonParameters<E::Fr>,
+	engine: PhantomData<E>,
+	rounds: PhantomData<H>,
+}
+
+impl<E: PairingEngine, H: Rounds, const HEIGHT: usize> MixerProver<E, H, HEIGHT> {
+	pub fn new(params: PoseidonParameters<E::Fr>) -> Self {
+		Self {
+			params,
+			engine: PhantomData,
+			rounds: PhantomData,
+		}
+	}
+
+	/// Creates the leaf and nullifier hash for the given secrets
+	pub fn create_leaf(&self, secrets: &Private<E::Fr>) -> Result<(E::Fr, E::Fr), Error> {
+		let leaf = Leaf::<E::Fr, H>::create_leaf(secrets, &(), &self.params)?;
+		let nullifier_hash = Leaf::<E::Fr, H>::create_nullifier(secrets, &self.params)?;
+		Ok((leaf, nullifier_hash))
+	}
+
+	/// Builds a tree over the given leaves, using the prover's parameters
+	pub fn create_tree(&self, leaves: &[E::Fr]) -> Result<MixerTree<E::Fr, H, HEIGHT>, Error> {
+		MixerTree::<E::Fr, H, HEIGHT>::new_sequential(
+			Rc::new(self.params.clone()),
+			Rc::new(()),
+			leaves,
+		)
+	}
+
+	/// Returns the public inputs in the order expected by the circuit:
+	/// `[root, nullifier_hash]`
+	pub fn public_inputs(root: E::Fr, nullifier_hash: E::Fr) -> Vec<E::Fr> {
+		vec![root, nullifier_hash]
+	}
+
+	/// Generates the circuit specific keys, using a random note as witness
+	pub fn setup<R: RngCore + CryptoRng>(
+		&self,
+		rng: &mut R,
+	) -> Result<(ProvingKey<E>, VerifyingKey<E>), Error> {
+		let secrets = Leaf::<E::Fr, H>::generate_secrets(rng)?;
+		let (leaf, nullifier_hash) = self.create_leaf(&secrets)?;
+		let tree = self.create_tree(&[leaf])?;
+		let circuit = MixerProverCircuit::<E::Fr, H, HEIGHT> {
+			params: self.params.clone(),
+			secrets,
+			path: tree.generate_membership_proof(0),
+			root: tree.root().inner(),
+			nullifier_hash,
+		};
+
+		Groth16::<E>::circuit_specific_setup(circuit, rng)
+	}
+
+	pub fn create_proof<R: RngCore + CryptoRng>(
+		&self,
+		pk: &ProvingKey<E>,
+		secrets: Private<E::Fr>,
+		path: MixerPath<E::Fr, H, HEIGHT>,
+		root: E::Fr,
+		nullifier_hash: E::Fr,
+		rng: &mut R,
+	) -> Result<Proof<E>, Error> {
+		let circuit = MixerProverCircuit::<E::Fr, H, HEIGHT> {
+			params: self.params.clone(),
+			secrets,
+			path,
+			root,
+			nullifier_hash,
+		};
+
+		Groth16::<E>::prove(pk, circuit, rng)
+	}
+
+	/// Synthesizes the circuit for the given inputs without proving it,
+	/// returning the assignment and whether it satisfies the constraints
+	pub fn generate_witness(
+		&self,
+		secrets: Private<E::Fr>,
+		path: MixerPath<E::Fr, H, HEIGHT>,
+		root: E::Fr,
+		nullifier_hash: E::Fr,
+	) -> Result<WitnessAssignment<E::Fr>, Error> {
+		let circuit = MixerProverCircuit::<E::Fr, H, HEIGHT> {
+			params: self.params.clone(),
+			secrets,
+			path,
+			root,
+			nullifier_hash,
+		};
+
+		Ok(generate_witness(circuit)?)
+	}
+
+	/// Verifies a proof against public inputs built with `public_inputs`
+	pub fn verify(vk: &VerifyingKey<E>, public_inputs: &[E::Fr], proof: &Proof<E>) -> bool {
+		Groth16::<E>::verify(vk, public_inputs, proof).unwrap_or(false)
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		prover::verify_batch,
+		setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3},
+	};
+	use ark_bn254::{Bn254, Fr as Bn254Fr};
+	use ark_std::{test_rng, UniformRand};
+
+	type Prover = MixerProver<Bn254, PoseidonRounds_x5_3, 10>;
+
+	#[test]
+	fn should_prove_and_verify_withdrawal() {
+		let rng = &mut test_rng();
+		let prover = Prover::new(setup_params_x5_3::<Bn254Fr>(Curve::Bn254));
+
+		let secrets = Private::generate(rng);
+		let (leaf, nullifier_hash) = prover.create_leaf(&secrets).unwrap();
+		let leaves = vec![Bn254Fr::rand(rng), leaf, Bn254Fr::rand(rng)];
+		let tree = prover.create_tree(&leaves).unwrap();
+		let root = tree.root().inner();
+		let path = tree.generate_membership_proof(1);
+
+		let (pk, vk) = prover.setup(rng).unwrap();
+		let proof = prover
+			.create_proof(&pk, secrets, path, root, nullifier_hash, rng)
+			.unwrap();
+
+		let public_inputs = Prover::public_inputs(root, nullifier_hash);
+		assert!(Prover::verify(&vk, &public_inputs, &proof));
+
+		// Swapping the public inputs must not verify
+		let swapped_inputs = Prover::public_inputs(nullifier_hash, root);
+		assert!(!Prover::verify(&vk, &swapped_inputs, &proof));
+	}
+
+	#[test]
+	fn should_generate_witness_and_find_wrong_nullifier() {
+		let rng = &mut test_rng();
+		let prover = Prover::new(setup_params_x5_3::<Bn254Fr>(Curve::Bn254));
+
+		let secrets = Private::generate(rng);
+		let (leaf, nullifier_hash) = prover.create_leaf(&secrets).unwrap();
+		let tree = prover.create_tree(&[leaf]).unwrap();
+		let root = tree.root().inner();
+		let path = tree.generate_membership_proof(0);
+
+		let assignment = prover
+			.generate_witness(secrets.clone(), path.clone(), root, nullifier_hash)
+			.unwrap();
+		assert!(assignment.is_satisfied);
+		assert!(assignment.unsatisfied.is_none());
+		assert_eq!(assignment.instance, vec![
+			Bn254Fr::from(1u64),
+			root,
+			nullifier_hash
+		]);
+		assert!(!assignment.witness.is_empty());
+
+		let wrong_nullifier_hash = Bn254Fr::rand(rng);
+		let assignment = prover
+			.generate_witness(secrets, path, root, wrong_nullifier_hash)
+			.unwrap();
+		assert!(!assignment.is_satisfied);
+		assert!(assignment.unsatisfied.is_some());
+	}
+
+	#[test]
+	fn should_verify_batch_of_withdrawals() {
+		let rng = &mut test_rng();
+		let prover = Prover::new(setup_params_x5_3::<Bn254Fr>(Curve::Bn254));
+		let (pk, vk) = prover.setup(rng).unwrap();
+
+		let notes = (0..4)
+			.map(|_| {
+				let secrets = Private::generate(rng);
+				let (leaf, nullifier_hash) = prover.create_leaf(&secrets).unwrap();
+				(secrets, leaf, nullifier_hash)
+			})
+			.collect::<Vec<_>>();
+		let leaves = notes.iter().map(|(_, leaf, _)| *leaf).collect::<Vec<_>>();
+		let tree = prover.create_tree(&leaves).unwrap();
+		let root = tree.root().inner();
+
+		let mut public_inputs = Vec::new();
+		let mut proofs = Vec::new();
+		for (i, (secrets, _, nullifier_hash)) in notes.into_iter().enumerate() {
+			let path = tree.generate_membership_proof(i as u64);
+			let proof = prover
+				.create_proof(&pk, secrets, path, root, nullifier_hash, rng)
+				.unwrap();
+			public_inputs.push(Prover::public_inputs(root, nullifier_hash));
+			proofs.push(proof);
+		}
+
+		assert!(verify_batch(&vk, &public_inputs[..3], &proofs[..3], rng));
+		assert!(verify_batch(&vk, &public_inputs, &proofs, rng));
+
+		// A single corrupted proof fails the whole batch
+		let mut corrupted = proofs.clone();
+		corrupted[3].c = proofs[2].c;
+		assert!(!verify_batch(&vk, &public_inputs, &corrupted, rng));
+
+		// As do public inputs that don't belong to their proof
+		let mut swapped = public_inputs.clone();
+		swapped.swap(2, 3);
+		assert!(!verify_batch(&vk, &swapped, &proofs, rng));
+	}
+}
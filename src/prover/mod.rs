@@ -0,0 +1,210 @@
+//! Proving wrappers that wire the gadgets of this crate into complete
+//! circuits, and expose setup/prove/verify helpers for them.
+
+use crate::{
+	identity::{constraints::CRHGadget as IdentityCRHGadget, CRH as IdentityCRH},
+	merkle_tree::{constraints::PathVar, Config as MerkleConfig, Path, Rc, SparseMerkleTree},
+	mimc::{
+		constraints::CRHGadget as MiMCCRHGadget, MiMCParameters, Rounds as MiMCRounds,
+		CRH as MiMCCRH,
+	},
+	poseidon::{constraints::CRHGadget as PoseidonCRHGadget, Rounds, CRH as PoseidonCRH},
+};
+use ark_crypto_primitives::Error;
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_groth16::{prepare_inputs, prepare_verifying_key, Proof, VerifyingKey};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+use ark_std::{
+	marker::PhantomData,
+	rand::{CryptoRng, RngCore},
+	string::String,
+	vec::Vec,
+};
+
+#[cfg(feature = "std")]
+pub mod keys;
+pub mod mixer;
+pub mod preimage;
+pub mod vanchor;
+
+/// Merkle tree configuration used by the provers: leaves are stored as is
+/// and inner nodes are hashed with Poseidon.
+#[derive(Clone)]
+pub struct PoseidonTreeConfig<F: PrimeField, H: Rounds, const HEIGHT: usize>(
+	PhantomData<F>,
+	PhantomData<H>,
+);
+
+impl<F: PrimeField, H: Rounds, const HEIGHT: usize> MerkleConfig
+	for PoseidonTreeConfig<F, H, HEIGHT>
+{
+	type H = PoseidonCRH<F, H>;
+	type LeafH = IdentityCRH<F>;
+
+	const HEIGHT: u8 = HEIGHT as u8;
+}
+
+pub type PoseidonTree<F, H, const HEIGHT: usize> = SparseMerkleTree<PoseidonTreeConfig<F, H, HEIGHT>>;
+pub type PoseidonPath<F, H, const HEIGHT: usize> = Path<PoseidonTreeConfig<F, H, HEIGHT>>;
+pub type PoseidonPathVar<F, H, const HEIGHT: usize> = PathVar<
+	F,
+	PoseidonTreeConfig<F, H, HEIGHT>,
+	PoseidonCRHGadget<F, H>,
+	IdentityCRHGadget<F>,
+>;
+
+/// Merkle tree configuration hashing inner nodes with MiMC, the counterpart
+/// of `PoseidonTreeConfig` for circuits built on the MiMC gadget.
+#[derive(Clone)]
+pub struct MiMCTreeConfig<F: PrimeField, P: MiMCRounds, const HEIGHT: usize>(
+	PhantomData<F>,
+	PhantomData<P>,
+);
+
+impl<F: PrimeField, P: MiMCRounds, const HEIGHT: usize> MerkleConfig
+	for MiMCTreeConfig<F, P, HEIGHT>
+{
+	type H = MiMCCRH<F, P>;
+	type LeafH = IdentityCRH<F>;
+
+	const HEIGHT: u8 = HEIGHT as u8;
+}
+
+pub type MiMCTree<F, P, const HEIGHT: usize> = SparseMerkleTree<MiMCTreeConfig<F, P, HEIGHT>>;
+pub type MiMCPath<F, P, const HEIGHT: usize> = Path<MiMCTreeConfig<F, P, HEIGHT>>;
+pub type MiMCPathVar<F, P, const HEIGHT: usize> =
+	PathVar<F, MiMCTreeConfig<F, P, HEIGHT>, MiMCCRHGadget<F, P>, IdentityCRHGadget<F>>;
+
+/// Builds a MiMC tree over `leaves`, stored as is at indices
+/// `0..leaves.len()`
+pub fn create_mimc_tree<F: PrimeField, P: MiMCRounds, const HEIGHT: usize>(
+	params: &MiMCParameters<F>,
+	leaves: &[F],
+) -> Result<MiMCTree<F, P, HEIGHT>, Error> {
+	MiMCTree::<F, P, HEIGHT>::new_sequential(Rc::new(params.clone()), Rc::new(()), leaves)
+}
+
+/// Values assigned to the variables of a circuit, along with whether they
+/// satisfy its constraints
+#[derive(Clone, Debug)]
+pub struct WitnessAssignment<F: PrimeField> {
+	pub is_satisfied: bool,
+	/// Name, or index if constraint names aren't tracked, of the first
+	/// unsatisfied constraint
+	pub unsatisfied: Option<String>,
+	/// Public inputs, starting with the constant `1`
+	pub instance: Vec<F>,
+	pub witness: Vec<F>,
+}
+
+/// Synthesizes `circuit` and checks its assignment without proving it, to
+/// find out which constraint fails on a given set of inputs
+pub fn generate_witness<F: PrimeField, C: ConstraintSynthesizer<F>>(
+	circuit: C,
+) -> Result<WitnessAssignment<F>, SynthesisError> {
+	let cs = ConstraintSystem::<F>::new_ref();
+	circuit.generate_constraints(cs.clone())?;
+
+	let is_satisfied = cs.is_satisfied()?;
+	let unsatisfied = cs.which_is_unsatisfied()?;
+	let inner = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+	Ok(WitnessAssignment {
+		is_satisfied,
+		unsatisfied,
+		instance: inner.instance_assignment.clone(),
+		witness: inner.witness_assignment.clone(),
+	})
+}
+
+/// Verifies Groth16 proofs sharing a verifying key at once, by checking a
+/// random linear combination of their pairing equations with a single final
+/// exponentiation. The batch fails if any proof is invalid, except with
+/// probability `1 / |Fr|`.
+pub fn verify_batch<E: PairingEngine, R: RngCore + CryptoRng>(
+	vk: &VerifyingKey<E>,
+	public_inputs: &[Vec<E::Fr>],
+	proofs: &[Proof<E>],
+	rng: &mut R,
+) -> bool {
+	if public_inputs.len() != proofs.len() {
+		return false;
+	}
+
+	let pvk = prepare_verifying_key(vk);
+	let mut pairs = Vec::with_capacity(proofs.len() + 2);
+	let mut r_sum = E::Fr::zero();
+	let mut inputs_sum = E::G1Projective::zero();
+	let mut c_sum = E::G1Projective::zero();
+	for (inputs, proof) in public_inputs.iter().zip(proofs) {
+		let prepared_inputs = match prepare_inputs(&pvk, inputs) {
+			Ok(prepared_inputs) => prepared_inputs,
+			Err(_) => return false,
+		};
+
+		let r = E::Fr::rand(rng);
+		r_sum += r;
+		inputs_sum += prepared_inputs.mul(r.into_repr());
+		c_sum += proof.c.mul(r.into_repr());
+		pairs.push((proof.a.mul(r.into_repr()).into_affine().into(), proof.b.into()));
+	}
+	pairs.push((inputs_sum.into_affine().into(), pvk.gamma_g2_neg_pc.clone()));
+	pairs.push((c_sum.into_affine().into(), pvk.delta_g2_neg_pc.clone()));
+
+	let qap = E::miller_loop(pairs.iter());
+	match E::final_exponentiation(&qap) {
+		Some(test) => test == pvk.alpha_g1_beta_g2.pow(r_sum.into_repr()),
+		None => false,
+	}
+}
+
+#[cfg(feature = "default_mimc")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		identity::constraints::Params as IdentityParams,
+		merkle_tree::constraints::NodeVar,
+		mimc::constraints::MiMCParametersVar,
+		setup::common::{setup_mimc_220, Curve, MiMCRounds_220_3},
+	};
+	use ark_bn254::Fr;
+	use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+
+	type Tree = MiMCTree<Fr, MiMCRounds_220_3, 3>;
+	type TreePathVar = MiMCPathVar<Fr, MiMCRounds_220_3, 3>;
+
+	#[test]
+	fn should_verify_mimc_tree_membership_in_circuit() {
+		let rng = &mut ark_std::test_rng();
+		let params = setup_mimc_220::<Fr>(Curve::Bn254);
+		let leaves = (0..5).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+		let tree: Tree = create_mimc_tree(&params, &leaves).unwrap();
+		let root = tree.root().inner();
+		let path = tree.generate_membership_proof(3);
+		assert_eq!(path.path.len(), 3);
+
+		let verify = |leaf: Fr| {
+			let cs = ConstraintSystem::<Fr>::new_ref();
+			let params_var = MiMCParametersVar::new_constant(cs.clone(), &params).unwrap();
+			let path_var = TreePathVar::new_with_params(
+				cs.clone(),
+				&path,
+				AllocationMode::Witness,
+				Rc::new(params_var),
+				Rc::new(IdentityParams::default()),
+			)
+			.unwrap();
+			let root_var = NodeVar::Inner(FpVar::new_input(cs.clone(), || Ok(root)).unwrap());
+			let leaf_var = FpVar::new_witness(cs.clone(), || Ok(leaf)).unwrap();
+			path_var
+				.check_membership(&root_var, leaf_var)
+				.unwrap()
+				.enforce_equal(&Boolean::TRUE)
+				.unwrap();
+			cs.is_satisfied().unwrap()
+		};
+		assert!(verify(leaves[3]));
+		assert!(!verify(leaves[2]));
+	}
+}
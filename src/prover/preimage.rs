@@ -0,0 +1,163 @@
+use crate::{
+	poseidon::{
+		constraints::{CRHGadget as PoseidonCRHGadget, PoseidonParametersVar},
+		PoseidonParameters, Rounds, CRH as PoseidonCRH,
+	},
+	Vec,
+};
+use ark_crypto_primitives::{crh::CRHGadget, Error, CRH, SNARK};
+use ark_ec::PairingEngine;
+use ark_ff::{to_bytes, PrimeField};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{
+	marker::PhantomData,
+	rand::{CryptoRng, RngCore},
+	UniformRand,
+};
+
+/// Gadget proving knowledge of a preimage of a Poseidon digest, hashed the
+/// way `PreimageProver::digest` hashes it natively
+pub struct PreimageGadget<F: PrimeField, H: Rounds> {
+	field: PhantomData<F>,
+	rounds: PhantomData<H>,
+}
+
+impl<F: PrimeField, H: Rounds> PreimageGadget<F, H> {
+	/// Enforces that `preimage` hashes to `digest`
+	pub fn enforce_preimage(
+		params: &PoseidonParametersVar<F>,
+		preimage: &FpVar<F>,
+		digest: &FpVar<F>,
+	) -> Result<(), SynthesisError> {
+		let bytes = preimage.to_bytes()?;
+		let computed = PoseidonCRHGadget::<F, H>::evaluate(params, &bytes)?;
+		computed.enforce_equal(digest)
+	}
+}
+
+/// Circuit proving knowledge of a preimage of `digest`, its single public
+/// input
+#[derive(Clone)]
+pub struct PreimageCircuit<F: PrimeField, H: Rounds> {
+	params: PoseidonParameters<F>,
+	preimage: F,
+	digest: F,
+	rounds: PhantomData<H>,
+}
+
+impl<F: PrimeField, H: Rounds> ConstraintSynthesizer<F> for PreimageCircuit<F, H> {
+	fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+		// Public inputs
+		let digest_var = FpVar::<F>::new_input(cs.clone(), || Ok(self.digest))?;
+
+		// Constants
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), self.params)?;
+
+		// Private inputs
+		let preimage_var = FpVar::<F>::new_witness(cs, || Ok(self.preimage))?;
+
+		PreimageGadget::<F, H>::enforce_preimage(&params_var, &preimage_var, &digest_var)
+	}
+}
+
+/// Groth16 prover for `PreimageCircuit`
+pub struct PreimageProver<E: PairingEngine, H: Rounds> {
+	params: PoseidonParameters<E::Fr>,
+	engine: PhantomData<E>,
+	rounds: PhantomData<H>,
+}
+
+impl<E: PairingEngine, H: Rounds> PreimageProver<E, H> {
+	pub fn new(params: PoseidonParameters<E::Fr>) -> Self {
+		Self {
+			params,
+			engine: PhantomData,
+			rounds: PhantomData,
+		}
+	}
+
+	/// Hashes `preimage` into the digest the circuit checks it against
+	pub fn digest(&self, preimage: E::Fr) -> Result<E::Fr, Error> {
+		let bytes = to_bytes![preimage]?;
+		PoseidonCRH::<E::Fr, H>::evaluate(&self.params, &bytes)
+	}
+
+	fn circuit(&self, preimage: E::Fr, digest: E::Fr) -> PreimageCircuit<E::Fr, H> {
+		PreimageCircuit {
+			params: self.params.clone(),
+			preimage,
+			digest,
+			rounds: PhantomData,
+		}
+	}
+
+	/// Generates the circuit specific keys, using a random preimage as
+	/// witness
+	pub fn setup<R: RngCore + CryptoRng>(
+		&self,
+		rng: &mut R,
+	) -> Result<(ProvingKey<E>, VerifyingKey<E>), Error> {
+		let preimage = E::Fr::rand(rng);
+		let digest = self.digest(preimage)?;
+		Groth16::<E>::circuit_specific_setup(self.circuit(preimage, digest), rng)
+	}
+
+	/// Proves knowledge of `preimage`, hashing it for the public digest
+	pub fn create_proof<R: RngCore + CryptoRng>(
+		&self,
+		pk: &ProvingKey<E>,
+		preimage: E::Fr,
+		rng: &mut R,
+	) -> Result<Proof<E>, Error> {
+		let digest = self.digest(preimage)?;
+		Groth16::<E>::prove(pk, self.circuit(preimage, digest), rng)
+	}
+
+	/// Verifies a proof of knowledge of a preimage of `digest`
+	pub fn verify(vk: &VerifyingKey<E>, digest: E::Fr, proof: &Proof<E>) -> bool {
+		let public_inputs: Vec<E::Fr> = vec![digest];
+		Groth16::<E>::verify(vk, &public_inputs, proof).unwrap_or(false)
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::setup::common::{setup_params_x5_3, Curve, PoseidonRounds_x5_3};
+	use ark_bn254::{Bn254, Fr as Bn254Fr};
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::test_rng;
+
+	type Prover = PreimageProver<Bn254, PoseidonRounds_x5_3>;
+
+	#[test]
+	fn should_prove_knowledge_of_preimage() {
+		let rng = &mut test_rng();
+		let prover = Prover::new(setup_params_x5_3::<Bn254Fr>(Curve::Bn254));
+		let (pk, vk) = prover.setup(rng).unwrap();
+
+		let preimage = Bn254Fr::rand(rng);
+		let digest = prover.digest(preimage).unwrap();
+		let proof = prover.create_proof(&pk, preimage, rng).unwrap();
+		assert!(Prover::verify(&vk, digest, &proof));
+
+		let wrong_digest = prover.digest(preimage + Bn254Fr::from(1u64)).unwrap();
+		assert!(!Prover::verify(&vk, wrong_digest, &proof));
+	}
+
+	#[test]
+	fn should_reject_wrong_preimage() {
+		let rng = &mut test_rng();
+		let prover = Prover::new(setup_params_x5_3::<Bn254Fr>(Curve::Bn254));
+		let digest = prover.digest(Bn254Fr::rand(rng)).unwrap();
+
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let circuit = prover.circuit(Bn254Fr::rand(rng), digest);
+		circuit.generate_constraints(cs.clone()).unwrap();
+		assert_eq!(cs.num_instance_variables(), 2);
+		assert!(!cs.is_satisfied().unwrap());
+	}
+}
@@ -0,0 +1,806 @@
+use super::{PoseidonPath, PoseidonPathVar, PoseidonTree};
+use crate::{
+	circuit::vanchor::{
+		decompose_signed_amount, enforce_balance, enforce_commitment, hash, hash_var, AMOUNT_BITS,
+	},
+	commitment::poseidon::Commitment,
+	gadget_utils::enforce_chain_id,
+	identity::constraints::Params as IdentityParams,
+	merkle_tree::{constraints::NodeVar, Rc},
+	poseidon::{constraints::PoseidonParametersVar, PoseidonParameters, Rounds},
+	range::{enforce_bit_length, enforce_less_than},
+	set::membership::constraints::check_set_membership,
+	Vec,
+};
+use ark_crypto_primitives::{CryptoError, Error, SNARK};
+use ark_ec::PairingEngine;
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_r1cs_std::{
+	fields::{fp::FpVar, FieldVar},
+	prelude::*,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{
+	marker::PhantomData,
+	rand::{CryptoRng, Rng, RngCore},
+};
+
+#[cfg(feature = "parallel")]
+use ark_std::string::{String, ToString};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// An unspent transaction output of the variable anchor.
+///
+/// `commitment = H(chain_id, amount, public_key, blinding)` where
+/// `public_key = H(private_key)`. Once the UTXO is inserted in a tree at
+/// `index`, its nullifier is `H(commitment, index, H(private_key, commitment, index))`.
+#[derive(Clone, Debug)]
+pub struct Utxo<F: PrimeField> {
+	pub chain_id: F,
+	pub amount: F,
+	pub blinding: F,
+	pub private_key: F,
+	pub public_key: F,
+	pub commitment: F,
+	pub index: Option<u64>,
+	pub nullifier: Option<F>,
+}
+
+/// Circuit for a variable anchor transaction with `INS` inputs and `OUTS`
+/// outputs, spending from a tree whose root belongs to a set of
+/// `BRIDGE_SIZE` roots (one per linked chain).
+///
+/// Public inputs are allocated in the following order:
+/// 1. `public_amount`
+/// 2. `ext_data_hash`
+/// 3. input nullifiers, `INS` elements
+/// 4. output commitments, `OUTS` elements
+/// 5. `chain_id`
+/// 6. root set, `BRIDGE_SIZE` elements
+#[derive(Clone)]
+pub struct VAnchorCircuit<
+	F: PrimeField,
+	H: Rounds,
+	const HEIGHT: usize,
+	const BRIDGE_SIZE: usize,
+	const INS: usize,
+	const OUTS: usize,
+> {
+	params: PoseidonParameters<F>,
+	public_amount: F,
+	ext_data_hash: F,
+	chain_id: F,
+	root_set: Vec<F>,
+	in_root: F,
+	in_utxos: Vec<Utxo<F>>,
+	in_paths: Vec<PoseidonPath<F, H, HEIGHT>>,
+	out_utxos: Vec<Utxo<F>>,
+	max_amount: Option<F>,
+}
+
+impl<
+		F: PrimeField,
+		H: Rounds,
+		const HEIGHT: usize,
+		const BRIDGE_SIZE: usize,
+		const INS: usize,
+		const OUTS: usize,
+	> VAnchorCircuit<F, H, HEIGHT, BRIDGE_SIZE, INS, OUTS>
+{
+	/// Public inputs in the order they are allocated by the circuit
+	pub fn public_inputs(&self) -> Vec<F> {
+		let mut inputs = vec![self.public_amount, self.ext_data_hash];
+		inputs.extend(self.in_utxos.iter().map(|u| u.nullifier.unwrap_or_default()));
+		inputs.extend(self.out_utxos.iter().map(|u| u.commitment));
+		inputs.push(self.chain_id);
+		inputs.extend(self.root_set.iter().cloned());
+		inputs
+	}
+}
+
+impl<
+		F: PrimeField,
+		H: Rounds,
+		const HEIGHT: usize,
+		const BRIDGE_SIZE: usize,
+		const INS: usize,
+		const OUTS: usize,
+	> ConstraintSynthesizer<F> for VAnchorCircuit<F, H, HEIGHT, BRIDGE_SIZE, INS, OUTS>
+{
+	fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+		assert_eq!(self.root_set.len(), BRIDGE_SIZE);
+		assert_eq!(self.in_utxos.len(), INS);
+		assert_eq!(self.in_paths.len(), INS);
+		assert_eq!(self.out_utxos.len(), OUTS);
+
+		let nullifiers: Vec<F> = self
+			.in_utxos
+			.iter()
+			.map(|u| u.nullifier.unwrap_or_default())
+			.collect();
+		let commitments: Vec<F> = self.out_utxos.iter().map(|u| u.commitment).collect();
+
+		// Public inputs
+		let public_amount_var = FpVar::<F>::new_input(cs.clone(), || Ok(self.public_amount))?;
+		// The verifier supplies the public inputs, which binds the external data
+		// hash to the proof without any constraint on it
+		let _ext_data_hash_var = FpVar::<F>::new_input(cs.clone(), || Ok(self.ext_data_hash))?;
+		let nullifiers_var = Vec::<FpVar<F>>::new_input(cs.clone(), || Ok(nullifiers))?;
+		let commitments_var = Vec::<FpVar<F>>::new_input(cs.clone(), || Ok(commitments))?;
+		let chain_id_var = FpVar::<F>::new_input(cs.clone(), || Ok(self.chain_id))?;
+		let root_set_var = Vec::<FpVar<F>>::new_input(cs.clone(), || Ok(self.root_set))?;
+
+		// Constants
+		let params_var = Rc::new(PoseidonParametersVar::new_constant(
+			cs.clone(),
+			self.params,
+		)?);
+		let max_amount_var = self
+			.max_amount
+			.map(|max_amount| FpVar::<F>::new_constant(cs.clone(), max_amount))
+			.transpose()?;
+
+		// Inputs: each non-dummy input must be in a tree whose root is in the set
+		let mut in_amounts = Vec::with_capacity(INS);
+		for (i, (utxo, path)) in self.in_utxos.iter().zip(self.in_paths.iter()).enumerate() {
+			let amount = FpVar::new_witness(cs.clone(), || Ok(utxo.amount))?;
+			let blinding = FpVar::new_witness(cs.clone(), || Ok(utxo.blinding))?;
+			let private_key = FpVar::new_witness(cs.clone(), || Ok(utxo.private_key))?;
+			let in_chain_id = FpVar::new_witness(cs.clone(), || Ok(utxo.chain_id))?;
+			let index = FpVar::new_witness(cs.clone(), || {
+				Ok(F::from(utxo.index.unwrap_or_default()))
+			})?;
+			let root = FpVar::new_witness(cs.clone(), || Ok(self.in_root))?;
+			let path_var = PoseidonPathVar::<F, H, HEIGHT>::new_with_params(
+				cs.clone(),
+				path,
+				AllocationMode::Witness,
+				params_var.clone(),
+				Rc::new(IdentityParams::default()),
+			)?;
+
+			enforce_bit_length(&amount, AMOUNT_BITS)?;
+			enforce_chain_id(&in_chain_id, &chain_id_var)?;
+
+			let public_key = hash_var::<F, H>(&params_var, &[private_key.clone()])?;
+			let commitment = hash_var::<F, H>(&params_var, &[
+				in_chain_id,
+				amount.clone(),
+				public_key,
+				blinding,
+			])?;
+			let signature =
+				hash_var::<F, H>(&params_var, &[private_key, commitment.clone(), index.clone()])?;
+			let nullifier =
+				hash_var::<F, H>(&params_var, &[commitment.clone(), index, signature])?;
+			nullifier.enforce_equal(&nullifiers_var[i])?;
+
+			let is_member = path_var.check_membership(&NodeVar::Inner(root.clone()), &commitment)?;
+			let is_known_root = check_set_membership(&root, &root_set_var)?;
+			let is_dummy = amount.is_eq(&FpVar::zero())?;
+			is_member
+				.and(&is_known_root)?
+				.or(&is_dummy)?
+				.enforce_equal(&Boolean::TRUE)?;
+
+			in_amounts.push(amount);
+		}
+
+		// Nullifiers must be pairwise distinct
+		for i in 0..INS {
+			for j in (i + 1)..INS {
+				nullifiers_var[i].enforce_not_equal(&nullifiers_var[j])?;
+			}
+		}
+
+		// Outputs: commitments must open to the witnessed amounts
+		let mut out_amounts = Vec::with_capacity(OUTS);
+		for (i, utxo) in self.out_utxos.iter().enumerate() {
+			let amount = FpVar::new_witness(cs.clone(), || Ok(utxo.amount))?;
+			let blinding = FpVar::new_witness(cs.clone(), || Ok(utxo.blinding))?;
+			let public_key = FpVar::new_witness(cs.clone(), || Ok(utxo.public_key))?;
+			let out_chain_id = FpVar::new_witness(cs.clone(), || Ok(utxo.chain_id))?;
+
+			enforce_bit_length(&amount, AMOUNT_BITS)?;
+			if let Some(max_amount_var) = &max_amount_var {
+				enforce_less_than(&amount, max_amount_var)?;
+			}
+
+			enforce_commitment::<F, H>(
+				&commitments_var[i],
+				&[out_chain_id, amount.clone(), public_key, blinding],
+				&params_var,
+			)?;
+
+			out_amounts.push(amount);
+		}
+
+		// Balance: inputs plus the public amount must equal the outputs
+		decompose_signed_amount(&public_amount_var)?;
+		enforce_balance(&in_amounts, &out_amounts, &public_amount_var)?;
+
+		Ok(())
+	}
+}
+
+/// Groth16 prover for the variable anchor, using a single Poseidon
+/// instance `H` (of width at least 5) for every hash in the circuit.
+pub struct VAnchorR1CSProver<
+	E: PairingEngine,
+	H: Rounds,
+	const HEIGHT: usize,
+	const BRIDGE_SIZE: usize,
+	const INS: usize,
+	const OUTS: usize,
+> {
+	params: PoseidonParameters<E::Fr>,
+	max_amount: Option<E::Fr>,
+	engine: PhantomData<E>,
+	rounds: PhantomData<H>,
+}
+
+impl<
+		E: PairingEngine,
+		H: Rounds,
+		const HEIGHT: usize,
+		const BRIDGE_SIZE: usize,
+		const INS: usize,
+		const OUTS: usize,
+	> VAnchorR1CSProver<E, H, HEIGHT, BRIDGE_SIZE, INS, OUTS>
+{
+	/// Creates a prover whose output amounts are only bounded by their bit
+	/// length of `AMOUNT_BITS`
+	pub fn new(params: PoseidonParameters<E::Fr>) -> Self {
+		Self {
+			params,
+			max_amount: None,
+			engine: PhantomData,
+			rounds: PhantomData,
+		}
+	}
+
+	/// Requires every output amount to be strictly below `max_amount`, such
+	/// as the total supply of the token. The bound is a constant of the
+	/// circuit, so keys must be generated again after changing it. The
+	/// comparison decomposes each output amount in bits again, at the cost
+	/// of several hundred constraints per output.
+	pub fn with_max_amount(mut self, max_amount: E::Fr) -> Self {
+		self.max_amount = Some(max_amount);
+		self
+	}
+
+	/// Creates a UTXO with a fresh keypair and blinding. The nullifier is
+	/// only computed if the index of the UTXO in its tree is known.
+	pub fn create_utxo<R: Rng>(
+		&self,
+		chain_id: u64,
+		amount: E::Fr,
+		index: Option<u64>,
+		rng: &mut R,
+	) -> Result<Utxo<E::Fr>, Error> {
+		let chain_id = E::Fr::from(chain_id);
+		let blinding = E::Fr::rand(rng);
+		let private_key = E::Fr::rand(rng);
+		let public_key = hash::<_, H>(&self.params, &[private_key])?;
+		let commitment = Commitment::<_, H>::commit_fields(&self.params, &[
+			chain_id, amount, public_key, blinding,
+		])?;
+
+		let mut utxo = Utxo {
+			chain_id,
+			amount,
+			blinding,
+			private_key,
+			public_key,
+			commitment,
+			index: None,
+			nullifier: None,
+		};
+		if let Some(index) = index {
+			self.set_index(&mut utxo, index)?;
+		}
+		Ok(utxo)
+	}
+
+	/// Sets the index of the UTXO in its tree, and derives its nullifier
+	pub fn set_index(&self, utxo: &mut Utxo<E::Fr>, index: u64) -> Result<(), Error> {
+		set_index::<_, H>(&self.params, utxo, index)
+	}
+
+	/// Recomputes the commitment of each input UTXO, sets its index, deriving
+	/// its nullifier, and generates its membership proof in `tree`. Returns
+	/// the spent UTXOs and their paths in the order of `in_utxos`.
+	pub fn prepare_inputs(
+		&self,
+		tree: &PoseidonTree<E::Fr, H, HEIGHT>,
+		in_utxos: &[Utxo<E::Fr>],
+		in_indices: &[u64],
+	) -> Result<(Vec<Utxo<E::Fr>>, Vec<PoseidonPath<E::Fr, H, HEIGHT>>), Error> {
+		if in_utxos.len() != in_indices.len() {
+			return Err(CryptoError::IncorrectInputLength(in_indices.len()).into());
+		}
+		let mut in_utxos = in_utxos.to_vec();
+		let in_paths = in_utxos
+			.iter_mut()
+			.zip(in_indices.iter())
+			.map(|(utxo, index)| prepare_input::<_, H, HEIGHT>(&self.params, tree, utxo, *index))
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok((in_utxos, in_paths))
+	}
+
+	/// Builds a tree over the given leaves, using the prover's parameters
+	pub fn create_tree(
+		&self,
+		leaves: &[E::Fr],
+	) -> Result<PoseidonTree<E::Fr, H, HEIGHT>, Error> {
+		PoseidonTree::<E::Fr, H, HEIGHT>::new_sequential(
+			Rc::new(self.params.clone()),
+			Rc::new(()),
+			leaves,
+		)
+	}
+
+	/// Returns the public inputs in the order expected by the circuit, see
+	/// `VAnchorCircuit` for the layout.
+	pub fn public_inputs(
+		public_amount: E::Fr,
+		ext_data_hash: E::Fr,
+		in_nullifiers: &[E::Fr; INS],
+		out_commitments: &[E::Fr; OUTS],
+		chain_id: E::Fr,
+		root_set: &[E::Fr; BRIDGE_SIZE],
+	) -> Vec<E::Fr> {
+		let mut inputs = vec![public_amount, ext_data_hash];
+		inputs.extend_from_slice(in_nullifiers);
+		inputs.extend_from_slice(out_commitments);
+		inputs.push(chain_id);
+		inputs.extend_from_slice(root_set);
+		inputs
+	}
+
+	/// Generates the circuit specific keys, using a transaction made of
+	/// dummy (zero amount) UTXOs as witness
+	pub fn setup<R: RngCore + CryptoRng>(
+		&self,
+		rng: &mut R,
+	) -> Result<(ProvingKey<E>, VerifyingKey<E>), Error> {
+		let chain_id = 0u64;
+		let mut in_utxos = Vec::with_capacity(INS);
+		for i in 0..INS {
+			in_utxos.push(self.create_utxo(chain_id, E::Fr::zero(), Some(i as u64), rng)?);
+		}
+		let mut out_utxos = Vec::with_capacity(OUTS);
+		for _ in 0..OUTS {
+			out_utxos.push(self.create_utxo(chain_id, E::Fr::zero(), None, rng)?);
+		}
+		let tree = self.create_tree(&[E::Fr::zero()])?;
+		let in_root = tree.root().inner();
+		let in_paths = (0..INS)
+			.map(|i| tree.generate_membership_proof(i as u64))
+			.collect();
+
+		let circuit = VAnchorCircuit::<E::Fr, H, HEIGHT, BRIDGE_SIZE, INS, OUTS> {
+			params: self.params.clone(),
+			public_amount: E::Fr::zero(),
+			ext_data_hash: E::Fr::zero(),
+			chain_id: E::Fr::from(chain_id),
+			root_set: vec![in_root; BRIDGE_SIZE],
+			in_root,
+			in_utxos,
+			in_paths,
+			out_utxos,
+			max_amount: self.max_amount,
+		};
+
+		Groth16::<E>::circuit_specific_setup(circuit, rng)
+	}
+
+	/// Proves a transaction spending `in_utxos`, located at `in_indices` of
+	/// the tree built from `in_leaves`, into `out_utxos`. Returns the proof
+	/// along with its public inputs.
+	pub fn create_proof<R: RngCore + CryptoRng>(
+		&self,
+		pk: &ProvingKey<E>,
+		chain_id: u64,
+		public_amount: E::Fr,
+		ext_data_hash: E::Fr,
+		root_set: [E::Fr; BRIDGE_SIZE],
+		in_indices: [u64; INS],
+		in_leaves: &[E::Fr],
+		in_utxos: &[Utxo<E::Fr>; INS],
+		out_utxos: &[Utxo<E::Fr>; OUTS],
+		rng: &mut R,
+	) -> Result<(Proof<E>, Vec<E::Fr>), Error> {
+		let tree = self.create_tree(in_leaves)?;
+		let in_root = tree.root().inner();
+
+		let (in_utxos, in_paths) = self.prepare_inputs(&tree, in_utxos, &in_indices)?;
+
+		let circuit = VAnchorCircuit::<E::Fr, H, HEIGHT, BRIDGE_SIZE, INS, OUTS> {
+			params: self.params.clone(),
+			public_amount,
+			ext_data_hash,
+			chain_id: E::Fr::from(chain_id),
+			root_set: root_set.to_vec(),
+			in_root,
+			in_utxos,
+			in_paths,
+			out_utxos: out_utxos.to_vec(),
+			max_amount: self.max_amount,
+		};
+		let public_inputs = circuit.public_inputs();
+		let proof = Groth16::<E>::prove(pk, circuit, rng)?;
+
+		Ok((proof, public_inputs))
+	}
+
+	/// Verifies a proof against public inputs laid out as in `public_inputs`
+	pub fn verify(vk: &VerifyingKey<E>, public_inputs: &[E::Fr], proof: &Proof<E>) -> bool {
+		Groth16::<E>::verify(vk, public_inputs, proof).unwrap_or(false)
+	}
+}
+
+#[cfg(feature = "parallel")]
+impl<
+		E: PairingEngine,
+		H: Rounds,
+		const HEIGHT: usize,
+		const BRIDGE_SIZE: usize,
+		const INS: usize,
+		const OUTS: usize,
+	> VAnchorR1CSProver<E, H, HEIGHT, BRIDGE_SIZE, INS, OUTS>
+{
+	/// Same as `prepare_inputs`, preparing the inputs concurrently: the
+	/// commitments, nullifiers and membership proofs of the UTXOs are computed
+	/// on the rayon thread pool. Returns the same UTXOs and paths, in the
+	/// order of `in_utxos`.
+	pub fn prepare_inputs_parallel(
+		&self,
+		tree: &PoseidonTree<E::Fr, H, HEIGHT>,
+		in_utxos: &[Utxo<E::Fr>],
+		in_indices: &[u64],
+	) -> Result<(Vec<Utxo<E::Fr>>, Vec<PoseidonPath<E::Fr, H, HEIGHT>>), Error> {
+		if in_utxos.len() != in_indices.len() {
+			return Err(CryptoError::IncorrectInputLength(in_indices.len()).into());
+		}
+		let params = &self.params;
+		let mut in_utxos = in_utxos.to_vec();
+		// `Error` can't be sent across threads, so errors are carried back as
+		// their message, and the one of the first failed input is returned
+		let in_paths = in_utxos
+			.par_iter_mut()
+			.zip(in_indices.par_iter())
+			.map(|(utxo, index)| {
+				prepare_input::<_, H, HEIGHT>(params, tree, utxo, *index)
+					.map_err(|e| e.to_string())
+			})
+			.collect::<Vec<Result<_, String>>>()
+			.into_iter()
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok((in_utxos, in_paths))
+	}
+}
+
+/// Recomputes the commitment of `utxo`, sets its index, deriving its
+/// nullifier, and returns its membership proof in `tree`
+fn prepare_input<F: PrimeField, H: Rounds, const HEIGHT: usize>(
+	params: &PoseidonParameters<F>,
+	tree: &PoseidonTree<F, H, HEIGHT>,
+	utxo: &mut Utxo<F>,
+	index: u64,
+) -> Result<PoseidonPath<F, H, HEIGHT>, Error> {
+	utxo.commitment = Commitment::<_, H>::commit_fields(params, &[
+		utxo.chain_id,
+		utxo.amount,
+		utxo.public_key,
+		utxo.blinding,
+	])?;
+	set_index::<_, H>(params, utxo, index)?;
+	Ok(tree.generate_membership_proof(index))
+}
+
+/// Sets the index of `utxo` and derives its nullifier
+fn set_index<F: PrimeField, H: Rounds>(
+	params: &PoseidonParameters<F>,
+	utxo: &mut Utxo<F>,
+	index: u64,
+) -> Result<(), Error> {
+	let index_f = F::from(index);
+	let signature = hash::<_, H>(params, &[utxo.private_key, utxo.commitment, index_f])?;
+	let nullifier = hash::<_, H>(params, &[utxo.commitment, index_f, signature])?;
+	utxo.index = Some(index);
+	utxo.nullifier = Some(nullifier);
+	Ok(())
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		circuit::vanchor::{derive_linking_tag, derive_linking_tag_var},
+		field_hasher::{
+			constraints::{FieldHasherGadget, PoseidonHasherGadget},
+			FieldHasher, PoseidonHasher,
+		},
+		prover::generate_witness,
+		setup::common::{setup_params_x5_5, Curve, PoseidonRounds_x5_5},
+	};
+	use ark_bn254::{Bn254, Fr as Bn254Fr};
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{test_rng, UniformRand};
+
+	type Prover = VAnchorR1CSProver<Bn254, PoseidonRounds_x5_5, 10, 2, 2, 2>;
+	type ExtDataHasher = PoseidonHasher<Bn254Fr, PoseidonRounds_x5_5>;
+	type ExtDataHasherGadget = PoseidonHasherGadget<Bn254Fr, PoseidonRounds_x5_5>;
+
+	#[test]
+	fn should_prove_and_verify_2_in_2_out() {
+		let rng = &mut test_rng();
+		let prover = Prover::new(setup_params_x5_5::<Bn254Fr>(Curve::Bn254));
+		let chain_id = 1u64;
+
+		// Deposit 10 into the tree, spend it alongside a dummy input
+		let in_utxo = prover
+			.create_utxo(chain_id, Bn254Fr::from(10u64), None, rng)
+			.unwrap();
+		let dummy_utxo = prover
+			.create_utxo(chain_id, Bn254Fr::from(0u64), None, rng)
+			.unwrap();
+		let in_leaves = vec![Bn254Fr::rand(rng), in_utxo.commitment];
+		let in_root = prover.create_tree(&in_leaves).unwrap().root().inner();
+		let root_set = [Bn254Fr::rand(rng), in_root];
+
+		// Withdraw 3 publicly, split the rest in two outputs
+		let public_amount = -Bn254Fr::from(3u64);
+		let out_utxos = [
+			prover
+				.create_utxo(chain_id, Bn254Fr::from(5u64), None, rng)
+				.unwrap(),
+			prover
+				.create_utxo(chain_id, Bn254Fr::from(2u64), None, rng)
+				.unwrap(),
+		];
+		let ext_data_hash = Bn254Fr::rand(rng);
+
+		let (pk, vk) = prover.setup(rng).unwrap();
+		let (proof, public_inputs) = prover
+			.create_proof(
+				&pk,
+				chain_id,
+				public_amount,
+				ext_data_hash,
+				root_set,
+				[1, 0],
+				&in_leaves,
+				&[in_utxo.clone(), dummy_utxo.clone()],
+				&out_utxos,
+				rng,
+			)
+			.unwrap();
+
+		// The verifier rebuilds the public inputs on its own
+		let mut spent = [in_utxo, dummy_utxo];
+		prover.set_index(&mut spent[0], 1).unwrap();
+		prover.set_index(&mut spent[1], 0).unwrap();
+		let expected_inputs = Prover::public_inputs(
+			public_amount,
+			ext_data_hash,
+			&[spent[0].nullifier.unwrap(), spent[1].nullifier.unwrap()],
+			&[out_utxos[0].commitment, out_utxos[1].commitment],
+			Bn254Fr::from(chain_id),
+			&root_set,
+		);
+		assert_eq!(public_inputs, expected_inputs);
+		assert!(Prover::verify(&vk, &public_inputs, &proof));
+
+		// The same values in another order must not verify
+		let mut misordered_inputs = expected_inputs.clone();
+		misordered_inputs.swap(0, 1);
+		assert!(!Prover::verify(&vk, &misordered_inputs, &proof));
+		let mut misordered_inputs = expected_inputs;
+		misordered_inputs.rotate_right(1);
+		assert!(!Prover::verify(&vk, &misordered_inputs, &proof));
+	}
+
+	/// Circuit spending a UTXO minted for `in_chain_id` on `chain_id`
+	fn spend_circuit(
+		prover: &Prover,
+		in_chain_id: u64,
+		chain_id: u64,
+	) -> VAnchorCircuit<Bn254Fr, PoseidonRounds_x5_5, 10, 2, 2, 2> {
+		let rng = &mut test_rng();
+		let in_utxo = prover
+			.create_utxo(in_chain_id, Bn254Fr::from(10u64), Some(1), rng)
+			.unwrap();
+		let dummy_utxo = prover
+			.create_utxo(chain_id, Bn254Fr::from(0u64), Some(0), rng)
+			.unwrap();
+		let tree = prover
+			.create_tree(&[Bn254Fr::rand(rng), in_utxo.commitment])
+			.unwrap();
+		let in_root = tree.root().inner();
+		let out_utxos = vec![
+			prover
+				.create_utxo(chain_id, Bn254Fr::from(10u64), None, rng)
+				.unwrap(),
+			prover
+				.create_utxo(chain_id, Bn254Fr::from(0u64), None, rng)
+				.unwrap(),
+		];
+
+		VAnchorCircuit {
+			params: prover.params.clone(),
+			public_amount: Bn254Fr::from(0u64),
+			ext_data_hash: Bn254Fr::rand(rng),
+			chain_id: Bn254Fr::from(chain_id),
+			root_set: vec![in_root, Bn254Fr::rand(rng)],
+			in_root,
+			in_utxos: vec![in_utxo, dummy_utxo],
+			in_paths: vec![
+				tree.generate_membership_proof(1),
+				tree.generate_membership_proof(0),
+			],
+			out_utxos,
+			max_amount: prover.max_amount,
+		}
+	}
+
+	#[test]
+	fn should_reject_utxo_of_other_chain() {
+		let prover = Prover::new(setup_params_x5_5::<Bn254Fr>(Curve::Bn254));
+
+		let circuit = spend_circuit(&prover, 1, 1);
+		assert!(generate_witness(circuit).unwrap().is_satisfied);
+
+		// A UTXO minted for chain 2 can't be spent claiming chain 1
+		let circuit = spend_circuit(&prover, 2, 1);
+		assert!(!generate_witness(circuit).unwrap().is_satisfied);
+	}
+
+	#[test]
+	fn should_bound_output_amounts() {
+		// The circuit outputs an amount of 10
+		let params = setup_params_x5_5::<Bn254Fr>(Curve::Bn254);
+		let prover = Prover::new(params.clone()).with_max_amount(Bn254Fr::from(11u64));
+		assert!(generate_witness(spend_circuit(&prover, 1, 1)).unwrap().is_satisfied);
+
+		let prover = Prover::new(params.clone()).with_max_amount(Bn254Fr::from(10u64));
+		assert!(!generate_witness(spend_circuit(&prover, 1, 1)).unwrap().is_satisfied);
+
+		// Without a bound, only the bit length of the amounts is checked
+		let num_constraints = |prover: &Prover| {
+			let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+			let circuit = spend_circuit(prover, 1, 1);
+			circuit.generate_constraints(cs.clone()).unwrap();
+			cs.num_constraints()
+		};
+		let unbounded = Prover::new(params);
+		assert!(generate_witness(spend_circuit(&unbounded, 1, 1)).unwrap().is_satisfied);
+		assert!(num_constraints(&unbounded) < num_constraints(&prover));
+	}
+
+	#[test]
+	fn should_derive_linking_tag() {
+		let rng = &mut test_rng();
+		let prover = Prover::new(setup_params_x5_5::<Bn254Fr>(Curve::Bn254));
+		let hasher = ExtDataHasher::new(setup_params_x5_5(Curve::Bn254));
+		let epoch = Bn254Fr::from(7u64);
+
+		let utxo = prover
+			.create_utxo(1, Bn254Fr::from(10u64), None, rng)
+			.unwrap();
+		let other = prover
+			.create_utxo(1, Bn254Fr::from(10u64), None, rng)
+			.unwrap();
+		// A second UTXO of the same owner
+		let same_owner = Utxo {
+			amount: Bn254Fr::from(4u64),
+			blinding: Bn254Fr::rand(rng),
+			..utxo.clone()
+		};
+
+		let tag = derive_linking_tag(&utxo.private_key, &epoch, &hasher).unwrap();
+		let same_owner_tag = derive_linking_tag(&same_owner.private_key, &epoch, &hasher).unwrap();
+		let other_tag = derive_linking_tag(&other.private_key, &epoch, &hasher).unwrap();
+		assert_eq!(tag, same_owner_tag);
+		assert_ne!(tag, other_tag);
+
+		// Tags of another epoch can't be linked
+		let next_epoch = epoch + Bn254Fr::from(1u64);
+		let next_tag = derive_linking_tag(&utxo.private_key, &next_epoch, &hasher).unwrap();
+		assert_ne!(tag, next_tag);
+
+		// Not even the first epoch reveals the public key
+		let zero = Bn254Fr::from(0u64);
+		let first_tag = derive_linking_tag(&utxo.private_key, &zero, &hasher).unwrap();
+		assert_ne!(first_tag, hasher.hash(&[utxo.private_key]).unwrap());
+
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let hasher_var = ExtDataHasherGadget::from_native(cs.clone(), hasher).unwrap();
+		let epoch_var = FpVar::new_input(cs.clone(), || Ok(epoch)).unwrap();
+		let tag_var = FpVar::new_input(cs.clone(), || Ok(tag)).unwrap();
+		for (utxo, expected) in [(&utxo, true), (&same_owner, true), (&other, false)].iter() {
+			let private_key = FpVar::new_witness(cs.clone(), || Ok(utxo.private_key)).unwrap();
+			let computed = derive_linking_tag_var(&private_key, &epoch_var, &hasher_var).unwrap();
+			assert_eq!(computed.is_eq(&tag_var).unwrap().value().unwrap(), *expected);
+		}
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_enforce_output_commitment() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_5::<Bn254Fr>(Curve::Bn254);
+		let prover = Prover::new(params.clone());
+		let utxo = prover
+			.create_utxo(1, Bn254Fr::from(10u64), None, rng)
+			.unwrap();
+
+		let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+		let commitment = FpVar::new_input(cs.clone(), || Ok(utxo.commitment)).unwrap();
+		let fields = [utxo.chain_id, utxo.amount, utxo.public_key, utxo.blinding]
+			.iter()
+			.map(|f| FpVar::new_witness(cs.clone(), || Ok(*f)).unwrap())
+			.collect::<Vec<_>>();
+		enforce_commitment::<_, PoseidonRounds_x5_5>(&commitment, &fields, &params_var).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		// Claiming a larger amount doesn't open the commitment
+		let mut tampered = fields.clone();
+		tampered[1] = FpVar::new_witness(cs.clone(), || Ok(Bn254Fr::from(11u64))).unwrap();
+		enforce_commitment::<_, PoseidonRounds_x5_5>(&commitment, &tampered, &params_var).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[cfg(feature = "parallel")]
+	#[test]
+	fn should_prepare_inputs_in_parallel() {
+		let rng = &mut test_rng();
+		let prover = Prover::new(setup_params_x5_5::<Bn254Fr>(Curve::Bn254));
+
+		let in_utxos = (0..4u64)
+			.map(|i| {
+				prover
+					.create_utxo(1, Bn254Fr::from(i), None, rng)
+					.unwrap()
+			})
+			.collect::<Vec<_>>();
+		let mut in_leaves = vec![Bn254Fr::rand(rng); 8];
+		let in_indices = [6, 1, 3, 4];
+		for (utxo, index) in in_utxos.iter().zip(in_indices.iter()) {
+			in_leaves[*index as usize] = utxo.commitment;
+		}
+		let tree = prover.create_tree(&in_leaves).unwrap();
+
+		let (serial_utxos, serial_paths) = prover
+			.prepare_inputs(&tree, &in_utxos, &in_indices)
+			.unwrap();
+		let (parallel_utxos, parallel_paths) = prover
+			.prepare_inputs_parallel(&tree, &in_utxos, &in_indices)
+			.unwrap();
+
+		assert_eq!(parallel_utxos.len(), serial_utxos.len());
+		for (serial, parallel) in serial_utxos.iter().zip(parallel_utxos.iter()) {
+			assert_eq!(serial.commitment, parallel.commitment);
+			assert_eq!(serial.index, parallel.index);
+			assert!(serial.nullifier.is_some());
+			assert_eq!(serial.nullifier, parallel.nullifier);
+		}
+		assert_eq!(parallel_paths.len(), serial_paths.len());
+		for (serial, parallel) in serial_paths.iter().zip(parallel_paths.iter()) {
+			assert_eq!(serial.path, parallel.path);
+		}
+
+		// Every input needs an index
+		assert!(prover
+			.prepare_inputs_parallel(&tree, &in_utxos, &in_indices[..3])
+			.is_err());
+		assert!(prover
+			.prepare_inputs(&tree, &in_utxos, &in_indices[..3])
+			.is_err());
+	}
+}
@@ -0,0 +1,189 @@
+use crate::Vec;
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::SynthesisError;
+
+/// Decomposes `value` into `bits` little-endian booleans and enforces that
+/// they recompose to `value`, i.e. that `value < 2^bits`.
+///
+/// `bits` must be smaller than the modulus bit size so that the
+/// recomposition can't wrap around the field. The returned bits can be
+/// reused by the caller (e.g. for comparisons).
+pub fn enforce_bit_length<F: PrimeField>(
+	value: &FpVar<F>,
+	bits: usize,
+) -> Result<Vec<Boolean<F>>, SynthesisError> {
+	assert!(
+		bits < F::size_in_bits(),
+		"bit length must be smaller than the modulus bit size"
+	);
+
+	let cs = value.cs();
+	let mode = if cs.is_none() {
+		AllocationMode::Constant
+	} else {
+		AllocationMode::Witness
+	};
+
+	let value_bits = value.value().map(|v| v.into_repr().to_bits_le());
+	let mut bits_var = Vec::with_capacity(bits);
+	for i in 0..bits {
+		let bit = Boolean::new_variable(
+			ark_relations::ns!(cs, "bit"),
+			|| value_bits.as_ref().map(|b| b[i]).map_err(|e| *e),
+			mode,
+		)?;
+		bits_var.push(bit);
+	}
+
+	Boolean::le_bits_to_fp_var(&bits_var)?.enforce_equal(value)?;
+
+	Ok(bits_var)
+}
+
+/// Enforces `value < bound`. Both are decomposed into `F::size_in_bits() - 2`
+/// bits, along with `bound - value - 1`, which only fits in as many bits if
+/// it didn't wrap around the field, i.e. if `value` is below `bound`.
+pub fn enforce_less_than<F: PrimeField>(
+	value: &FpVar<F>,
+	bound: &FpVar<F>,
+) -> Result<(), SynthesisError> {
+	let bits = F::size_in_bits() - 2;
+	enforce_bit_length(value, bits)?;
+	enforce_bit_length(bound, bits)?;
+	enforce_bit_length(&(bound - value - F::one()), bits)?;
+	Ok(())
+}
+
+/// Enforces `values[i] <= values[i + 1]` for every consecutive pair. Unlike
+/// `enforce_less_than`, the values can take the whole range of the field,
+/// e.g. hashes: each value is decomposed once into its canonical bits, and
+/// each pair compares them from the most significant one.
+pub fn enforce_sorted<F: PrimeField>(values: &[FpVar<F>]) -> Result<(), SynthesisError> {
+	let bits = values
+		.iter()
+		.map(|value| value.to_bits_le())
+		.collect::<Result<Vec<_>, _>>()?;
+	for pair in bits.windows(2) {
+		is_less_or_equal_bits(&pair[0], &pair[1])?.enforce_equal(&Boolean::TRUE)?;
+	}
+	Ok(())
+}
+
+/// Whether the little-endian bits `a` read as a number no larger than the
+/// bits `b`, of the same length
+fn is_less_or_equal_bits<F: PrimeField>(
+	a: &[Boolean<F>],
+	b: &[Boolean<F>],
+) -> Result<Boolean<F>, SynthesisError> {
+	let mut is_less = Boolean::FALSE;
+	let mut is_equal = Boolean::TRUE;
+	for (a_i, b_i) in a.iter().zip(b).rev() {
+		// The first bit that differs from the top decides
+		is_less = is_less.or(&is_equal.and(&a_i.not().and(b_i)?)?)?;
+		is_equal = is_equal.and(&a_i.xor(b_i)?.not())?;
+	}
+	is_less.or(&is_equal)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_bn254::Fr;
+	use ark_ff::{Field, One, UniformRand};
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::test_rng;
+
+	const BITS: usize = 248;
+
+	#[test]
+	fn should_accept_max_value() {
+		let value = Fr::from(2u64).pow([BITS as u64]) - Fr::one();
+
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let value_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(value)).unwrap();
+		let bits = enforce_bit_length(&value_var, BITS).unwrap();
+
+		assert_eq!(bits.len(), BITS);
+		assert!(cs.is_satisfied().unwrap());
+		assert!(crate::range::fits_in_bits(&value, BITS));
+	}
+
+	#[test]
+	fn should_reject_overflowing_value() {
+		let value = Fr::from(2u64).pow([BITS as u64]);
+
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let value_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(value)).unwrap();
+		enforce_bit_length(&value_var, BITS).unwrap();
+
+		assert!(!cs.is_satisfied().unwrap());
+		assert!(!crate::range::fits_in_bits(&value, BITS));
+	}
+
+	#[test]
+	fn should_enforce_less_than_bound() {
+		let check = |value: Fr, bound: Fr| {
+			let cs = ConstraintSystem::<Fr>::new_ref();
+			let value_var = FpVar::<Fr>::new_witness(cs.clone(), || Ok(value)).unwrap();
+			let bound_var = FpVar::<Fr>::new_constant(cs.clone(), bound).unwrap();
+			enforce_less_than(&value_var, &bound_var).unwrap();
+			cs.is_satisfied().unwrap()
+		};
+
+		let bound = Fr::from(1000u64);
+		assert!(check(bound - Fr::one(), bound));
+		assert!(check(Fr::from(0u64), bound));
+		assert!(!check(bound, bound));
+		assert!(!check(bound + Fr::one(), bound));
+		// A negative value is a huge one
+		assert!(!check(-Fr::one(), bound));
+	}
+
+	#[test]
+	fn should_enforce_sorted_values() {
+		let check = |values: &[u64]| {
+			let cs = ConstraintSystem::<Fr>::new_ref();
+			let values_var = values
+				.iter()
+				.map(|v| FpVar::<Fr>::new_witness(cs.clone(), || Ok(Fr::from(*v))).unwrap())
+				.collect::<Vec<_>>();
+			enforce_sorted(&values_var).unwrap();
+			cs.is_satisfied().unwrap()
+		};
+
+		assert!(check(&[1, 2, 2, 5, 1000]));
+		assert!(check(&[7]));
+		assert!(check(&[]));
+		assert!(!check(&[1, 5, 2, 1000]));
+		assert!(!check(&[2, 1]));
+	}
+
+	#[test]
+	fn should_enforce_sorted_full_width_values() {
+		let check = |values: &[Fr]| {
+			let cs = ConstraintSystem::<Fr>::new_ref();
+			let values_var = values
+				.iter()
+				.map(|v| FpVar::<Fr>::new_witness(cs.clone(), || Ok(*v)).unwrap())
+				.collect::<Vec<_>>();
+			enforce_sorted(&values_var).unwrap();
+			cs.is_satisfied().unwrap()
+		};
+
+		// Hash-like values, nearly all above the 2^252 a decomposition into
+		// `size_in_bits - 2` bits would allow
+		let rng = &mut test_rng();
+		let mut values: Vec<Fr> = (0..6).map(|_| Fr::rand(rng)).collect();
+		values.sort_by_key(|v| v.into_repr());
+		assert!(values[5].into_repr() > Fr::from(2u64).pow([252]).into_repr());
+		assert!(check(&values));
+		values.swap(1, 4);
+		assert!(!check(&values));
+
+		// Up to the largest element
+		assert!(check(&[Fr::one(), -Fr::from(2u64), -Fr::one()]));
+		assert!(!check(&[-Fr::one(), -Fr::from(2u64)]));
+		assert!(!check(&[-Fr::one(), Fr::one()]));
+	}
+}
@@ -0,0 +1,11 @@
+use ark_ff::{BigInteger, PrimeField};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+#[cfg(feature = "r1cs")]
+pub use constraints::*;
+
+/// Returns true if `value`, read as an integer, fits in `bits` bits.
+pub fn fits_in_bits<F: PrimeField>(value: &F, bits: usize) -> bool {
+	value.into_repr().num_bits() as usize <= bits
+}
@@ -0,0 +1,345 @@
+//! Straightforward reimplementations of the hashes and constructions that
+//! have gadgets, written from their definitions rather than shared with the
+//! native code, to cross-check the gadgets against. They favour readability
+//! over speed and panic on malformed parameters.
+
+use crate::{
+	mimc::MiMCParameters,
+	poseidon::{sbox::PoseidonSbox, PoseidonParameters},
+	Vec,
+};
+use ark_ff::PrimeField;
+
+fn sbox<F: PrimeField>(sbox: PoseidonSbox, x: F) -> F {
+	match sbox {
+		PoseidonSbox::Exponentiation(alpha) => x.pow([alpha as u64]),
+		PoseidonSbox::Inverse => x.inverse().expect("inverse S-box of zero"),
+	}
+}
+
+/// Poseidon permutation: each round adds its round keys, applies the S-box
+/// to the whole state in full rounds and to the first element in partial
+/// rounds, then multiplies by the MDS matrix. Reads the width, round counts
+/// and S-boxes from the runtime fields of `params`.
+pub fn poseidon_permute<F: PrimeField>(params: &PoseidonParameters<F>, state: &[F]) -> Vec<F> {
+	assert_eq!(state.len(), params.width);
+	let half_full_rounds = params.full_rounds / 2;
+	let mut round_keys = params.round_keys.iter();
+	let mut state = state.to_vec();
+	for round in 0..params.full_rounds + params.partial_rounds {
+		for x in state.iter_mut() {
+			*x += round_keys.next().expect("too few round keys");
+		}
+
+		let is_full = round < half_full_rounds || round >= half_full_rounds + params.partial_rounds;
+		if is_full {
+			for x in state.iter_mut() {
+				*x = sbox(params.sbox, *x);
+			}
+		} else {
+			state[0] = sbox(params.partial_sbox, state[0]);
+		}
+
+		state = params
+			.mds_matrix
+			.iter()
+			.map(|row| {
+				row.iter()
+					.zip(&state)
+					.fold(F::zero(), |acc, (m, x)| acc + *m * x)
+			})
+			.collect();
+	}
+	state
+}
+
+/// Poseidon hash of up to `width - capacity` inputs, placed after the zero
+/// capacity elements, the digest being the first element of the state
+pub fn poseidon_hash<F: PrimeField>(params: &PoseidonParameters<F>, inputs: &[F]) -> F {
+	assert!(inputs.len() <= params.width - params.capacity);
+	let mut state = vec![F::zero(); params.width];
+	state[params.capacity..params.capacity + inputs.len()].copy_from_slice(inputs);
+	poseidon_permute(params, &state)[0]
+}
+
+/// MiMC Feistel permutation of `(left, right)`. Every round but the last
+/// maps `(l, r)` to `(r + (k + l + c_i)^5, l)`, the last one only adds to
+/// `r`. The first and last rounds have no constant and, like the native
+/// hash, the first one only swaps the halves.
+pub fn mimc_feistel<F: PrimeField>(params: &MiMCParameters<F>, left: F, right: F) -> (F, F) {
+	let (mut left, mut right) = (left, right);
+	for i in 0..params.rounds {
+		let is_last = i == params.rounds - 1;
+		let c = if i == 0 || is_last {
+			F::zero()
+		} else {
+			params.round_keys[i - 1]
+		};
+		let f = if i == 0 {
+			F::zero()
+		} else {
+			(params.k + left + c).pow([5u64])
+		};
+
+		if is_last {
+			right += f;
+		} else {
+			let next_left = right + f;
+			right = left;
+			left = next_left;
+		}
+	}
+	(left, right)
+}
+
+/// MiMC sponge hash, absorbing the inputs zero-padded to `num_inputs` into
+/// the left half of the state, the digest being the left half
+pub fn mimc_hash<F: PrimeField>(params: &MiMCParameters<F>, inputs: &[F]) -> F {
+	assert!(inputs.len() <= params.num_inputs);
+	let mut padded = inputs.to_vec();
+	padded.resize(params.num_inputs, F::zero());
+
+	let (mut left, mut right) = (F::zero(), F::zero());
+	for x in padded {
+		let (l, r) = mimc_feistel(params, left + x, right);
+		left = l;
+		right = r;
+	}
+	left
+}
+
+/// Root of a tree of the given height whose leaves are `leaves` followed by
+/// `empty_leaf`, hashing each pair of nodes with `hash_two`
+pub fn merkle_root<F: PrimeField>(
+	leaves: &[F],
+	height: usize,
+	empty_leaf: F,
+	hash_two: impl Fn(F, F) -> F,
+) -> F {
+	assert!(leaves.len() <= 1 << height);
+	let mut level = leaves.to_vec();
+	level.resize(1 << height, empty_leaf);
+	for _ in 0..height {
+		level = level
+			.chunks(2)
+			.map(|pair| hash_two(pair[0], pair[1]))
+			.collect();
+	}
+	level[0]
+}
+
+/// Mixer leaf `H(r, nullifier, rho)` and nullifier hash
+/// `H(nullifier, nullifier)` with Poseidon
+pub fn mixer_leaf<F: PrimeField>(
+	params: &PoseidonParameters<F>,
+	r: F,
+	nullifier: F,
+	rho: F,
+) -> (F, F) {
+	let leaf = poseidon_hash(params, &[r, nullifier, rho]);
+	let nullifier_hash = poseidon_hash(params, &[nullifier, nullifier]);
+	(leaf, nullifier_hash)
+}
+
+#[cfg(all(
+	feature = "r1cs",
+	feature = "default_poseidon",
+	feature = "default_mimc"
+))]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		field_hasher::{
+			constraints::{FieldHasherGadget, MiMCHasherGadget, PoseidonHasherGadget},
+			MiMCHasher, PoseidonHasher,
+		},
+		identity::{
+			constraints::{CRHGadget as IdentityCRHGadget, Params as IdentityParams},
+			CRH as IdentityCRH,
+		},
+		leaf::mixer::{
+			constraints::{MixerLeafGadget, PrivateVar},
+			MixerLeaf,
+		},
+		merkle_tree::{
+			constraints::{MerkleRootGadget, NodeVar},
+			Config,
+		},
+		poseidon::{
+			constraints::{CRHGadget as PoseidonCRHGadget, PoseidonParametersVar},
+			CRH as PoseidonCRH,
+		},
+		setup::common::{
+			setup_mimc_220, setup_params_x5_3, setup_params_x5_5, Curve, MiMCRounds_220_3,
+			PoseidonRounds_x5_3, PoseidonRounds_x5_5,
+		},
+	};
+	use ark_bn254::Fr;
+	use ark_ff::Zero;
+	use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+	use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+	use ark_std::{boxed::Box, test_rng, UniformRand};
+
+	/// Number of random inputs each gadget is checked on
+	const NUM_SAMPLES: usize = 32;
+
+	type Outputs = Result<Vec<FpVar<Fr>>, SynthesisError>;
+	type Reference = Box<dyn Fn(&[Fr]) -> Vec<Fr>>;
+	type Gadget = Box<dyn Fn(ConstraintSystemRef<Fr>, &[FpVar<Fr>]) -> Outputs>;
+
+	/// A gadget and its reference, both mapping `num_inputs` field elements
+	/// to the same outputs
+	struct Case {
+		name: &'static str,
+		num_inputs: usize,
+		reference: Reference,
+		gadget: Gadget,
+	}
+
+	impl Case {
+		fn new<R, G>(name: &'static str, num_inputs: usize, reference: R, gadget: G) -> Self
+		where
+			R: Fn(&[Fr]) -> Vec<Fr> + 'static,
+			G: Fn(ConstraintSystemRef<Fr>, &[FpVar<Fr>]) -> Outputs + 'static,
+		{
+			Self {
+				name,
+				num_inputs,
+				reference: Box::new(reference),
+				gadget: Box::new(gadget),
+			}
+		}
+	}
+
+	#[derive(Clone, PartialEq)]
+	struct TreeConfig;
+	impl Config for TreeConfig {
+		type H = PoseidonCRH<Fr, PoseidonRounds_x5_3>;
+		type LeafH = IdentityCRH<Fr>;
+
+		const HEIGHT: u8 = 2;
+	}
+
+	type TreeRootGadget = MerkleRootGadget<
+		Fr,
+		TreeConfig,
+		PoseidonCRHGadget<Fr, PoseidonRounds_x5_3>,
+		IdentityCRHGadget<Fr>,
+	>;
+	type LeafGadget = MixerLeafGadget<
+		Fr,
+		PoseidonCRH<Fr, PoseidonRounds_x5_5>,
+		PoseidonCRHGadget<Fr, PoseidonRounds_x5_5>,
+		MixerLeaf<Fr, PoseidonCRH<Fr, PoseidonRounds_x5_5>>,
+	>;
+
+	/// Every gadget checked against its reference, a new gadget only needs an
+	/// entry here
+	fn cases() -> Vec<Case> {
+		let params3 = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let params5 = setup_params_x5_5::<Fr>(Curve::Bn254);
+		let mimc_params = setup_mimc_220::<Fr>(Curve::Bn254);
+
+		let (p3, g3) = (params3.clone(), params3.clone());
+		let (p5, g5) = (params5.clone(), params5.clone());
+		let (pm, gm) = (mimc_params.clone(), mimc_params);
+		let (pt, gt) = (params3.clone(), params3);
+		let (pl, gl) = (params5.clone(), params5);
+		vec![
+			Case::new(
+				"poseidon_x5_3",
+				2,
+				move |x| vec![poseidon_hash(&p3, x)],
+				move |cs, x| {
+					let hasher = PoseidonHasher::new(g3.clone());
+					let hasher_var =
+						PoseidonHasherGadget::<Fr, PoseidonRounds_x5_3>::from_native(cs, hasher)?;
+					Ok(vec![hasher_var.hash(x)?])
+				},
+			),
+			Case::new(
+				"poseidon_x5_5",
+				4,
+				move |x| vec![poseidon_hash(&p5, x)],
+				move |cs, x| {
+					let hasher = PoseidonHasher::new(g5.clone());
+					let hasher_var =
+						PoseidonHasherGadget::<Fr, PoseidonRounds_x5_5>::from_native(cs, hasher)?;
+					Ok(vec![hasher_var.hash(x)?])
+				},
+			),
+			Case::new(
+				"mimc_220_3",
+				3,
+				move |x| vec![mimc_hash(&pm, x)],
+				move |cs, x| {
+					let hasher = MiMCHasher::new(gm.clone());
+					let hasher_var =
+						MiMCHasherGadget::<Fr, MiMCRounds_220_3>::from_native(cs, hasher)?;
+					Ok(vec![hasher_var.hash(x)?])
+				},
+			),
+			Case::new(
+				"merkle_root",
+				1 << TreeConfig::HEIGHT,
+				move |x| {
+					let height = TreeConfig::HEIGHT as usize;
+					vec![merkle_root(x, height, Fr::zero(), |l, r| {
+						poseidon_hash(&pt, &[l, r])
+					})]
+				},
+				move |cs, x| {
+					let params_var = PoseidonParametersVar::new_constant(cs, &gt)?;
+					let root =
+						TreeRootGadget::calculate_root(&params_var, &IdentityParams::default(), x)?;
+					match root {
+						NodeVar::Inner(root) | NodeVar::Leaf(root) => Ok(vec![root]),
+					}
+				},
+			),
+			Case::new(
+				"mixer_leaf",
+				3,
+				move |x| {
+					let (leaf, nullifier_hash) = mixer_leaf(&pl, x[0], x[1], x[2]);
+					vec![leaf, nullifier_hash]
+				},
+				move |cs, x| {
+					let params_var = PoseidonParametersVar::new_constant(cs, &gl)?;
+					let secrets = PrivateVar::new(x[0].clone(), x[1].clone(), x[2].clone());
+					let (leaf, nullifier_hash) =
+						LeafGadget::create_leaf_and_nullifier(&params_var, &secrets)?;
+					Ok(vec![leaf, nullifier_hash])
+				},
+			),
+		]
+	}
+
+	#[test]
+	fn should_match_reference_implementations() {
+		let rng = &mut test_rng();
+		for case in cases() {
+			for _ in 0..NUM_SAMPLES {
+				let inputs: Vec<Fr> = (0..case.num_inputs).map(|_| Fr::rand(rng)).collect();
+				let expected = (case.reference)(&inputs);
+
+				let cs = ConstraintSystem::<Fr>::new_ref();
+				let input_vars = inputs
+					.iter()
+					.map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)))
+					.collect::<Result<Vec<_>, _>>()
+					.unwrap();
+				let outputs = (case.gadget)(cs.clone(), &input_vars).unwrap();
+				let values = outputs
+					.iter()
+					.map(|output| output.value())
+					.collect::<Result<Vec<_>, _>>()
+					.unwrap();
+
+				assert_eq!(values, expected, "{} differs from its reference", case.name);
+				assert!(cs.is_satisfied().unwrap(), "{} is unsatisfied", case.name);
+			}
+		}
+	}
+}
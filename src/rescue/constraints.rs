@@ -0,0 +1,194 @@
+use super::{alpha_inverse, RescueParameters, Rounds, CRH};
+use crate::{
+	poseidon::constraints::PoseidonParametersVar,
+	utils::{full_chunk_size, to_field_var_elements_chunked, Endianness},
+};
+use ark_crypto_primitives::crh::constraints::{CRHGadget as CRHGadgetTrait, TwoToOneCRHGadget};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+	fields::{fp::FpVar, FieldVar},
+	prelude::*,
+	uint8::UInt8,
+};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::{marker::PhantomData, vec::Vec};
+
+/// Round keys and MDS matrix are laid out like Poseidon's.
+pub type RescueParametersVar<F> = PoseidonParametersVar<F>;
+
+pub struct CRHGadget<F: PrimeField, P: Rounds> {
+	field: PhantomData<F>,
+	params: PhantomData<P>,
+}
+
+impl<F: PrimeField, P: Rounds> CRHGadget<F, P> {
+	fn permute(
+		parameters: &RescueParametersVar<F>,
+		mut state: Vec<FpVar<F>>,
+	) -> Result<Vec<FpVar<F>>, SynthesisError> {
+		let width = P::WIDTH;
+		let alpha_inv =
+			alpha_inverse::<F>(P::ALPHA).map_err(|_| SynthesisError::Unsatisfiable)?;
+
+		let mut round_keys_offset = 0;
+		for _ in 0..P::ROUNDS {
+			// forward Sbox layer
+			for i in 0..width {
+				state[i] = state[i].pow_by_constant(&[P::ALPHA])?;
+			}
+			state = Self::apply_linear_layer(&state, &parameters.mds_matrix);
+			for i in 0..width {
+				state[i] += &parameters.round_keys[round_keys_offset];
+				round_keys_offset += 1;
+			}
+
+			// inverse Sbox layer
+			for i in 0..width {
+				state[i] = Self::synthesize_inverse_sbox(&state[i], &alpha_inv)?;
+			}
+			state = Self::apply_linear_layer(&state, &parameters.mds_matrix);
+			for i in 0..width {
+				state[i] += &parameters.round_keys[round_keys_offset];
+				round_keys_offset += 1;
+			}
+		}
+
+		Ok(state)
+	}
+
+	/// Witnesses `y = x^(1 / alpha)` and enforces `y^alpha == x`, which costs
+	/// the same as the forward S-box instead of a full exponentiation.
+	fn synthesize_inverse_sbox(
+		x: &FpVar<F>,
+		alpha_inv: &[u64],
+	) -> Result<FpVar<F>, SynthesisError> {
+		let cs = x.cs();
+		let mode = if cs.is_none() {
+			AllocationMode::Constant
+		} else {
+			AllocationMode::Witness
+		};
+		let root = FpVar::new_variable(
+			ark_relations::ns!(cs, "inverse_sbox"),
+			|| Ok(x.value()?.pow(alpha_inv)),
+			mode,
+		)?;
+		root.pow_by_constant(&[P::ALPHA])?.enforce_equal(x)?;
+		Ok(root)
+	}
+
+	fn apply_linear_layer(state: &Vec<FpVar<F>>, mds_matrix: &Vec<Vec<FpVar<F>>>) -> Vec<FpVar<F>> {
+		let mut new_state: Vec<FpVar<F>> = Vec::new();
+		for i in 0..state.len() {
+			let mut sc = FpVar::<F>::zero();
+			for j in 0..state.len() {
+				let mij = &mds_matrix[i][j];
+				sc += mij * &state[j];
+			}
+			new_state.push(sc);
+		}
+		new_state
+	}
+}
+
+impl<F: PrimeField, P: Rounds> CRHGadgetTrait<CRH<F, P>, F> for CRHGadget<F, P> {
+	type OutputVar = FpVar<F>;
+	type ParametersVar = RescueParametersVar<F>;
+
+	fn evaluate(
+		parameters: &Self::ParametersVar,
+		input: &[UInt8<F>],
+	) -> Result<Self::OutputVar, SynthesisError> {
+		let f_var_inputs: Vec<FpVar<F>> =
+			to_field_var_elements_chunked(input, full_chunk_size::<F>(), Endianness::Little)?;
+		if f_var_inputs.len() > P::WIDTH {
+			panic!(
+				"incorrect input length {:?} for width {:?}",
+				f_var_inputs.len(),
+				P::WIDTH,
+			);
+		}
+
+		let mut buffer = vec![FpVar::zero(); P::WIDTH];
+		buffer
+			.iter_mut()
+			.zip(f_var_inputs)
+			.for_each(|(b, l_b)| *b = l_b);
+
+		let result = Self::permute(&parameters, buffer);
+		result.map(|x| x.get(0).cloned().ok_or(SynthesisError::AssignmentMissing))?
+	}
+}
+
+impl<F: PrimeField, P: Rounds> TwoToOneCRHGadget<CRH<F, P>, F> for CRHGadget<F, P> {
+	type OutputVar = FpVar<F>;
+	type ParametersVar = RescueParametersVar<F>;
+
+	fn evaluate(
+		parameters: &Self::ParametersVar,
+		left_input: &[UInt8<F>],
+		right_input: &[UInt8<F>],
+	) -> Result<Self::OutputVar, SynthesisError> {
+		// assume equality of left and right length
+		assert_eq!(left_input.len(), right_input.len());
+		let chained_input: Vec<_> = left_input
+			.to_vec()
+			.into_iter()
+			.chain(right_input.to_vec().into_iter())
+			.collect();
+		<Self as CRHGadgetTrait<_, _>>::evaluate(parameters, &chained_input)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_bls12_381::Fr;
+	use ark_crypto_primitives::crh::CRH as CRHTrait;
+	use ark_ff::to_bytes;
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{test_rng, UniformRand};
+
+	#[derive(Default, Clone)]
+	struct RescueRounds3;
+
+	impl Rounds for RescueRounds3 {
+		const ALPHA: u64 = 5;
+		const ROUNDS: usize = 4;
+		const WIDTH: usize = 3;
+	}
+
+	type RescueCRH3 = CRH<Fr, RescueRounds3>;
+	type RescueCRH3Gadget = CRHGadget<Fr, RescueRounds3>;
+
+	fn setup_params<R: ark_std::rand::Rng>(rng: &mut R) -> RescueParameters<Fr> {
+		let width = RescueRounds3::WIDTH;
+		let round_keys = (0..2 * RescueRounds3::ROUNDS * width)
+			.map(|_| Fr::rand(rng))
+			.collect();
+		let mds = (0..width)
+			.map(|_| (0..width).map(|_| Fr::rand(rng)).collect())
+			.collect();
+		RescueParameters::new(round_keys, mds)
+	}
+
+	#[test]
+	fn test_rescue_native_equality() {
+		let rng = &mut test_rng();
+		let params = setup_params(rng);
+
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let inp = to_bytes![Fr::rand(rng), Fr::rand(rng), Fr::rand(rng)].unwrap();
+		let inp_u8 = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(inp.clone())).unwrap();
+		let params_var =
+			RescueParametersVar::new_variable(cs.clone(), || Ok(&params), AllocationMode::Constant)
+				.unwrap();
+
+		let res = <RescueCRH3 as CRHTrait>::evaluate(&params, &inp).unwrap();
+		let res_var =
+			<RescueCRH3Gadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_u8).unwrap();
+
+		assert_eq!(res, res_var.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+	}
+}
@@ -0,0 +1,265 @@
+use crate::{
+	poseidon::PoseidonParameters,
+	utils::{full_chunk_size, to_field_elements_chunked, Endianness},
+};
+use ark_crypto_primitives::{crh::TwoToOneCRH, Error, CRH as CRHTrait};
+use ark_ff::{fields::PrimeField, BigInteger};
+use ark_std::{error::Error as ArkError, marker::PhantomData, rand::Rng, vec::Vec, UniformRand};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+#[derive(Debug)]
+pub enum RescueError {
+	InvalidAlpha(u64),
+	InvalidParameters,
+	InvalidInputs,
+}
+
+impl core::fmt::Display for RescueError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		use RescueError::*;
+		let msg = match self {
+			InvalidAlpha(a) => format!("alpha is not invertible modulo p - 1: {}", a),
+			InvalidParameters => format!("invalid parameters"),
+			InvalidInputs => format!("invalid inputs"),
+		};
+		write!(f, "{}", msg)
+	}
+}
+
+impl ArkError for RescueError {}
+
+pub trait Rounds: Default + Clone {
+	/// The size of the permutation, in field elements.
+	const WIDTH: usize;
+	/// Number of rounds, each made of a forward and an inverse S-box step
+	const ROUNDS: usize;
+	/// The exponent of the forward S-box, must be coprime with `p - 1`
+	const ALPHA: u64;
+}
+
+/// Rescue-Prime uses the same round key / MDS layout as Poseidon, with
+/// `2 * ROUNDS * WIDTH` round keys.
+pub type RescueParameters<F> = PoseidonParameters<F>;
+
+/// Computes `1 / alpha mod (p - 1)` as little-endian limbs, so that
+/// `x^(1 / alpha)` is the inverse of the forward S-box.
+pub fn alpha_inverse<F: PrimeField>(alpha: u64) -> Result<Vec<u64>, RescueError> {
+	// The modulus is odd so subtracting one never borrows
+	let mut p_minus_one = F::characteristic().to_vec();
+	p_minus_one[0] -= 1;
+
+	// Find k such that alpha divides k * (p - 1) + 1, the quotient is the inverse
+	for k in 1..alpha.max(2) {
+		let mut acc = Vec::with_capacity(p_minus_one.len() + 1);
+		let mut carry: u128 = 1;
+		for limb in &p_minus_one {
+			let v = (*limb as u128) * (k as u128) + carry;
+			acc.push(v as u64);
+			carry = v >> 64;
+		}
+		acc.push(carry as u64);
+
+		let mut rem: u128 = 0;
+		let mut quotient = vec![0u64; acc.len()];
+		for i in (0..acc.len()).rev() {
+			let cur = (rem << 64) | acc[i] as u128;
+			quotient[i] = (cur / alpha as u128) as u64;
+			rem = cur % alpha as u128;
+		}
+		if rem == 0 {
+			return Ok(quotient);
+		}
+	}
+
+	Err(RescueError::InvalidAlpha(alpha))
+}
+
+/// Samples a `width x width` Cauchy matrix `1 / (x_i + y_j)`, which is MDS
+/// when the `x_i` and `y_j` are all distinct and no `x_i + y_j` is zero
+pub fn create_cauchy_mds<F: PrimeField, R: Rng>(width: usize, rng: &mut R) -> Vec<Vec<F>> {
+	loop {
+		let points: Vec<F> = (0..2 * width).map(|_| F::rand(rng)).collect();
+		let distinct = points
+			.iter()
+			.enumerate()
+			.all(|(i, a)| points[i + 1..].iter().all(|b| a != b));
+		let (xs, ys) = points.split_at(width);
+		let mds = xs
+			.iter()
+			.map(|x| ys.iter().map(|y| (*x + y).inverse()).collect())
+			.collect::<Option<Vec<Vec<F>>>>();
+		match mds {
+			Some(mds) if distinct => return mds,
+			_ => continue,
+		}
+	}
+}
+
+pub struct CRH<F: PrimeField, P: Rounds> {
+	field: PhantomData<F>,
+	rounds: PhantomData<P>,
+}
+
+impl<F: PrimeField, P: Rounds> CRH<F, P> {
+	fn permute(params: &RescueParameters<F>, mut state: Vec<F>) -> Result<Vec<F>, RescueError> {
+		let width = P::WIDTH;
+		if params.round_keys.len() < 2 * P::ROUNDS * width || params.mds_matrix.len() != width {
+			return Err(RescueError::InvalidParameters);
+		}
+		let alpha_inv = alpha_inverse::<F>(P::ALPHA)?;
+
+		let mut round_keys_offset = 0;
+		for _ in 0..P::ROUNDS {
+			// forward Sbox layer
+			for i in 0..width {
+				state[i] = state[i].pow([P::ALPHA]);
+			}
+			state = Self::apply_linear_layer(&state, &params.mds_matrix);
+			for i in 0..width {
+				state[i] += params.round_keys[round_keys_offset];
+				round_keys_offset += 1;
+			}
+
+			// inverse Sbox layer
+			for i in 0..width {
+				state[i] = state[i].pow(&alpha_inv);
+			}
+			state = Self::apply_linear_layer(&state, &params.mds_matrix);
+			for i in 0..width {
+				state[i] += params.round_keys[round_keys_offset];
+				round_keys_offset += 1;
+			}
+		}
+
+		Ok(state)
+	}
+
+	fn apply_linear_layer(state: &Vec<F>, mds: &Vec<Vec<F>>) -> Vec<F> {
+		let mut new_state: Vec<F> = Vec::new();
+		for i in 0..state.len() {
+			let mut sc = F::zero();
+			for j in 0..state.len() {
+				let mij = mds[i][j];
+				sc += mij * state[j];
+			}
+			new_state.push(sc);
+		}
+		new_state
+	}
+}
+
+impl<F: PrimeField, P: Rounds> CRHTrait for CRH<F, P> {
+	type Output = F;
+	type Parameters = RescueParameters<F>;
+
+	const INPUT_SIZE_BITS: usize = F::BigInt::NUM_LIMBS * 8 * P::WIDTH * 8;
+
+	/// Samples random round keys and a random Cauchy MDS matrix
+	fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+		let round_keys = (0..2 * P::ROUNDS * P::WIDTH)
+			.map(|_| F::rand(rng))
+			.collect();
+		let mds = create_cauchy_mds(P::WIDTH, rng);
+		Ok(Self::Parameters::new(round_keys, mds))
+	}
+
+	fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+		let f_inputs: Vec<F> =
+			to_field_elements_chunked(input, full_chunk_size::<F>(), Endianness::Little)?;
+
+		if f_inputs.len() > P::WIDTH {
+			panic!(
+				"incorrect input length {:?} for width {:?} -- input bits {:?}",
+				f_inputs.len(),
+				P::WIDTH,
+				input.len()
+			);
+		}
+
+		let mut buffer = vec![F::zero(); P::WIDTH];
+		buffer.iter_mut().zip(f_inputs).for_each(|(p, v)| *p = v);
+
+		let result = Self::permute(&parameters, buffer)?;
+
+		Ok(result.get(0).cloned().ok_or(RescueError::InvalidInputs)?)
+	}
+}
+
+impl<F: PrimeField, P: Rounds> TwoToOneCRH for CRH<F, P> {
+	type Output = F;
+	type Parameters = RescueParameters<F>;
+
+	const LEFT_INPUT_SIZE_BITS: usize = Self::INPUT_SIZE_BITS / 2;
+	const RIGHT_INPUT_SIZE_BITS: usize = Self::INPUT_SIZE_BITS / 2;
+
+	fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+		<Self as CRHTrait>::setup(rng)
+	}
+
+	fn evaluate(
+		parameters: &Self::Parameters,
+		left_input: &[u8],
+		right_input: &[u8],
+	) -> Result<Self::Output, Error> {
+		assert_eq!(left_input.len(), right_input.len());
+		assert!(left_input.len() * 8 <= Self::LEFT_INPUT_SIZE_BITS);
+		let chained: Vec<_> = left_input
+			.iter()
+			.chain(right_input.iter())
+			.map(|x| *x)
+			.collect();
+
+		<Self as CRHTrait>::evaluate(parameters, &chained)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_bls12_381::Fr;
+	use ark_ff::Field;
+	use ark_std::{test_rng, UniformRand};
+
+	#[test]
+	fn should_invert_forward_sbox() {
+		let rng = &mut test_rng();
+		let alpha_inv = alpha_inverse::<Fr>(5).unwrap();
+		for _ in 0..10 {
+			let x = Fr::rand(rng);
+			assert_eq!(x.pow([5u64]).pow(&alpha_inv), x);
+		}
+	}
+
+	#[test]
+	fn should_reject_non_invertible_alpha() {
+		// p - 1 is even for every odd prime
+		assert!(alpha_inverse::<Fr>(2).is_err());
+	}
+
+	#[derive(Default, Clone)]
+	struct RescueRounds3;
+
+	impl Rounds for RescueRounds3 {
+		const ALPHA: u64 = 5;
+		const ROUNDS: usize = 8;
+		const WIDTH: usize = 3;
+	}
+
+	#[test]
+	fn should_setup_parameters() {
+		let rng = &mut test_rng();
+		let params = <CRH<Fr, RescueRounds3> as CRHTrait>::setup(rng).unwrap();
+		assert_eq!(params.round_keys.len(), 2 * 8 * 3);
+		assert_eq!(params.mds_matrix.len(), 3);
+		assert!(params.mds_matrix.iter().all(|row| row.len() == 3));
+
+		let input = ark_ff::to_bytes![Fr::from(1u64), Fr::from(2u64)].unwrap();
+		let digest = <CRH<Fr, RescueRounds3> as CRHTrait>::evaluate(&params, &input).unwrap();
+		let other_params = <CRH<Fr, RescueRounds3> as CRHTrait>::setup(rng).unwrap();
+		let other_digest =
+			<CRH<Fr, RescueRounds3> as CRHTrait>::evaluate(&other_params, &input).unwrap();
+		assert_ne!(digest, other_digest);
+	}
+}
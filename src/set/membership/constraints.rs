@@ -1,5 +1,5 @@
 use super::{Private, SetMembership};
-use crate::Vec;
+use crate::{gadget_utils::is_member, Vec};
 use ark_ff::fields::PrimeField;
 use ark_r1cs_std::{
 	eq::EqGadget,
@@ -58,6 +58,26 @@ impl<F: PrimeField> AllocVar<Private<F>, F> for PrivateVar<F> {
 	}
 }
 
+/// Checks whether `target` equals any element of `set`.
+///
+/// The result is the disjunction of `target == set[i]` over all elements,
+/// so unlike `SetMembershipGadget::check` it requires no private witness.
+/// An empty set is never a match.
+pub fn check_set_membership<F: PrimeField>(
+	target: &FpVar<F>,
+	set: &[FpVar<F>],
+) -> Result<Boolean<F>, SynthesisError> {
+	is_member(target, set)
+}
+
+/// Enforces that `target` equals some element of `set`.
+pub fn enforce_set_membership<F: PrimeField>(
+	target: &FpVar<F>,
+	set: &[FpVar<F>],
+) -> Result<(), SynthesisError> {
+	check_set_membership(target, set)?.enforce_equal(&Boolean::TRUE)
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -91,4 +111,38 @@ mod test {
 		is_member_native.enforce_equal(&is_member).unwrap();
 		is_member.enforce_equal(&Boolean::TRUE).unwrap();
 	}
+
+	#[test]
+	fn should_find_target_in_root_set() {
+		let rng = &mut test_rng();
+		let roots = vec![Fq::rand(rng), Fq::rand(rng)];
+		let target = roots[1];
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let target_var = FpVar::<Fq>::new_witness(cs.clone(), || Ok(target)).unwrap();
+		let roots_var = Vec::<FpVar<Fq>>::new_input(cs.clone(), || Ok(roots)).unwrap();
+
+		let is_member = check_set_membership(&target_var, &roots_var).unwrap();
+		assert!(is_member.value().unwrap());
+
+		enforce_set_membership(&target_var, &roots_var).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_not_find_target_outside_root_set() {
+		let rng = &mut test_rng();
+		let roots = vec![Fq::rand(rng), Fq::rand(rng)];
+		let target = Fq::rand(rng);
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let target_var = FpVar::<Fq>::new_witness(cs.clone(), || Ok(target)).unwrap();
+		let roots_var = Vec::<FpVar<Fq>>::new_input(cs.clone(), || Ok(roots)).unwrap();
+
+		let is_member = check_set_membership(&target_var, &roots_var).unwrap();
+		assert!(!is_member.value().unwrap());
+
+		enforce_set_membership(&target_var, &roots_var).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
 }
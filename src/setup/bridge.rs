@@ -223,6 +223,10 @@ pub fn setup_random_circuit_x17<R: Rng, F: PrimeField>(rng: &mut R, curve: Curve
 	)
 }
 
+/// Returns the public inputs in the order they are allocated by the bridge
+/// circuit: `[chain_id, nullifier_hash, ..roots, root, recipient, relayer,
+/// fee]`, where `chain_id` is the public part of the leaf and the last three
+/// are the arbitrary input.
 pub fn get_public_inputs<F: PrimeField>(
 	chain_id: F,
 	nullifier_hash: F,
@@ -2,7 +2,7 @@ use ark_ec::PairingEngine;
 use ark_std::marker::PhantomData;
 use crate::{
 	identity::{constraints::CRHGadget as IdentityCRHGadget, CRH as IdentityCRH},
-	merkle_tree::{Config as MerkleConfig, Path, SparseMerkleTree},
+	merkle_tree::{Config as MerkleConfig, Path, Rc, SparseMerkleTree},
 	poseidon::{constraints::CRHGadget, sbox::PoseidonSbox, PoseidonParameters, Rounds, CRH},
 	utils::{
 		// exp 5
@@ -20,7 +20,7 @@ use crate::{
 use ark_crypto_primitives::SNARK;
 use ark_ff::fields::PrimeField;
 use ark_groth16::{Groth16, Proof, VerifyingKey};
-use ark_std::{rc::Rc, vec::Vec};
+use ark_std::vec::Vec;
 use crate::mimc::Rounds as MiMCRounds;
 
 #[derive(Default, Clone)]
@@ -43,6 +43,21 @@ impl Rounds for PoseidonRounds_x5_3 {
 	const WIDTH: usize = 3;
 }
 
+/// Rounds of the width 2 set, hashing a single element with one element of
+/// capacity. Without capacity, the permutation of two inputs could be
+/// inverted from any output, so the set is not meant for two-to-one hashes
+/// such as the nodes of a Merkle tree.
+#[derive(Default, Clone)]
+pub struct PoseidonRounds_x5_2;
+
+impl Rounds for PoseidonRounds_x5_2 {
+	const CAPACITY: usize = 1;
+	const FULL_ROUNDS: usize = 8;
+	const PARTIAL_ROUNDS: usize = 56;
+	const SBOX: PoseidonSbox = PoseidonSbox::Exponentiation(5);
+	const WIDTH: usize = 2;
+}
+
 #[derive(Default, Clone)]
 pub struct PoseidonRounds_x17_5;
 
@@ -63,6 +78,36 @@ impl Rounds for PoseidonRounds_x17_3 {
 	const WIDTH: usize = 3;
 }
 
+/// Rounds of the width 3 set keeping one element of capacity, for tests of
+/// inputs absorbed in blocks of the rate of two
+#[cfg(test)]
+#[derive(Default, Clone)]
+pub struct PoseidonRounds3Capacity1;
+
+#[cfg(test)]
+impl Rounds for PoseidonRounds3Capacity1 {
+	const CAPACITY: usize = 1;
+	const FULL_ROUNDS: usize = 8;
+	const PARTIAL_ROUNDS: usize = 57;
+	const SBOX: PoseidonSbox = PoseidonSbox::Exponentiation(5);
+	const WIDTH: usize = 3;
+}
+
+/// Rounds of the width 5 set keeping one element of capacity, for tests of
+/// inputs absorbed in blocks of the rate of four
+#[cfg(test)]
+#[derive(Default, Clone)]
+pub struct PoseidonRounds5Capacity1;
+
+#[cfg(test)]
+impl Rounds for PoseidonRounds5Capacity1 {
+	const CAPACITY: usize = 1;
+	const FULL_ROUNDS: usize = 8;
+	const PARTIAL_ROUNDS: usize = 60;
+	const SBOX: PoseidonSbox = PoseidonSbox::Exponentiation(5);
+	const WIDTH: usize = 5;
+}
+
 pub type PoseidonCRH_x5_3<F> = CRH<F, PoseidonRounds_x5_3>;
 pub type PoseidonCRH_x5_3Gadget<F> = CRHGadget<F, PoseidonRounds_x5_3>;
 
@@ -186,13 +231,13 @@ pub fn setup_params_x5_3<F: PrimeField>(curve: Curve) -> PoseidonParameters<F> {
 		Curve::Bls381 => {
 			let rounds3 = get_rounds_poseidon_bls381_x5_3::<F>();
 			let mds3 = get_mds_poseidon_bls381_x5_3::<F>();
-			let params3 = PoseidonParameters::<F>::new(rounds3, mds3);
+			let params3 = PoseidonParameters::from_rounds::<PoseidonRounds_x5_3>(rounds3, mds3);
 			params3
 		},
 		Curve::Bn254 => {
 			let rounds3 = get_rounds_poseidon_bn254_x5_3::<F>();
 			let mds3 = get_mds_poseidon_bn254_x5_3::<F>();
-			let params3 = PoseidonParameters::<F>::new(rounds3, mds3);
+			let params3 = PoseidonParameters::from_rounds::<PoseidonRounds_x5_3>(rounds3, mds3);
 			params3
 		},
 	}
@@ -204,13 +249,13 @@ pub fn setup_params_x5_5<F: PrimeField>(curve: Curve) -> PoseidonParameters<F> {
 		Curve::Bls381 => {
 			let rounds5 = get_rounds_poseidon_bls381_x5_5::<F>();
 			let mds5 = get_mds_poseidon_bls381_x5_5::<F>();
-			let params5 = PoseidonParameters::<F>::new(rounds5, mds5);
+			let params5 = PoseidonParameters::from_rounds::<PoseidonRounds_x5_5>(rounds5, mds5);
 			params5
 		},
 		Curve::Bn254 => {
 			let rounds5 = get_rounds_poseidon_bn254_x5_5::<F>();
 			let mds5 = get_mds_poseidon_bn254_x5_5::<F>();
-			let params5 = PoseidonParameters::<F>::new(rounds5, mds5);
+			let params5 = PoseidonParameters::from_rounds::<PoseidonRounds_x5_5>(rounds5, mds5);
 			params5
 		},
 	}
@@ -222,13 +267,13 @@ pub fn setup_params_x17_3<F: PrimeField>(curve: Curve) -> PoseidonParameters<F>
 		Curve::Bls381 => {
 			let rounds3 = get_rounds_poseidon_bls381_x17_3::<F>();
 			let mds3 = get_mds_poseidon_bls381_x17_3::<F>();
-			let params3 = PoseidonParameters::<F>::new(rounds3, mds3);
+			let params3 = PoseidonParameters::from_rounds::<PoseidonRounds_x17_3>(rounds3, mds3);
 			params3
 		},
 		Curve::Bn254 => {
 			let rounds3 = get_rounds_poseidon_bn254_x17_3::<F>();
 			let mds3 = get_mds_poseidon_bn254_x17_3::<F>();
-			let params3 = PoseidonParameters::<F>::new(rounds3, mds3);
+			let params3 = PoseidonParameters::from_rounds::<PoseidonRounds_x17_3>(rounds3, mds3);
 			params3
 		},
 	}
@@ -240,13 +285,13 @@ pub fn setup_params_x17_5<F: PrimeField>(curve: Curve) -> PoseidonParameters<F>
 		Curve::Bls381 => {
 			let rounds5 = get_rounds_poseidon_bls381_x17_5::<F>();
 			let mds5 = get_mds_poseidon_bls381_x17_5::<F>();
-			let params5 = PoseidonParameters::<F>::new(rounds5, mds5);
+			let params5 = PoseidonParameters::from_rounds::<PoseidonRounds_x17_5>(rounds5, mds5);
 			params5
 		},
 		Curve::Bn254 => {
 			let rounds5 = get_rounds_poseidon_bn254_x17_5::<F>();
 			let mds5 = get_mds_poseidon_bn254_x17_5::<F>();
-			let params5 = PoseidonParameters::<F>::new(rounds5, mds5);
+			let params5 = PoseidonParameters::from_rounds::<PoseidonRounds_x17_5>(rounds5, mds5);
 			params5
 		},
 	}
@@ -245,6 +245,9 @@ pub fn setup_random_circuit_mimc_220<R: Rng, F: PrimeField>(rng: &mut R, curve:
 	setup_circuit_mimc_220(&leaves, index, recipient, relayer, rng, curve)
 }
 
+/// Returns the public inputs in the order they are allocated by the mixer
+/// circuit: `[nullifier_hash, root, recipient, relayer]`. The leaf has no
+/// public part, and `recipient` and `relayer` are the arbitrary input.
 pub fn get_public_inputs<F: PrimeField>(
 	nullifier_hash: F,
 	root: F,
@@ -0,0 +1,182 @@
+use super::Signature;
+use crate::{
+	poseidon::{
+		constraints::{CRHGadget as PoseidonCRHGadget, PoseidonParametersVar},
+		Rounds,
+	},
+	Vec,
+};
+use ark_crypto_primitives::crh::constraints::CRHGadget as CRHGadgetTrait;
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::{borrow::Borrow, marker::PhantomData};
+
+pub struct SignatureVar<F: PrimeField, C: ProjectiveCurve<BaseField = F>, GG: CurveVar<C, F>>
+where
+	for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+	pub r: GG,
+	/// Little-endian bits of the scalar `s`
+	pub s: Vec<Boolean<F>>,
+	curve: PhantomData<C>,
+}
+
+impl<F, C, GG> AllocVar<Signature<C>, F> for SignatureVar<F, C, GG>
+where
+	F: PrimeField,
+	C: ProjectiveCurve<BaseField = F>,
+	GG: CurveVar<C, F>,
+	for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+	fn new_variable<T: Borrow<Signature<C>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
+		let signature = f().map(|s| s.borrow().clone());
+
+		let r = GG::new_variable(
+			cs.clone(),
+			|| {
+				signature
+					.as_ref()
+					.map(|s| s.r.into_projective())
+					.map_err(|e| *e)
+			},
+			mode,
+		)?;
+		let s_bits = signature
+			.as_ref()
+			.map(|s| s.s.into_repr().to_bits_le())
+			.map_err(|e| *e);
+		let s = (0..C::ScalarField::size_in_bits())
+			.map(|i| {
+				Boolean::new_variable(
+					cs.clone(),
+					|| s_bits.as_ref().map(|bits| bits[i]).map_err(|e| *e),
+					mode,
+				)
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(Self {
+			r,
+			s,
+			curve: PhantomData,
+		})
+	}
+}
+
+pub struct PoseidonSchnorrGadget<F: PrimeField, C: ProjectiveCurve<BaseField = F>, GG, H: Rounds>
+where
+	GG: CurveVar<C, F>,
+	for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+	field: PhantomData<F>,
+	curve: PhantomData<C>,
+	curve_var: PhantomData<GG>,
+	rounds: PhantomData<H>,
+}
+
+impl<F, C, GG, H> PoseidonSchnorrGadget<F, C, GG, H>
+where
+	F: PrimeField,
+	C: ProjectiveCurve<BaseField = F>,
+	GG: CurveVar<C, F>,
+	H: Rounds,
+	for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+	/// Returns whether `signature` is a valid signature of `message` under
+	/// `pubkey`, as produced by `PoseidonSchnorr::sign`
+	pub fn verify_signature(
+		params: &PoseidonParametersVar<F>,
+		pubkey: &GG,
+		message: &FpVar<F>,
+		signature: &SignatureVar<F, C, GG>,
+	) -> Result<Boolean<F>, SynthesisError> {
+		// The challenge is used as an integer, which is equivalent to reducing
+		// it modulo the group order natively
+		let mut bytes = signature.r.to_bytes()?;
+		bytes.extend(pubkey.to_bytes()?);
+		bytes.extend(message.to_bytes()?);
+		let e = <PoseidonCRHGadget<F, H> as CRHGadgetTrait<_, _>>::evaluate(params, &bytes)?;
+		let e_bits = e.to_bits_le()?;
+
+		let generator = GG::constant(C::prime_subgroup_generator());
+		let lhs = generator.scalar_mul_le(signature.s.iter())?;
+		let rhs = pubkey.scalar_mul_le(e_bits.iter())? + &signature.r;
+
+		lhs.is_eq(&rhs)
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		setup::common::{setup_params_x5_5, Curve, PoseidonRounds_x5_5},
+		signature::PoseidonSchnorr,
+	};
+	use ark_ed_on_bn254::{constraints::EdwardsVar, EdwardsProjective, Fq};
+	use ark_ff::UniformRand;
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::test_rng;
+
+	type Schnorr = PoseidonSchnorr<Fq, EdwardsProjective, PoseidonRounds_x5_5>;
+	type SchnorrGadget =
+		PoseidonSchnorrGadget<Fq, EdwardsProjective, EdwardsVar, PoseidonRounds_x5_5>;
+
+	fn verify_in_circuit(
+		params: &crate::poseidon::PoseidonParameters<Fq>,
+		pk: &<EdwardsProjective as ProjectiveCurve>::Affine,
+		message: Fq,
+		signature: &Signature<EdwardsProjective>,
+	) -> (bool, bool) {
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let params_var = PoseidonParametersVar::new_constant(cs.clone(), params).unwrap();
+		let pk_var = EdwardsVar::new_witness(cs.clone(), || Ok(pk.into_projective())).unwrap();
+		let message_var = FpVar::new_input(cs.clone(), || Ok(message)).unwrap();
+		let signature_var = SignatureVar::new_witness(cs.clone(), || Ok(signature)).unwrap();
+
+		let is_valid =
+			SchnorrGadget::verify_signature(&params_var, &pk_var, &message_var, &signature_var)
+				.unwrap();
+		let value = is_valid.value().unwrap();
+		is_valid.enforce_equal(&Boolean::TRUE).unwrap();
+		(value, cs.is_satisfied().unwrap())
+	}
+
+	#[test]
+	fn should_verify_valid_signature_in_circuit() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_5::<Fq>(Curve::Bn254);
+		let (sk, pk) = Schnorr::keygen(rng);
+		let message = Fq::rand(rng);
+		let signature = Schnorr::sign(&params, &sk, &message, rng).unwrap();
+
+		assert_eq!(
+			verify_in_circuit(&params, &pk, message, &signature),
+			(true, true)
+		);
+	}
+
+	#[test]
+	fn should_reject_tampered_signature_in_circuit() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_5::<Fq>(Curve::Bn254);
+		let (sk, pk) = Schnorr::keygen(rng);
+		let message = Fq::rand(rng);
+		let mut signature = Schnorr::sign(&params, &sk, &message, rng).unwrap();
+		signature.s += <EdwardsProjective as ProjectiveCurve>::ScalarField::from(1u64);
+
+		assert_eq!(
+			verify_in_circuit(&params, &pk, message, &signature),
+			(false, false)
+		);
+	}
+}
@@ -0,0 +1,103 @@
+use crate::poseidon::{PoseidonParameters, Rounds, CRH as PoseidonCRH};
+use ark_crypto_primitives::{Error, CRH as CRHTrait};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{to_bytes, PrimeField, UniformRand};
+use ark_std::{marker::PhantomData, rand::Rng};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// Schnorr signature `(R, s)` such that `s * G = R + e * PK`, with the
+/// challenge `e = Poseidon(R, PK, message)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature<C: ProjectiveCurve> {
+	pub r: C::Affine,
+	pub s: C::ScalarField,
+}
+
+/// Schnorr signatures over a twisted Edwards curve whose base field is the
+/// constraint field, using Poseidon for the challenge so that verification is
+/// cheap in-circuit. `H` must hash 5 field elements.
+pub struct PoseidonSchnorr<F: PrimeField, C: ProjectiveCurve<BaseField = F>, H: Rounds> {
+	field: PhantomData<F>,
+	curve: PhantomData<C>,
+	rounds: PhantomData<H>,
+}
+
+impl<F: PrimeField, C: ProjectiveCurve<BaseField = F>, H: Rounds> PoseidonSchnorr<F, C, H> {
+	pub fn keygen<R: Rng>(rng: &mut R) -> (C::ScalarField, C::Affine) {
+		let sk = C::ScalarField::rand(rng);
+		let pk = C::prime_subgroup_generator()
+			.mul(sk.into_repr())
+			.into_affine();
+		(sk, pk)
+	}
+
+	/// Computes the challenge `Poseidon(R, PK, message)`, with points encoded
+	/// as their `(x, y)` coordinates
+	pub fn challenge(
+		params: &PoseidonParameters<F>,
+		r: &C::Affine,
+		pk: &C::Affine,
+		message: &F,
+	) -> Result<F, Error> {
+		let bytes = to_bytes![r, pk, message]?;
+		PoseidonCRH::<F, H>::evaluate(params, &bytes)
+	}
+
+	pub fn sign<R: Rng>(
+		params: &PoseidonParameters<F>,
+		sk: &C::ScalarField,
+		message: &F,
+		rng: &mut R,
+	) -> Result<Signature<C>, Error> {
+		let generator = C::prime_subgroup_generator();
+		let pk = generator.mul(sk.into_repr()).into_affine();
+
+		let k = C::ScalarField::rand(rng);
+		let r = generator.mul(k.into_repr()).into_affine();
+		let e = Self::challenge(params, &r, &pk, message)?;
+		let e = C::ScalarField::from_le_bytes_mod_order(&to_bytes![e]?);
+
+		Ok(Signature { r, s: k + e * sk })
+	}
+
+	pub fn verify(
+		params: &PoseidonParameters<F>,
+		pk: &C::Affine,
+		message: &F,
+		signature: &Signature<C>,
+	) -> Result<bool, Error> {
+		let e = Self::challenge(params, &signature.r, pk, message)?;
+		let e = C::ScalarField::from_le_bytes_mod_order(&to_bytes![e]?);
+
+		let lhs = C::prime_subgroup_generator().mul(signature.s.into_repr());
+		let rhs = signature.r.into_projective() + pk.mul(e.into_repr());
+		Ok(lhs == rhs)
+	}
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::setup::common::{setup_params_x5_5, Curve, PoseidonRounds_x5_5};
+	use ark_ed_on_bn254::{EdwardsProjective, Fq};
+	use ark_std::test_rng;
+
+	type Schnorr = PoseidonSchnorr<Fq, EdwardsProjective, PoseidonRounds_x5_5>;
+
+	#[test]
+	fn should_verify_signature() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_5::<Fq>(Curve::Bn254);
+		let (sk, pk) = Schnorr::keygen(rng);
+		let message = Fq::rand(rng);
+
+		let signature = Schnorr::sign(&params, &sk, &message, rng).unwrap();
+		assert!(Schnorr::verify(&params, &pk, &message, &signature).unwrap());
+
+		let other_message = Fq::rand(rng);
+		assert!(!Schnorr::verify(&params, &pk, &other_message, &signature).unwrap());
+	}
+}
@@ -0,0 +1,87 @@
+//! Constraint counting utilities, used to tune circuits and to catch
+//! regressions in the size of the hash gadgets.
+
+use crate::{
+	utils::{full_chunk_size, to_field_var_elements_chunked, Endianness},
+	Vec,
+};
+use ark_crypto_primitives::crh::{constraints::CRHGadget, CRH};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{prelude::*, uint8::UInt8};
+use ark_relations::r1cs::{ConstraintSystem, SynthesisError};
+
+/// Returns the number of constraints added by a single `evaluate` of the
+/// gadget `G` on `input_len` witnessed bytes. Parameters are allocated as
+/// constants and input allocation is not counted.
+pub fn constraint_count<F, H, G>(
+	params: &H::Parameters,
+	input_len: usize,
+) -> Result<usize, SynthesisError>
+where
+	F: PrimeField,
+	H: CRH,
+	G: CRHGadget<H, F>,
+{
+	let cs = ConstraintSystem::<F>::new_ref();
+	let params_var = G::ParametersVar::new_constant(cs.clone(), params)?;
+	let input = Vec::<UInt8<F>>::new_witness(cs.clone(), || Ok(vec![0u8; input_len]))?;
+
+	let before = cs.num_constraints();
+	G::evaluate(&params_var, &input)?;
+	Ok(cs.num_constraints() - before)
+}
+
+/// Returns the number of constraints spent packing `input_len` witnessed
+/// bytes into field elements, which is included in `constraint_count`.
+pub fn input_packing_constraint_count<F: PrimeField>(
+	input_len: usize,
+) -> Result<usize, SynthesisError> {
+	let cs = ConstraintSystem::<F>::new_ref();
+	let input = Vec::<UInt8<F>>::new_witness(cs.clone(), || Ok(vec![0u8; input_len]))?;
+
+	let before = cs.num_constraints();
+	to_field_var_elements_chunked(&input, full_chunk_size::<F>(), Endianness::Little)?;
+	Ok(cs.num_constraints() - before)
+}
+
+#[cfg(feature = "default_poseidon")]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::setup::common::{
+		setup_params_x5_3, setup_params_x5_5, Curve, PoseidonCRH_x5_3, PoseidonCRH_x5_3Gadget,
+		PoseidonCRH_x5_5, PoseidonCRH_x5_5Gadget,
+	};
+	use ark_bn254::Fr;
+
+	// Every state element is a variable, so each S-box costs 3 constraints:
+	// 3 * (FULL_ROUNDS * WIDTH + PARTIAL_ROUNDS)
+	const POSEIDON_X5_3_PERMUTATION: usize = 243;
+	const POSEIDON_X5_5_PERMUTATION: usize = 300;
+
+	#[test]
+	fn should_pin_poseidon_x5_3_constraints() {
+		let params = setup_params_x5_3::<Fr>(Curve::Bn254);
+		let input_len = 3 * 32;
+		let total = constraint_count::<Fr, PoseidonCRH_x5_3<Fr>, PoseidonCRH_x5_3Gadget<Fr>>(
+			&params, input_len,
+		)
+		.unwrap();
+		let packing = input_packing_constraint_count::<Fr>(input_len).unwrap();
+
+		assert_eq!(total - packing, POSEIDON_X5_3_PERMUTATION);
+	}
+
+	#[test]
+	fn should_pin_poseidon_x5_5_constraints() {
+		let params = setup_params_x5_5::<Fr>(Curve::Bn254);
+		let input_len = 5 * 32;
+		let total = constraint_count::<Fr, PoseidonCRH_x5_5<Fr>, PoseidonCRH_x5_5Gadget<Fr>>(
+			&params, input_len,
+		)
+		.unwrap();
+		let packing = input_packing_constraint_count::<Fr>(input_len).unwrap();
+
+		assert_eq!(total - packing, POSEIDON_X5_5_PERMUTATION);
+	}
+}
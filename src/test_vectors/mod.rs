@@ -0,0 +1,127 @@
+//! Fixed digests of the shipped parameter sets over bn254, taken from the
+//! current implementations. An edit to a round constant or to a permutation
+//! changes every digest built on it, and fails the vectors here instead of
+//! going unnoticed.
+//!
+//! Inputs are field elements, passed to `CRH::evaluate` as their `to_bytes`
+//! encoding. Digests are big-endian hex, like the constants in `utils`.
+
+/// Input field elements and the expected digest of a hash
+pub struct TestVector {
+	pub input: &'static [u64],
+	pub digest: &'static str,
+}
+
+/// `PoseidonRounds_x5_3` with the bn254 round keys and MDS matrix. The first
+/// vector is the circomlib `poseidon([1, 2])` digest.
+pub const POSEIDON_BN254_X5_3: [TestVector; 4] = [
+	TestVector {
+		input: &[0, 1, 2],
+		digest: "0x115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+	},
+	TestVector {
+		input: &[1],
+		digest: "0x0ee069e6aa796ef0e46cbd51d10468393d443a00f5affe72898d9ab62e335e16",
+	},
+	TestVector {
+		input: &[1, 2],
+		digest: "0x2c0ef7d998395eb71b4ae3b58a557ca68129d749127a1ee115795198df0416f4",
+	},
+	TestVector {
+		input: &[u64::MAX, 42],
+		digest: "0x18b87ed260ef467860d4e3b74e33ee48155006753a2a522302f9c768c8008176",
+	},
+];
+
+/// `PoseidonRounds_x5_5` with the bn254 round keys and MDS matrix
+pub const POSEIDON_BN254_X5_5: [TestVector; 3] = [
+	TestVector {
+		input: &[0, 1, 2, 3, 4],
+		digest: "0x299c867db6c1fdd79dcefa40e4510b9837e60ebb1ce0663dbaa525df65250465",
+	},
+	TestVector {
+		input: &[1],
+		digest: "0x2b010b099164057693e9f6803f735beaf04e0fe71f86ca19f04c043d9009e72f",
+	},
+	TestVector {
+		input: &[1, 2, 3, 4],
+		digest: "0x0c8011b52e6f86de62cd4a8768ccceef76b030715b0e4b0f0bdbf6af349a4838",
+	},
+];
+
+/// `MiMCRounds_220_3` with the round keys of `get_rounds_mimc_220` and
+/// `k = 0`
+pub const MIMC_220_BN254: [TestVector; 4] = [
+	TestVector {
+		input: &[0, 1, 2],
+		digest: "0x05a6f8cb4c92c73c4b5e1c917ea8e2f5bb763667a73dc06b37957f0aa3015088",
+	},
+	TestVector {
+		input: &[1],
+		digest: "0x29dbc3a33f3981fb97e10190527969b8edea0c118de64514d60d5c184acda355",
+	},
+	TestVector {
+		input: &[1, 2],
+		digest: "0x2235977c585ff4858bb988cd688bbebeafa8379d6cd6740ab49b560825a20062",
+	},
+	TestVector {
+		input: &[u64::MAX, 42],
+		digest: "0x2db805e3af0c8b76e80aafe4a70f34549ff0c671af5457c2494d720de146b2fa",
+	},
+];
+
+#[cfg(all(
+	feature = "r1cs",
+	feature = "default_poseidon",
+	feature = "default_mimc"
+))]
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{
+		setup::common::{
+			setup_mimc_220, setup_params_x5_3, setup_params_x5_5, Curve, MiMCCRH_220,
+			PoseidonCRH_x5_3, PoseidonCRH_x5_5,
+		},
+		utils::parse_vec,
+	};
+	use ark_bn254::Fr;
+	use ark_crypto_primitives::CRH;
+	use ark_ff::to_bytes;
+
+	fn check_vectors<H: CRH<Output = Fr>>(
+		name: &str,
+		params: &H::Parameters,
+		vectors: &[TestVector],
+	) {
+		for (i, vector) in vectors.iter().enumerate() {
+			let input = vector
+				.input
+				.iter()
+				.map(|x| Fr::from(*x))
+				.collect::<Vec<_>>();
+			let digest = H::evaluate(params, &to_bytes![input].unwrap()).unwrap();
+			let expected = parse_vec::<Fr>(vec![vector.digest])[0];
+			assert_eq!(digest, expected, "{} vector {}", name, i);
+		}
+	}
+
+	#[test]
+	fn should_match_test_vectors() {
+		check_vectors::<PoseidonCRH_x5_3<Fr>>(
+			"poseidon bn254 x5_3",
+			&setup_params_x5_3(Curve::Bn254),
+			&POSEIDON_BN254_X5_3,
+		);
+		check_vectors::<PoseidonCRH_x5_5<Fr>>(
+			"poseidon bn254 x5_5",
+			&setup_params_x5_5(Curve::Bn254),
+			&POSEIDON_BN254_X5_5,
+		);
+		check_vectors::<MiMCCRH_220<Fr>>(
+			"mimc 220 bn254",
+			&setup_mimc_220(Curve::Bn254),
+			&MIMC_220_BN254,
+		);
+	}
+}
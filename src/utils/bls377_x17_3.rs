@@ -0,0 +1,156 @@
+// https://extgit.iaik.tugraz.at/krypto/hadeshash
+
+// Parameter for:
+// exponentiation = 17
+// width = 3
+// full rounds = 8
+// partial rounds = 33
+// prime field =
+// 0x12ab655e9a2ca55660b44d1e5c37b00159aa76fed00000010a11800000000001
+
+// Grain LFSR of generate_parameters_grain.sage, arguments:
+// 1 0 253 3 8 33
+// 0x12ab655e9a2ca55660b44d1e5c37b00159aa76fed00000010a11800000000001
+pub const ROUND_CONSTS: [&str; 123] = [
+	"0x039a10c734adecf6699e1eea0b04ab0c864bc26486829be03fda4f7837250edd",
+	"0x1266b0af2b86019dc3c22b32729cf31049a1a4fcd5c7e1304348302370228eb3",
+	"0x03f02e99fa8381bb2c9fedc8fba35a92f4486d58b77e12f4062d9ff34a6a2754",
+	"0x06bb2ea5ab87e6fa52283c23b61e017af0244b1d90350f5f278763a75f8006ff",
+	"0x08647570dfafd1e5dee64e25d1350665786ec0321def0ee3b6aa547c1007b856",
+	"0x0d9e116b022c4b01fa7ed274365ad850435825016d4965e0373343222b152437",
+	"0x0bcb16beb0762504315d28483b658e078a87080db7cd1d5bc7aac9844b455e98",
+	"0x0f624820e11ed6183711d7abe1f404a2f038d84ae1f40d142e399b56320f0e3a",
+	"0x0246f25de133e6136c449e2815bb7f4147882842c76710d93cf49421f01191ea",
+	"0x0ecdad9c20b9207341ffb814e82bef72852e813bd9ab643ffbb3a8db51c450f6",
+	"0x0b1139b4f8a716fd50076f817e1164f7233b9e0d3bc6f19264632fe0f8c8699c",
+	"0x0c6b96b414220a2c2b5dec884071259a5c05752a8798fe2cf447271be22f3bfd",
+	"0x02c9c0e144b5f6f2a0c1f4718c1ac25e49bfb013a913f48bd625f2d2ba1b5b8f",
+	"0x0362df232d805e79f677a204040b5d67ad0b7c172bf75d3cc107ef32ae17b91e",
+	"0x07dc517dcd89c499547774f029198464bdb3f5b4863d49138f205dab7a1112e8",
+	"0x10abd8153eadceb3118a903fef03767e5eaf377bb237cd650cf15d9d99ae96b9",
+	"0x10d9702db73b114dd94183ca418ea30987cd2cf3e39c43ba79c80045debf3bff",
+	"0x01d791f7e615afbddc481d6d7f27a82cebdfa7e61c1f31d5ee6b977759c8376d",
+	"0x079daa1d88ae7648b26090472a90dd9597c089e2c3874fc76f380473d7164b37",
+	"0x0567f85c1e2fb0edee8295159d90de081598d0050e15d3b2e7c316312bfa49bf",
+	"0x0f6898f3060937bf85c07117849af5719437250a6b7a2b58a038d59a4cf31e54",
+	"0x09e1414e24396d963f47e0dbdf7a3101f95f780eed2f43f953974485228b46d6",
+	"0x0ec85c71561a097d1a0f5d595289d7b27c1faa0e134e627200c74b089a31e9ed",
+	"0x08051f05cd3f3b396cda0195f3fd7f9ae29de61595d79845140342ceee98d11d",
+	"0x0ce0a2423bf99644c397f426a90edcdd6c6f06f306c946fbaa2990e33e27f8c5",
+	"0x00ed808e22ce93b4d40dc7c30aa1c4464f0b1b141ab474b456571d342d1f329d",
+	"0x0e91a495d2e1c3fb8d9e26c48c35574f1ce1562a209e02dd6dc4bbf539f5fef4",
+	"0x069b28e2bf84d9a7ff13e301c6f82886e52fdbf8463b823b5ce25bda34e1d508",
+	"0x125a9feb97fa0267d0859e6726aff430e0bc170cdb3a14c732d417bdbfa560b3",
+	"0x06e3b798b3be1445620f64b0aa60cbf977786291cbc0c6c0025556c30fad1050",
+	"0x098184eb078cf8073499c54cfab07f60657a41829e7ae308d5761d98393bda28",
+	"0x067604545ef45ee13f09f413590f71b4386e95c587aa092adc20e7836cdd1648",
+	"0x08aa1a67154025a5b4ee46c50bcfc1ca781582a576e8fc7be0e941067ea76c7c",
+	"0x0ca80eaef954e7fcd17f05d710fe90af5222b2fc5c8e6ae443d30e9e4cd3c3bf",
+	"0x0297d8be527b8a29865895e17ad947372194a0e081343a201644e2513af7ae75",
+	"0x0c5cb4a0df2f18409b00b85fad6abdde976f7aa20d221a0808f547742d227d9e",
+	"0x0a5a7b91ccda867dd938bb59720ae93f9c1225e261c68c24e98693da0cf9460f",
+	"0x0bf1cc9f4cb5f2d7b61b0c3b03456fdd34750327a5ce39b065c5dfce812f4ac9",
+	"0x122fe363507345bacd3b9600c24d5f826e9f509420ee0f8562d3c7cfb16d4c50",
+	"0x01770fb1840a0a40e2c4f7369c6c6f0fe8aae01d1837ff881a1d20688764624c",
+	"0x0e7e9123e2674b18908322a5bdc454823e260631858b1dd80332a2415c3e26c7",
+	"0x01e7c61648cf84da0dc63ff7ae73fc1471032c469a1335d5ee9c80414e7b51d4",
+	"0x04e6b946a1e7f4b3f84c3a90a93b1e0dda961528e4106a3cf93ea53c189e63e6",
+	"0x0ad95fcf6d6755890a46e08c56e3b6a032253398bd2b376d377c576ae032d1ab",
+	"0x0c787ff2048ace0e9519fa0305c9598aefeca05a57d8c59c528fbbfb6de79af5",
+	"0x0dd7d7d7a037344dbfa2919dd8e9cf81a17dd531732a0cc0ea0c3b91762b4d4d",
+	"0x0ee1edbc80978aa78b4681c186434fc7310f4a947c72bca70d006e87fb7325f8",
+	"0x02025de19540c469c845c46e365a2c0ed1a790af385c0ec0776bb5ddab06116f",
+	"0x07f19d257ba3d9dc2086d31d633d1f9e7ffe371d28744857e9ce485c514e1723",
+	"0x0555031d40f85b91850ee8ed752cc6b6a5694bca9fc34fe4e71cf228687ed313",
+	"0x014fac092f2429873ccaca702ab91254e3f136ef5851ec070d0a39f5db2a8cce",
+	"0x11e2e939ebb305ba69d7e30c1d3155cf5f21cf57c4eb396834aa02a154ed1d32",
+	"0x06166bd483b2de043214bd2e0fe8117f1ab082b8905f85306542a7eb06b03aa1",
+	"0x116a259bb9efd0b32264945bef1af4b4dbb7c834985b009a9c8c798dd0954d1e",
+	"0x10ce094b06cde2f361baa8f9b03335d415bfc58b39c7fc0fecd9f118e31eaecb",
+	"0x0b1665bda7f09c6b982d6370c6b88c28fa34a60888aba9cba3bd8ea4ebce8827",
+	"0x0e87f15f02282d4f455cfb4fbfcd208056cefb62151a060b1cbf8cd6d1d1ac73",
+	"0x11e6818710a14bd682b604645d009cd381740b8fce6bd4efb4640cf94e086358",
+	"0x123eaf0271a148497c1499f662b2a21e999b4be6715f8682b02af2b0bff521e1",
+	"0x0603e57aa1512d34778b65a2bb1d3935c4dbcd1287509aca2a184d2c9b053751",
+	"0x039147bd9a5c761caef578192171ea95564fa6595cbcb6a0dea1c200b6d6f982",
+	"0x048e2e3ac0482d3101ecfa8cf3c728228c1eaeeb783b1d32456e5a286217ad2d",
+	"0x02003f91eed02381e7b56f8e77cd42da6888ad3727ad8d3f6fc297d909c1bb0a",
+	"0x1100be8480cefec3efbbfe5f9e4ff5e357d4826100280458243a5ab31d7bd15b",
+	"0x0a85863368892d362da848f988f0adeb321aa79d438313df30afdba8d7749b58",
+	"0x00472eb600a6a40d1a8ff8288c312bf77c260fd97fc60553c1c3f9058610be67",
+	"0x05774f686797bc9e02e772859d510ff137dfe0746153a4639d74c3c63632b77e",
+	"0x0ca103fbc026e00e221f3a724e14543f4f1bb676646a78a7368f19293b18dbfa",
+	"0x011facd5db12e52e49df025e5bdbff8d8911ff2170423f15478aa541f442df13",
+	"0x0f7672b6d8b9e315c0df241255fd3a9894536ded1126a545271a75cf17702b0e",
+	"0x107437eb2dd0e4e2631b16aeccab422b8e3f61dc8d6bf0825a4c8f5b3098d1e8",
+	"0x0e0ac79e3000a8ca1fea9dcbdc4eb4948cce8f115f5518d1782b0f377e39a9b5",
+	"0x0a20c2408fdc3eef5f5d50827873bf33fccbad93f93423e4cf8e70a64589a18d",
+	"0x002daa19177f4fe6c48cf7aa57623ad2b36ffcf080a5412a649831faac3c7e47",
+	"0x10fa8183c320543473023af62aed955c3d8631e643cee68ee76b5498862839c3",
+	"0x107580eb0c09797390dfa5530060310ab00f748eb0173b7eb0239cec9f4967db",
+	"0x05ee335a0bb0262541b3fc46e52cce0f30d4d435d6b8fd606e4988103a5f387d",
+	"0x11fb95c58ceceec8b62b67243a944efcbd378694422f6c0d93ce7251b96131dd",
+	"0x0ddda4f1a3d2ecf870b2f82be2f70c17a9e9196e50afc76800cd55c7474c9116",
+	"0x0855483c0124b06fb5d02c4639b85438b0966efd5d7381fbbba10b4aa012d586",
+	"0x0b1f0e2a756675edbc72630c6044908e40ee663fcedfbf979897237e80d905ac",
+	"0x00160060ef38fe4709164f4088e269d8a239414a1a7bae7a8f2d3b222c10089a",
+	"0x12882f796eff4a89927e27709b75ad0d8252797b45fdff948a7886e9591b180f",
+	"0x0746510dac59ccb4fb9ea0c4564d7fa2a7d4862d67724800aa73883ae8831cfe",
+	"0x09c456d61b1106fe3077ada29f5f64d8b0ec96b80af455c526bfe05b34e32d99",
+	"0x015df32ee2ae6e24bf23169f3b4dae04f2871b89a343b494ea2a195e37427677",
+	"0x08b0505bc69cf027ca17f478c0bce28e91bdccc4fdfabcd4d53abdc069d4ab7a",
+	"0x011dd1182ae79d8ec8f301594b77b1034e4e4edc3f331d80f3d3fda12fe1543a",
+	"0x01fb9b590197afc2db1c2400c9d968641faae2f0fce7539eb62ffc3a70261abe",
+	"0x08724e92e8db6937684634a0a33936020de2c7278adc5a330b7549d389fd401c",
+	"0x06d8876af92d89d5128b33e72150ccba3df2161da33bd4671d42bd850594a93d",
+	"0x046c28cf285e40011b9783a1a369784ca69b53f11d5f9431f0416419c172aad0",
+	"0x0c939d1bed219ce89a67e9bfc2abed36d235e80f24bbfa65bb831ac7a478ae12",
+	"0x10d86e1c159ae401a2597098ced927b73ffb290566eea622dd238dbdefebd891",
+	"0x0406f542137b7dd30d08d9a16e63a306c883755d790705536165f8dcaa44ff01",
+	"0x0bdf8c74b5873f283fe06a5892c12dcc8dc5946fdbf14b388426b7f60f35da3a",
+	"0x0ce6b674ef38feed75af34387d34afc5f719be35c0b6ba274b55fee0427fbfc8",
+	"0x0cf72130336a9270e954e11eb16184ccaaba9ea214bb3600ec19b112636d4c07",
+	"0x0c8334ceb6b19fae41b3c8fffef08c81bc1d92a31674b6c383fe85d538727dea",
+	"0x045aa71562ce6c5a18700feefb1cef82f11e7d723a77fa44a3f9121018f8f1bd",
+	"0x088e01116e0dcd686b533cdbe38a0639712e32cd413eff7da5548118f600012c",
+	"0x10fad34f146fd1610f9c464e70286fd9fbb51fef1a84ab4fe13e8101e60af29b",
+	"0x02c95ae1ad498b643984b12a271c09daa6e36be3dc17088e421de6d2b48cbe55",
+	"0x046b49b0ab6c95130e822ea7001cacdbca6941b4aa4d672d82f803875601f389",
+	"0x0fcff4a4e5b0c1cc33b9d0c4dd4adbc3621a5df4ca6e077f6396dcb9feb290ad",
+	"0x09b8691e687bed90a86b9b64c10ee94f4b164d233679687770ec662777da7d29",
+	"0x1249fd09d858930140373fa8fd526f1012682f9c01a6cecfc4c85359f39a563a",
+	"0x11db38440d5099d3f773a1d0f547014daa3a83dcf50fb4612713cb9773127798",
+	"0x038e3dee976472805227ffad0ee767f0cd3e975ec2938eb5ceb11b9994536d83",
+	"0x0ea01cb1f66010bb0367361da8f2aba73c63465656977e43ce91b889f35621a5",
+	"0x0cf44fc5e986224ca081d1d456c5af7ca984fbd352dc0d63106414e2647fa333",
+	"0x0ac67a70c492191ca311899b30796913a4fcbe3ceb2032faec9157d57c9e3f17",
+	"0x014e24ec58a22b34f510dbc4d7e51fda4ff3813143f37a850454d3b2ee2b36a1",
+	"0x09bec970792fac4aca6c361c83622ba8cd37ea110ce5fc624e74526a0b1032c3",
+	"0x01a5a566e79c57f9f55b41a1cc7ae363bf4ed4f8ed10161e37655fa5894fffbf",
+	"0x0e803d55463c657c0ccae31464490f6596d644596238ebb7f0a0366419208072",
+	"0x026c30c99a9b55dd6e59ee5f0a2ac03ffd0cf85389fcfb172a3c15cd06b2444d",
+	"0x0f2cdd93aedd1ed6def87684d3d8fc8a033ae5ee15f6f02441c8a46f1db18630",
+	"0x02f32602d46f59ffb21414594022874d8a227b38b0e6b7b25c8f352600620af0",
+	"0x00fcb066a2aa714262046c18e53aa1aa989405412826ef34b6a0635dfb81d8e7",
+	"0x0110f5c2636bbf563e3998d81d79b9f32708adb38705e90aee38a34f3f67bed4",
+	"0x0249f7068ce49b9e2d8c665c39aaba580cc6e10bd99399d82ba4e1f706b602ae",
+	"0x06ee074105b38ddac2fe0b2420d0fc4c13dde142ee5554ca6d428b73f7336c7d",
+];
+
+pub const MDS_ENTRIES: [[&str; 3]; 3] = [
+	[
+		"0x0e6b335d6e2ea5d9e8aa60fb089854c5f696345ed41da202344dda548ef69a98",
+		"0x10fe9e5afb9c91a34f00f8053c175dbc4f23b9355b9848eacb933d5823437e90",
+		"0x0141eefe518403d73f4869a74a814de3b597c077d279b99006b4b7fed46b6170",
+	],
+	[
+		"0x08dbf6ec0e454274b854a2f23d9335392e3cd820e4388043c519b8c9cbcf05dd",
+		"0x0478c3a3f18b4b8d583e4f8ec9e99195c0e1a6a80cf5bbc890ba313c846cefe0",
+		"0x0b854e32ff6a1295fb4c068667ace282282ec3fac7cf02e1ff09968d124400ea",
+	],
+	[
+		"0x0d48bcdf16d563a9d7d3961a791314e61abe759a53fc192dd305b690de014713",
+		"0x0a089badd1bc7f9c4522bbc4166a8922d3784ade0fd0123e95b454ae001a5607",
+		"0x0659d9aab4980c72174896c2841d56fdde6db06ded7b786d3c8a4a346843f9ed",
+	],
+];
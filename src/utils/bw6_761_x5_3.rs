@@ -0,0 +1,228 @@
+// https://extgit.iaik.tugraz.at/krypto/hadeshash
+
+// Parameter for:
+// exponentiation = 5
+// width = 3
+// full rounds = 8
+// partial rounds = 57
+// prime field =
+// 0x1ae3a4617c510eac63b05c06ca1493b1a22d9f300f5138f1ef3622fba094800170b5d44300000008508c00000000001
+
+// Grain LFSR of generate_parameters_grain.sage, arguments:
+// 1 0 377 3 8 57
+// 0x1ae3a4617c510eac63b05c06ca1493b1a22d9f300f5138f1ef3622fba094800170b5d44300000008508c00000000001
+pub const ROUND_CONSTS: [&str; 195] = [
+	"0x00313ea0d53c29d9042bdfe23fe99fd0a8b37ff814b11932b506883e26dde1cd771748934d0d22c155e206e8982c62d4",
+	"0x0133502e7755ebaf0867ad963588dd9e8d1a13419a40796b58163f36dedd1df090c399ead08e814c6800a99ee55ef329",
+	"0x00b32c36d43b60a116dde4191b5aaaf73b404af396d4250e03ef9a2d9fddacb19d512fb421d0c8dcfac0c7a4a83ef44f",
+	"0x0087713576937bde0276dda18a37ba498d22f27b2fcbd85db617e59ce3a25d0bad17256f2474b1e23a2127dedd70ecac",
+	"0x00b1c6ea02310d39e02cd038e205ff227f53ed7f5d41f33d749e8a93222087e5210cb57f072434b9b1f0577e3099d77b",
+	"0x0195c081c42f5e503ce5e6bed07ce2bb514b3c3087a961aa98ecc924ce150b0dc2542474f08aa8c928a37f1ce42706b4",
+	"0x0129040be9353935560edcce4f062759a4bb703e82fd3297c9bfb648d3b436bfda55059f715d2f8939413b0cf33613f2",
+	"0x01237d18d17cdfbd61ea8e8510377260b9120c35b1c34927665a50df7271f9a656c0e6c26efadcc584b62f37c3f62c6c",
+	"0x01a6a53b4373da4339dd4394af17c62ad52cdf754859cc630f7fa11403618ced6fc603616ce95f7cd10b3e73474b0d6a",
+	"0x00bf480058e22f8ef0f4c5fabbd85d2308954e3f379125487d51bd3e23cadc081ed7f69ac8fb0b364c418641e5beccd8",
+	"0x00389f72c4dda9d593d5a8c09f2e55c4a91c37b0d0936e1da33a2705c5b14d4eabe79e598d79dc27856692846ed1c375",
+	"0x003f69f94199793ec9f5adc4c4bb1df3f4832a4816ff5a1ddb4e36a21dab74c75b2ce4851b05eb4cd0f70295f940a130",
+	"0x015d9e3c3b5a6d5ceefcaa6cee8696a0f2c36bd4068cd2420176ad22427b864a3776373d3410f37f165bfc631aee7b50",
+	"0x01330b1b10c3de88157ae24a2a7159b583687ccd511613fa0fea1781383a4a3efa7b05b0a7fadc1a123a4833ade3bc5f",
+	"0x0192f94d3c6c4bb41e2468e6328fbcbaafa87e903821155884ef96ba975c391ebd116a0993cb80706c4c45a035be2138",
+	"0x009302a3d8bbca7b5ade568e340ec1782b562def745f3888a0c287d22702f21db6aa8fd166d6b32b3e824fd2ef95373d",
+	"0x0078a0b8ab6776a8318115e76643bd28213ec3d6cf4d89b155d32e560e803560525b7f28fa8f406f44a0f9938dca03e0",
+	"0x0016f830e56eb4db8b16d206fc9d0842281bf8fb14a25bf4a16c450c73d8214f43850df673425cfce6fdd92ca865605f",
+	"0x00f3391238e690a5d74ef83837e51d71a2b81799c2b40b36e7afda50ef5f5a6d91bd4e614c44a6902d5b2acba01fee44",
+	"0x0161f52c9046473c616e82bb7c1618e952befd8bac056e268b5358f76d3f3dd5488b210acd6bf830bc4c2ebac150e6ba",
+	"0x0093375968048c69eec8bf47fb0f4914057f0ef808efa2119f9e3f393c8bdbeb46a4ea5f422e5d398a738aa2af49e097",
+	"0x009e2fb8dd5e42b14d532669c5f5abc5d4655ae0d0e0291b9c966141f98cb690e6c129658ce5ba433902c0e2da08e8b0",
+	"0x011f237c2268327f04f4bbde8b7f7961528b686861e24a40ea1676118a64d2312932425a79af0713e0d4f7dd6f64df26",
+	"0x007f922aae6af06a73be45b3cc6e33c5412b271d188465dccac74f240fa426103c087a059f2c8d4f8dbd023309b8a251",
+	"0x005e8ba2c4b4ee47a46aa6ec7586e1585ce6ad2e4a09e8ad77056f3ffa35ff5450236912f2169be89f8256503e6221dd",
+	"0x0109d48b6430c9adf27d6c1f3c15b50f4c675f4c427e039156baf6b26cf3373cfc822717e15c521a7c0d894d6cd639a5",
+	"0x0071b9748384b07f49847466fec4aee7da064d1f73bf975b4f496f98e50319cec90042b64aca3b63adbe8f38b28410b7",
+	"0x0071e11cc06bac9a4a27c096ae1e32a4773c6820a44cd6f5cababccc97c9158afd072fa3603adb4634a9fc935cf99097",
+	"0x00eeed27c1418b2d6ba08bee7550db832543ec061b364ba5adb3ccd49f64db28034e1ed242e485711de33d07c5d730df",
+	"0x005d036b2e0fde55e3f43f3b4222421440fc4a01c1a04bbbde55a16e368dbc1166276bf5fb33d11784c40893566451fe",
+	"0x017da8e1b1b4929c86a904b91c6bbe7b5d586706900861b104622c4d90d9c75077efbede07b802e4e5a4096afff44be8",
+	"0x00f51355d54fabdf5c853c75d6633b310b2dd7ae1676ed2430b1fbaaf3cf3051532c39d264a0e2dd6f5f2ce15fb25d07",
+	"0x00fd1008727d6ce07aa10927f8b3aaaf6ef53f796a8fd9cf94d970f1d3e08d6f9be5ed4f8962500b935c37eb3ccf2681",
+	"0x0144aa2698091ab1e1f415c9fc4a0b780afe7a78a62648f3a78f51cf6c1aed31c4bd1de2fc80bb3ac4027cd49d584c07",
+	"0x012c7810be3977df3236a36e1556eea34bc72a3358f1c9dfe58e405305a10215b7a2bbfd9293a8576d21032e0877b52e",
+	"0x0059a24b816d84a25fb4a1d5fae797d55eed72c0301f3bdfb5883968dfcec17b4060b4feaa88c1e4543dd0c482427c74",
+	"0x01913e0483346d2a4aee4156adb3a7ea5f7ca323157dcbec18d4e09dc099f666785b97fe930b168efea804c3e4c7c319",
+	"0x000a63c76237ed789b050a829dd5d070a85ab3f8f6343150ca706a3ec3709ff9afa74c686f30aeaa01993e1fb895c2e9",
+	"0x000cff675c1ce8cc1a35e3a1e45ce911d8bb777d0d1cb4d96880886be6a3e75b07e6f7371f263f036547fa2425b6a1a3",
+	"0x00681ab7f643b6ac7bf0f4ac33641bf7da69485580d86ecaac3f6c94228a57163d59fe8e5990d4b3b4fe2249b4e48bfe",
+	"0x0139ea999ccf5d3a725b90462e6f58a51c9236c29356626876c5496f6951e944bc1f044a899ecdcc2213f69dfc240661",
+	"0x00239e19632b815ea66f82fe4a82f1388d5430edf2b34953e280891d0fed8079033543fa3ebf20e23498b5d95a00d0a8",
+	"0x007404333b4f627bce449f15fda849e235ff3341e4bb54e102609c2a30f25a66f974371d04a024f7388609e489acc15a",
+	"0x0098c4220e38fdb0edd4506ab28facd8e6908c70e3466575f1098f50f4846d87dd6feaf50ad89c825f357cd011518e5f",
+	"0x009424d122e8f62ed3b1fbafc04667d8216474182cddd240e00a99620fa61eeb9fe9f0d2b71d413ca62901cc1d2e4843",
+	"0x015db4af764266d26b73499d5bbf7acd3cc6425adaea590b330ff0169abc3f915352cee737f8a019a31806f74290dde6",
+	"0x008c0f83e641dc9a23ab74d1ebd893b07aa601bdb78d3ed3f960d46ab7b9f66c990c4189c44459a91158915f333c7b90",
+	"0x004117b7243807d046d6909d015ca8b911f535b8ff28da82bc33c8db70746bf40477b6f7f33f0f882d979282248b9a16",
+	"0x009ddd1f2a9eb16742e410395c591c02b9cb43443637eb8559f708b4bc1cd039dd7e8b5d31c2fd5ee65f275519c46804",
+	"0x006c298c546b5cb1ffad3b1a8388c9ce412606e4c093f866b366b05b54233fa3a12f6a040b92d5a352c5836b6ce47c92",
+	"0x007600e49bf5ad89969ac3812ac134ec1c745c9ede863724ba6c336595ae8c66c313695ae0b405cac2fa64ed6054c809",
+	"0x013f4d276eefa07dd7bf62aba8ff98f84dbe3825b5b8cb9cbd13a6b384edfdb86632c6181ee11384d323f7495c431a55",
+	"0x00cb36e0b81a86334648528578c457c5069f976928b77f1fa59893db1f5673542e6e40dac059f8e83e92b389e8ca6efa",
+	"0x00d77b92123ccaebb786f6b223dab86d1e84b987d79b2f365001baf7a3b40246ab21ed239a2b347eff5a9f7490f9b5a9",
+	"0x012b29839d0f99baa6a65f2046ee4881820b84c77ef1078a5947e35df9088add40845b9287d141fd5a8d1ba19b552391",
+	"0x01442921aac51580f95b6a9b79cc40d572541f8421a4cd870ab62fd710fe70e97426a29de30c4beccee2c9ea4c91e6c2",
+	"0x017dc1af53e62d273e265849376517c4a8a2607ad3b88b8857b24901c26091b550e939dd97406aa75d6e4de3bacffa9c",
+	"0x00bef37527a452e6dba7803d934dd9a675c1387b22317020ad3b3f5694d84256cf8479c4816f8bb08e44eac4f4c03c90",
+	"0x00182ca9c9a3c32bce9c8bce98458d3ab8905f146e69b567ea6f919632e8358c0badf47e36ab173147c555fddd81f94f",
+	"0x0133d60353160dd62fca0875ede67b014d0c359b0ee30736495d9a93a81fde9fbb6f0620529db6f2ac2b269f9b39aa8b",
+	"0x005404e872b1311814f57356b222545c7dc8b05fc8a6e573a300b2aa0dc43264e92a456f8732c64a7f44bef7189ff40b",
+	"0x015634223b0da1d4b9bb7dd2cf6425a3a3350d18b35c2b39159e095cb82a17525f2157e8aafe94c5542e8c9885babc30",
+	"0x009fd7ef9ace20db22c5005d33e9c5a4036f013943cba64b1e9093f5b501943a63c1ab381e34c07f0f99e2b5b5b89872",
+	"0x00654a0adeb95283d3a8c17ffa2327269af460da3ab96f191358cb5fc7b432414f15665bddf449d537772f45114d5ee9",
+	"0x005b25b636636049ba932d213a50a9c9d2f306cd39d8fa9a28fb559d07e9457e7ac287d224c59525114b7b7ad295afee",
+	"0x011650e0d9d37b73646209d91f9a2c6a57255ec734bda64dd8c0b76fd452e0ba21988aa54d2ad6a69b851dc212bdf9bc",
+	"0x01a701f6b6b788c6bd29c80c11bc2a111a51a747c635f51c79c20f77f64a3d1fe5c4acd7859a02c7db06a4f07cf2b65a",
+	"0x001003a2ea520226dcb6811e890c03db3570fe818cf8bd4169a1ec216126edee7c5ba14360d1b49b17cf320fc8bb6bca",
+	"0x00acf2056d7ff8c4fa8c3b893a7333c435573b8a848c6f0018302c0f23df7a489181b54580224603c3fe962e61f9bbc6",
+	"0x00b1838c69fad41130dbb42ee1a41f8023adfcf6d1568f61bd56f4d22ab70c34452f99fbd84de3347f77c569f6e7c05b",
+	"0x00c2b69d699eba05f935a6db126a234c2b93f5fcfa0c7c281d3859d0a737c81afb9b5f0c70c5b61fa52d23a07e3f4c25",
+	"0x00aee7d3ac54f0f6472c1f5cbf6305fd433f5724ad7537f621f162c404d8fc87c4a39a798a3a0088b721b6bc3f1d8d3e",
+	"0x00ef8fa278392f32cdcc1732a6fbfcb5bfed02123135c70dbfbe12d9711514512302f8b7722896b060b0c4f44bd1952b",
+	"0x00aaebb5bd940461551e79f9327b876ad53902d09864e6f8867e9d767e161e09ccb4d3922c951317333ba3fc372749b3",
+	"0x00d5fdde4b9f3f22cda16b3c8d772a54615ed1de54b0d6f56a9113024d8435a715c42b0197781f5d9683310a87fcc746",
+	"0x002dd91a89891bbe443f68ccfda27e652881c4044ec04b520ca57ba26153dcf724c7b01dd75af455cb3e78caa4c0d5b0",
+	"0x00371c467d7d0e260b78b1af7b909f3bd291114ceb27687150df906daad5dbc3ee6c357d1c91ee699760bd73bac55520",
+	"0x00ad61a32ea5a4a39e0db6bcccc20ca2aec864a15cb6998721636e8df8df28ae791d795cbfe80b203150143f8b580d14",
+	"0x0189a91c7cce4e00c27abaf3c8902ea38d92b5faf3c26346b98ce0b186ce685a7c27c89561215f2523d9b6e6901e41b9",
+	"0x00bba4d0bf75e31e1fb4c36b3e6a7528354730260d168f7b8d4cbbbe5939bb3fdd3e7964b78ca1bd8a7db985d6e804e3",
+	"0x004b55a48d2fc9b7f28c46074726f37f5676a7e2e45086281ebdd5a23032af8851d0326b5dec6a0a20975fc8abeaa686",
+	"0x01026f7bbf4bb647895601147010f5eaca2b42208715f06fcb61330f6be89a8a29ac37eb5af3c873ede230e31621cd3e",
+	"0x004c489a1e51752fd2c8c4b0aef9cc3fcc0daf8be358d67985da5bfa1cf177506ef1d97c536313f3920af16d7d821c23",
+	"0x0134726342a9f97fcf7dcecd230aba1fc9735b8b521598039d0c0053ce144996880fe1983654a32edafed129974166ba",
+	"0x0174986808fabd0b834398138e4646952818862ab6beea866d93632cffcaf2471e61db6c6df9bd50eab14c121286ad9c",
+	"0x0031ec9aa7b8401826c219397208e32fe4c673bbec1cdbe8c7d92ea2dc3803ce227838868a85eb2289216453ac08c325",
+	"0x00c1ac79b746d602a09bc20e2900c7d41101d7a7ca33203809a8a72b8ba0622497af216b719f3866d218779fe54b193c",
+	"0x01a090fd28eb659dc6a1c83cfca3ed2e2e41889d786f59ef59a5a759b80e510793afc99d5454e2c0da2d219c5b92fa89",
+	"0x00a7b0cae1b1c1f75311949617026cf60494bef9735aa446175b197e763e4500f5ff7ff378c302f1eaabf43f9783b297",
+	"0x01a0c6e27a5d73ff09af2f695d296e54306111082b7a11b4fcdef3bf2087ba98df4de7f791fb02a978e93d0002726d5b",
+	"0x016d302ac84a45f8e7b96c8108dc87855b5aa1a504306bff532335b17033d46b553a857ea49794e2f2250d87b391e598",
+	"0x01378a7a5f142a563c622a91e2d947940cf631f5977fea3aedcebde7991974cbb2cc02833004330064cfc031b37d9629",
+	"0x018982c5e78b01881e42f11d612121e3f348f2e7bc469e36d6a5a7b4fdfd84cfc5f3d969d109ace81af4f6e9d94dfd1f",
+	"0x00f1975fdc606946aa62d6b0d59434eb8638a37c6c266e9239fdcd2216df952a087e2bb9864310874a914e47b5dde39f",
+	"0x00bc278d5b84b38232db38636a5725d6d0e1909a536964457fdae19132b6b37dd9ac46f7804213d48bcc84e506484e4e",
+	"0x000b1de1c6fc273d2b95b6715fcda650f8d8b1d1c6610358f15a02229a97ad55261d9253b952e1f35b0655300949bd33",
+	"0x00df9bdb648d8c4b146f0bb417ef10cb3470f15c3b9d9b8b08fd8eefd95c686ea0ba73cc101293922de898b932100d48",
+	"0x0143d136102e9d66fad3e7233eb834d5d4a729f2230098b50a439a6fb7cdab9cb4500632a4417e218e0cc482ef62b50e",
+	"0x0065973a499db721ff0b0c2651ba2ef9a22595facb47ab4c3120168ddab5665ab743272f697af62235f77f6acc1a7958",
+	"0x00ccfe5e651ff0dab8d7fd085b528bb379c0b5cee25d02f25284a9b9a98cd9f4d26e793ec7ff9b5b04fdf825d3a44d71",
+	"0x016a164d55b5eb1d522b33cec53be65438908f10e64a0ee074941859b8b246add735f3a522edc72bb997dc20638845bc",
+	"0x001b08cd11f2ad2bd85e19d329a197698db288c9586ab5480b478d65180456cf05feefc34fae161204fa3170f3a1429d",
+	"0x00795168dfaf1148a6b4f4a17af67bcc51b186dac3b2a969cada0765fb284cf2f49e68f1954e14a690f5e98a98269262",
+	"0x0103161f2afe91a4aa5bb23045edda933e0238aaaf59c555940d418e838330e3d8413afa7bda3bd3f31004474de80f7a",
+	"0x00408c9e768dc3a11ce1933f3f2c041cb9a2b13ce4a9353ed0745b80a51c05bd1e3356bd4458d4f2a26859aeb1de73e3",
+	"0x0194c5f0dd5cf660186b5fa2e4b4b6360ef1af84423c200e9546f24d7248e210913c7ba3acc990a73d8a09ef719d8130",
+	"0x014cd2726221fb7526324c3ff5d0a8040f6af21733356a5ddd8f4a3d0045b817d6440c62643282d6527e1999cfe0e0d9",
+	"0x00893cda7604efe31f5246d3ce2a1e609765f6fd66f4dcac7701f5b0df46e8bee4312e08d330601e2b5881bd2659368d",
+	"0x00fbe0e33b396ad06beb09dce63a4d87cdf268565a046e4507ff1af14320fa003f95d8ae03f1b59f4e4145a5cca7592c",
+	"0x00596ab0a150b95189661844cf8625059da96863c1e53b4aa3141879a1c449b890e11000f7fef54668293e7150a21f97",
+	"0x0045e4291fd50848eb61e9e703a3f52df28e05b4cf934d3e185ab6630ab4cf9343285daaf14d011439d08446991a20ca",
+	"0x00e1d42719d1aed8eda171562c252345d294d22e294cea18969d73bc772a46289915bd9ad217f4e71aae9cb1c0c4a7a9",
+	"0x0017fabf314f9f1d664e168e7a9aba761651f770f514c29b03f7d65e6d799f2925a16ad9240b404ea6979b62270cff90",
+	"0x004ca9f7d486a2e78cfa9d3afebbb5f7ace9c510e9610ba3ee00268eabb5cddce7b9d64139bc0487059c652c7f42e996",
+	"0x00fe744619411c381ff06f1868b5ecf7de5f9aa5dd06005bef884d0e79b535186107515437cf8f4dabf96273307be8e5",
+	"0x0157a17a5c928fffd72082cc5222e320f2dcd25f7bf15781b3b30090497c2f4a33a6bc7d81503d1500ab890e917a7f24",
+	"0x019f20361c88ca954bc791ac1545e2b00e370a0666e62983be52b78c32db379fd8a8e4b93df9ebbacb8f0374d8c20cea",
+	"0x00b1161e3b1d2a5008057ca7ba4e7a1731f43a28beaf40733233c58fa3fc22c69915c2caba597d0b983702ff2225b2eb",
+	"0x00bf4620ab2ffd9b28daf84e422e5d0b639932aa8f04402d43d2240c95fb3cd7cbfb2543e807ffb683cbca3e3091887f",
+	"0x005497ee812ecfbce1c28f668914596d88e6e9c1b05eb358d18f32310e44fca2b4ef67ecc55072171644534a3c1aedfa",
+	"0x0077605d6322803349b5d2a54254720a84637197778d861e8b04ae815a59c8dd443085ebf1c6f7a34d3f560a625445de",
+	"0x01309ce055dfe2e86f0743a19a39a218d986444df91cefd7988386c8be659fb486e6cedd23489561f856d54f5eb5f7e8",
+	"0x00847721c74d0f2a3632d86ff9711ddf0af1ef42322466719b95afb08b1213e1fa3277713b5570790651c7c7c942d685",
+	"0x00af797beee40971b2be982936043d08bb676a35ad945b41c9f243a0d77814106f51a51e4c01660b193fab1afac079b2",
+	"0x008dbbf169999e19cc11ff7bf9497cab6620afd1b8507db8302d1c85079c60f583e0f1bda86a4ed9d44513c395bf7965",
+	"0x0034f74d62824a7820942fb1a9d6b4a8b3c8ee934433928209741f1df8f052c2d2f92f9faaf4320677640ee8ffddf2f5",
+	"0x017b6e9603988d0e730346dbcc522ef06e33895af59a5e3ed9cd54ea594e3149f9d77f60647d2610bdecfca45e5dc604",
+	"0x010acb5511bb6603af4512392c9160531d161c1d0793ddd3953a6d6be9be8116783a3e470cb9ea919bd46e97f202cc98",
+	"0x016260e583269b7287d7bdb36633b46b42ea7779260c71e4cbf493a97072d9bfc767d3f0379524cbe0ffca233b94afd6",
+	"0x0136b3332c786f1f6f0352af3ae0e36a80714b678f817a5c73cbe80bc3c6467b112fdf86415cadcf0d3a892ca85e720f",
+	"0x00208a532630634e4c47e009385ed93fc3f8a766fd919c189d6898e1a33add76e10d4e8ecb94bd88b9bd758be4bd9e47",
+	"0x0126ab28bd9ec086d6f8ce91b17c4c0b06a6d4e08472f11bbc08cbf06acf1ca206b2c09e6d0c67097b0744460f1c1779",
+	"0x0115ab996d2c3772de9eae4aaf73129d797f31bb8f705992e390d80a2dd6d8a3f93327f98bb2861aaf42a273e01c835c",
+	"0x00dacb07cecdb82be6bdb9af0b7e9fd1469aefba5f34115124642ed2ec899ec6d1f1a3be351b3e00b3e59d7dae10eef3",
+	"0x012cd953a17d2cd7ed966abc78ceda8d1642cf9bc0270ade58b30128afc4c2b9cde3e3229b59a91a47c8a8f87222a6a6",
+	"0x0182134ba7a6cec395cddd566492fe8185c0a2db1224fdb6bbee0572b14e1708baa1e30f3e0730d8ca1685be10f98bf9",
+	"0x019586246b85012ce311ef143547132adbde45baec05f193f5e764e4b7eed142da23db13cbbbb9486e031d2cd87bc60e",
+	"0x00d6a7b6d920829108fec18f0a6a912e0eacff46831edaa42fdf58f670ef87274636f7122ba665dc703bebfc0c12d48e",
+	"0x00d064f50c606df3d8d3c8142ea3aafe4fb96c4be6f1ea661afc03b5e5ccb550148ce0c9bb39c0b0bb88b5311d5691f8",
+	"0x011b16e8235df9b94ce7c25120774700d5f72d404893d608d470d7f1807b9c1794676e2e060a6c1478db8aaee852cb9d",
+	"0x014a9e2f319a8b375c736971cabc0f0b90560aa2533b10818f88d6ea250562ebb8c1fb9d333eed91968862b9f2d0ab8e",
+	"0x0058973b8cbc9680e268e7583369bdca02e304fd50b6041a025621d15b3e256423efaf8e541e541ef4bee6d2e379256e",
+	"0x006cc8bd029d33610d67b850fbddb2c6a2aa2047f16e9ec3944366dea6281885ed421ff4d58759bb6e5e75c000ca8800",
+	"0x017e492ac5edb5e8dc6e7366b648de8bfff69f52db1bb58e6f9a9273bb38823b81181ca07ebac9635327112b6c6b8204",
+	"0x00aa0ab919574e63ac5fd93b16dfc49c6c3c9a843f4069c4945234e26f664a232e55260540acefedb5fbeec7570caf65",
+	"0x01697ced1cabd4a915ef50c2c1a4198f23be0ff7ec48322c420309deebd4073ebe2a947eabab16f592b435015dec4a6b",
+	"0x00faa03cfb027a30818aeb066914b26084dd88bec0811638f862b71c37c3eac2a03e0f3f9a99c3ac0f4542929bbb7ab2",
+	"0x0050f7ee7fa53eab7e9298bcd343ffe0ce82aae2aa4f87c57728a34ea2463b2abbf3988c43810e7c3b5f2949b68a5f86",
+	"0x00383b1dad188503675aaa4d1fb0142bf35215f304cd5c285ffc2c907a9b28b990fce52c326beb233d1975a55ac37043",
+	"0x0161fcd00915007296a129704275cd695611fa2b057b1d2069ac6c275c1cd8565096327c50a7df4ab49c7776b1d4ff5f",
+	"0x012a8c7571eb7c8aeebb32908146f3e1cea21744476cc06ab4f7bf3ea0ad274e8986009597c0865b552f5cf04273dd43",
+	"0x0146ac3474242b48af08b9187168f1b66b532084c93fa44a6a0667ac342fd19e5a8b328c78be1c951ea90ea27d0114d9",
+	"0x00cab155a747fb760773640622d84427dd50de288ccb0ff1af6f04443242b11b0e969b3f4824b45a9babbe21bbe8c2d8",
+	"0x01355359c1aadfd16e99fece89e6986fb485aa60d504ad9245201007f25411a7c2fed3a6762d29a8845258ff389ed56f",
+	"0x01031fb7057b1f1c177d5109f13f5efc1cc96f8a79df318b18b891208f85f319daaab48cebbd72df309b9a68aa984288",
+	"0x0085302e3bf28864b9c4668fdde8830d9e4b5c200f93f047156e9a56df5a2c13774bf3e1533139a4911e2c919b27a032",
+	"0x005efa5a8060896c6ef4513254af8589a3a27fb11e80d69f38f04623b872a19ccdeb74faace79cdbc0ea137a258eb242",
+	"0x0144c907ea2bd040a3b186a5d06f52b289d4f79b3910e39823d32cdc6d61318014f0f95896fd15a49cb8205d4fdef6eb",
+	"0x001f5037ea842c250266c3ce069c172b408add43e5131c996bd64da69c330d47b5caa677cbd71d1a828a4ba1974015e3",
+	"0x015479c10f95e8d722b68898db021779ff85ecc90a01fc3ee2c3d68d6ca673a6b553ff8a1c7d3a5a935a911cd310e003",
+	"0x004c2a2b5e6acc8944fb17eda6341dc5e015925bea5fb8d4777e944195b51c5e069bd12d20a1da1f5ea669db00a305d0",
+	"0x0098b362ad36edb6e6c27757e072cf259ac963691cb4179412b1b836393fa4d45e9850502f7affc8254049f6edba7c3b",
+	"0x004b44d43c14da4fef02a195c241af6f0f9f223f13a7621ceefe34767dd8209aeefd8d4368c375303566b1832dc1f9f0",
+	"0x001819151dcc8229b746c99d02162c327bde469c4a4161cd58cdd98f7fe5724bf12f84512fe77356d50f32f6699ee295",
+	"0x00dd142b279d7c232d4eb21d0cbe980fc362578257d98c18ac62526dd6f25e78e32bc9d5cd9794166b3a8fedbdb09d42",
+	"0x0172a5add5153b7a91ff8d1a79000134f2651a57c5be007a3518e95df9d0dd21147e63cbedc7d280a9507cc5a8a24dd4",
+	"0x00d9d7900cdfd6f6b03bf107d63581223323561d0914abfea1f8baac2c59a3e5e5fe7f690e86c6d764060034f7e01f33",
+	"0x002adffaab96b3dc57d5b8a23347aed14020a4db6d78c59713f7723a62d9986c8342893a4588d8726bbafa6e98d3758d",
+	"0x00485aa001d7199d8414e55c351e1013197a25ea62bf43ba3124cc9dae825bf7bd463164a7387a331c924c94468ad12a",
+	"0x002bf702de4a4ef72283997da3e7075055140e083d683340c4c4a80a2c316892688d52891583cddf63429e97228b4a6d",
+	"0x003ae9cdba49dadcde5124371703e71fbdd0d52a98990304006f7623f9c4f2e5395b3ad2de6a4b894966ac303b693fdc",
+	"0x00cdd791aa568ccacb0cfdc788fbb53f1cbce2cf2315d38c19673a9eaea2e993c7a263051fe6d1ab735e747653ebc5f9",
+	"0x017cdc7f25d1e9f4ec401b9a60862736c8ede33010c59447b07222330ea7c041e290bb7e2679f2479e3c3241138b849b",
+	"0x01192fb26458ddf7b34c76b28861be1d7b488298fdb3033fd1986b9ac012eabc1ef38f2dd66c7d45ec80fc84e951e1a0",
+	"0x01498c5a05758ecc0c28a6df8a706323be0f67cbc22030e7445b83bd4d5877ad6c2660ead45f5c55f88fc87b6fa91af2",
+	"0x0180e159720a43ea7aecc07d15336a5d99a471b790cd93635a6fa3b80fdfe7e927cf1f81d990f5ef6fe20a5dde113b04",
+	"0x01093a4e2cd570ea656b6e8c7959079844c3dbfd255d183143c315be78b84640003591c7960c01b444f76363044236e6",
+	"0x00697dfb1cc797bb1e237e726160ba931ff1c027f5717670223a1f79d8673e01c27f247e399d10df800cb26b9acf11b9",
+	"0x0135c3fb7849206488b20a96ab98491793f000a36f4dcb678da0137a5f47d165807cb862de4d0d0aefc2e6d2dd5f5745",
+	"0x018c08ef7e5b0583d9fb0fd3d9452fb6975b0deec8a3b0961c4310059c971a15fcd7d96f0e55cde1e7aebe60431f0c98",
+	"0x006a9fce7d77dead80b4a469694d886449b742c0ae9d32ae83ab3a7d03a3c509968748965485820544afd92044f54869",
+	"0x017b59a07e1db432d3302149f5149ae35e16c4b5638d6c7f437a79e2bef2202600ecd8ced17e51d8da07dfe3629111e9",
+	"0x0089f45da1ed5d6bbfe5d0633bd21f0771fe2ba69ff04c0c70fdfef06513d55f26cbcbeef5cb6a3a7e0d19882df4abc1",
+	"0x01787a44dc4933df4bf7148177215992f1452ac76a6029d0fc18a723dcd46365e76433132a34fd7fa5dfb8e2874a2d8f",
+	"0x00b4bc691e8a6f0337eafe11cd2c199897b8a7effc77bacf2250b5d606d4784671ced1db4a6e708ca98e43e1777e852a",
+	"0x00e267a8806f9e561ddf7d7f127ef55c70f204c65734643d2f7304f5cc7d93a4d6ced042840f74f0944b443f58e003ca",
+	"0x00ee2c40c7d8caf229ab94d92bfbef9bb27075f004216b69881a7a432bc1b8ed26052fab329723df3336489576537233",
+	"0x001281c1ee518378a573a6244d79c2e0ec5117647045ce94d46945d6eefe30d6eb937b41ac4023635608dd1dff44f173",
+	"0x00282113f1325923850eafc930fb9e6c18c31591bc26dcda1d5faafac03b2fcad8863e1192ceb707863c77a88bd1edce",
+	"0x015ecc951ca8cd43049b85e5f0bc02970cfd20cbcd6683ceddf4c6460d2e0c2fabc592a5c8b8133c16b9e1bed972779b",
+	"0x01310f49f354aa3b1fb5040370fd96001b6fa762ceca931dc74fc91d3f0756fd2ca30ec64cfa8b7078caa223afbea336",
+	"0x0061e9150ee590dccea8dd702933cd97274911a7c2a26df6fd6eccf63de91fc48a3b980c8aec4fe12bc4fd624fd7fedf",
+	"0x000b4da1fa7f2c0775a29a2f62b08983373a0f54f9af298cc68c1babaa0e2ce223c47d02d22df7e5348eae9d1ba6fe58",
+	"0x0130488bcd31fa12cadb14021efe33c65691c40310033b0cf24e3a2261a6c2d5d9bb1e4c163ea4b4146d60c3c9896fce",
+	"0x004015ffd81fc9ed145e659268b6d171b0489c1e1cb1068ea55f43139abb287fa45558bd977efcb16d8d5cc7307ffee9",
+];
+
+pub const MDS_ENTRIES: [[&str; 3]; 3] = [
+	[
+		"0x0057e69ae51deab57ff1c01b74fc7c8775cbec2c2a687fe4411c74fb1014b320f5c48ab159bcfb9f982bce371900442c",
+		"0x002063497f5ca40055dab42c0158d65b854408c78551ce8525dc8dbb11b3b8a41c58af019319d301fa653ddabec104d4",
+		"0x008dbe6a0071d17e4cf2a9687098571a7f3322953bbab4c0b016b2726938e8abbf53c15e97bafe6bd4bb9656286f7ed3",
+	],
+	[
+		"0x0044fc213f336f6029a6c599e18e4e5dbe5872edc8439d3e60595ae689190efb19c8e2cb55bce45a5362ce399c7215a8",
+		"0x0159135531e514e3e1d4ef156eaf95182cd544a704f7c9d0cb7bf984ab42d076b5bf03b89d320fbb8979a6829a5c3dc7",
+		"0x017ed06489abcb3a98602cf7823f4a0021c39e5790b664c3abaac838d84ea88793692fdddc281e4b8d73e62c32e1455f",
+	],
+	[
+		"0x018fffc820745a781a4c9af10a4b040a6f2c4c9544f8b61bceaeb6582132b498d2ccdbfcbaa226d7424b51508e7125a9",
+		"0x0073cc2b887a6981dd25fd77eccbb7fcb7f25ac591aeebe3ce84632d4a89ef09001eb3a60fe91d547e86afbeb4b59a99",
+		"0x013deca3d5dcf747fe583fe3db14c0ed657c67641882cd10587c5e943731bce4cf2f640a1e0afd3949a6120379d3e8e6",
+	],
+];
@@ -2,6 +2,8 @@
 pub mod bn254_x17_3;
 #[cfg(feature = "poseidon_bn254_x17_5")]
 pub mod bn254_x17_5;
+#[cfg(feature = "poseidon_bn254_x5_2")]
+pub mod bn254_x5_2;
 #[cfg(feature = "poseidon_bn254_x5_3")]
 pub mod bn254_x5_3;
 #[cfg(feature = "poseidon_bn254_x5_3")]
@@ -24,64 +26,238 @@ pub mod bls381_x5_3;
 #[cfg(feature = "poseidon_bls381_x5_5")]
 pub mod bls381_x5_5;
 
+#[cfg(feature = "poseidon_ed_on_bn254_x5_3")]
+pub mod ed_on_bn254_x5_3;
+
+#[cfg(feature = "poseidon_bls377_x17_3")]
+pub mod bls377_x17_3;
+
+#[cfg(feature = "poseidon_bw6_761_x5_3")]
+pub mod bw6_761_x5_3;
+
 #[cfg(feature = "mimc_220_ed_on_bn254")]
 pub mod mimc;
 
 use crate::Vec;
 use ark_crypto_primitives::Error;
-use ark_ff::{fields::PrimeField, BigInteger};
+use ark_ff::{fields::PrimeField, BigInteger, FpParameters};
 use ark_r1cs_std::{fields::fp::FpVar, prelude::*, uint8::UInt8};
 use ark_relations::r1cs::SynthesisError;
+use ark_std::error::Error as ArkError;
+
+/// Size of the byte encoding of a field element passed across FFI
+/// boundaries, such as the WASM bindings
+pub const FIELD_BYTES_SIZE: usize = 32;
+
+#[derive(Debug)]
+pub enum FieldBytesError {
+	/// Bytes encoding a value that isn't below the field modulus
+	NonCanonical,
+	/// Field element that doesn't fit in `FIELD_BYTES_SIZE` bytes
+	TooLarge,
+	/// Chunk size that is zero or wider than a field element
+	InvalidChunkSize(usize),
+}
+
+impl core::fmt::Display for FieldBytesError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		use FieldBytesError::*;
+		let msg = match self {
+			NonCanonical => format!("bytes are not a canonical field element"),
+			TooLarge => format!("field element doesn't fit in {} bytes", FIELD_BYTES_SIZE),
+			InvalidChunkSize(size) => format!("invalid chunk size of {} bytes", size),
+		};
+		write!(f, "{}", msg)
+	}
+}
 
+impl ArkError for FieldBytesError {}
+
+/// Byte order of each field element sized chunk when converting between
+/// bytes and field elements
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endianness {
+	Little,
+	Big,
+}
+
+impl Default for Endianness {
+	fn default() -> Self {
+		Endianness::Little
+	}
+}
+
+/// Number of whole bytes fitting in the capacity of `F`, so that a chunk of
+/// this size is always smaller than the modulus. One less than the
+/// `full_chunk_size` for the supported curves.
+pub fn safe_chunk_size<F: PrimeField>() -> usize {
+	F::Params::CAPACITY as usize / 8
+}
+
+/// Size in bytes of the serialized representation of `F`, as written by
+/// `from_field_elements`
+pub fn full_chunk_size<F: PrimeField>() -> usize {
+	F::BigInt::NUM_LIMBS * 8
+}
+
+/// Splits `bytes` into chunks of `safe_chunk_size` bytes (31 for the
+/// supported curves) and reads each chunk as a little-endian field element.
+/// A final partial chunk is read as a shorter number. Any input is accepted
+/// and every chunk is read without reduction.
 pub fn to_field_elements<F: PrimeField>(bytes: &[u8]) -> Result<Vec<F>, Error> {
-	let max_size_bytes = F::BigInt::NUM_LIMBS * 8;
-
-	// Pad the input with zeros
-	let padding_len = (max_size_bytes - (bytes.len() % max_size_bytes)) % max_size_bytes;
-	let padded_input: Vec<u8> = bytes
-		.iter()
-		.cloned()
-		.chain(core::iter::repeat(0u8).take(padding_len))
-		.collect();
+	to_field_elements_with(bytes, Endianness::Little)
+}
+
+/// Like `to_field_elements`, reading each chunk with the given byte order.
+/// A final partial chunk is zero-padded on its most significant side, at
+/// its end in little-endian and at its start in big-endian, so it reads as
+/// `F::from_le_bytes_mod_order` or `F::from_be_bytes_mod_order` would.
+pub fn to_field_elements_with<F: PrimeField>(
+	bytes: &[u8],
+	endianness: Endianness,
+) -> Result<Vec<F>, Error> {
+	to_field_elements_chunked(bytes, safe_chunk_size::<F>(), endianness)
+}
 
-	let res = padded_input
-		.chunks(max_size_bytes)
-		.map(|chunk| F::read(chunk))
+/// Like `to_field_elements_with`, with chunks of `chunk_size` bytes, which
+/// can't exceed the `full_chunk_size`. Full chunks decode the output of
+/// `from_field_elements_with`, and fail on encodings that are not smaller
+/// than the modulus.
+pub fn to_field_elements_chunked<F: PrimeField>(
+	bytes: &[u8],
+	chunk_size: usize,
+	endianness: Endianness,
+) -> Result<Vec<F>, Error> {
+	let max_size_bytes = full_chunk_size::<F>();
+	if chunk_size == 0 || chunk_size > max_size_bytes {
+		return Err(FieldBytesError::InvalidChunkSize(chunk_size).into());
+	}
+
+	let res = bytes
+		.chunks(chunk_size)
+		.map(|chunk| {
+			let mut repr: Vec<u8> = match endianness {
+				Endianness::Little => chunk.to_vec(),
+				Endianness::Big => chunk.iter().rev().cloned().collect(),
+			};
+			// Short chunks, including a partial last chunk, leave the most
+			// significant bytes empty
+			repr.resize(max_size_bytes, 0);
+			F::read(&repr[..])
+		})
 		.collect::<Result<Vec<_>, _>>()?;
 
 	Ok(res)
 }
 
+/// In-circuit counterpart of `to_field_elements`, applying the same padding
+/// rule so that native and gadget digests agree for any input length.
 pub fn to_field_var_elements<F: PrimeField>(
 	bytes: &[UInt8<F>],
 ) -> Result<Vec<FpVar<F>>, SynthesisError> {
-	let max_size = F::BigInt::NUM_LIMBS * 8;
-
-	// Pad the input with zeros
-	let padding_len = (max_size - (bytes.len() % max_size)) % max_size;
-	let padded_input: Vec<UInt8<F>> = bytes
-		.iter()
-		.cloned()
-		.chain(core::iter::repeat(UInt8::constant(0u8)).take(padding_len))
-		.collect();
+	to_field_var_elements_with(bytes, Endianness::Little)
+}
 
-	let res = padded_input
-		.chunks(max_size)
-		.map(|chunk| Boolean::le_bits_to_fp_var(chunk.to_bits_le()?.as_slice()))
+/// In-circuit counterpart of `to_field_elements_with`
+pub fn to_field_var_elements_with<F: PrimeField>(
+	bytes: &[UInt8<F>],
+	endianness: Endianness,
+) -> Result<Vec<FpVar<F>>, SynthesisError> {
+	to_field_var_elements_chunked(bytes, safe_chunk_size::<F>(), endianness)
+}
+
+/// In-circuit counterpart of `to_field_elements_chunked`. Full chunks are
+/// reduced modulo the field instead of failing, so they only agree with the
+/// native conversion on canonical encodings.
+pub fn to_field_var_elements_chunked<F: PrimeField>(
+	bytes: &[UInt8<F>],
+	chunk_size: usize,
+	endianness: Endianness,
+) -> Result<Vec<FpVar<F>>, SynthesisError> {
+	if chunk_size == 0 || chunk_size > full_chunk_size::<F>() {
+		return Err(SynthesisError::Unsatisfiable);
+	}
+
+	// A partial last chunk has fewer bits, leaving its most significant ones
+	// zero like the native conversion
+	let res = bytes
+		.chunks(chunk_size)
+		.map(|chunk| {
+			let bits = match endianness {
+				Endianness::Little => chunk.to_bits_le()?,
+				Endianness::Big => chunk.iter().rev().cloned().collect::<Vec<_>>().to_bits_le()?,
+			};
+			Boolean::le_bits_to_fp_var(bits.as_slice())
+		})
 		.collect::<Result<Vec<_>, SynthesisError>>()?;
 
 	Ok(res)
 }
 
 pub fn from_field_elements<F: PrimeField>(elts: &[F]) -> Result<Vec<u8>, Error> {
+	from_field_elements_with(elts, Endianness::Little)
+}
+
+/// Writes each element in `full_chunk_size` bytes with the given byte order,
+/// inverting `to_field_elements_chunked` with full chunks
+pub fn from_field_elements_with<F: PrimeField>(
+	elts: &[F],
+	endianness: Endianness,
+) -> Result<Vec<u8>, Error> {
 	let res = elts.iter().fold(vec![], |mut acc, prev| {
-		acc.extend_from_slice(&prev.into_repr().to_bytes_le());
+		let repr = prev.into_repr();
+		match endianness {
+			Endianness::Little => acc.extend_from_slice(&repr.to_bytes_le()),
+			Endianness::Big => acc.extend_from_slice(&repr.to_bytes_be()),
+		}
 		acc
 	});
 
 	Ok(res)
 }
 
+/// Writes `x` as `FIELD_BYTES_SIZE` little-endian bytes. Only fails on
+/// fields wider than 256 bits, for values that don't fit.
+pub fn fr_to_bytes_le<F: PrimeField>(x: &F) -> Result<[u8; FIELD_BYTES_SIZE], FieldBytesError> {
+	let repr = x.into_repr().to_bytes_le();
+	if repr.iter().skip(FIELD_BYTES_SIZE).any(|b| *b != 0) {
+		return Err(FieldBytesError::TooLarge);
+	}
+
+	let mut bytes = [0u8; FIELD_BYTES_SIZE];
+	let len = repr.len().min(FIELD_BYTES_SIZE);
+	bytes[..len].copy_from_slice(&repr[..len]);
+	Ok(bytes)
+}
+
+/// Like `fr_to_bytes_le`, writing the bytes in big-endian order
+pub fn fr_to_bytes_be<F: PrimeField>(x: &F) -> Result<[u8; FIELD_BYTES_SIZE], FieldBytesError> {
+	let mut bytes = fr_to_bytes_le(x)?;
+	bytes.reverse();
+	Ok(bytes)
+}
+
+/// Reads little-endian bytes as a field element, failing with
+/// `FieldBytesError::NonCanonical` unless they encode a value below the
+/// modulus, instead of reducing it. Any number of bytes is accepted, such as
+/// the `FIELD_BYTES_SIZE` of `fr_to_bytes_le` or a shorter constant.
+pub fn fr_from_bytes_le<F: PrimeField>(bytes: &[u8]) -> Result<F, FieldBytesError> {
+	let repr_size = F::BigInt::NUM_LIMBS * 8;
+	if bytes.iter().skip(repr_size).any(|b| *b != 0) {
+		return Err(FieldBytesError::NonCanonical);
+	}
+
+	let mut repr = bytes.to_vec();
+	repr.resize(repr_size, 0);
+	F::read(&repr[..]).map_err(|_| FieldBytesError::NonCanonical)
+}
+
+/// Like `fr_from_bytes_le`, reading the bytes in big-endian order
+pub fn fr_from_bytes_be<F: PrimeField>(bytes: &[u8]) -> Result<F, FieldBytesError> {
+	let le: Vec<u8> = bytes.iter().rev().cloned().collect();
+	fr_from_bytes_le(&le)
+}
+
 pub fn decode_hex(s: &str) -> Vec<u8> {
 	let s = &s[2..];
 	let vec: Vec<u8> = (0..s.len())
@@ -102,7 +278,7 @@ pub fn get_bytes_array_from_hex(hex_str: &str) -> [u8; 32] {
 pub fn parse_vec<F: PrimeField>(arr: Vec<&str>) -> Vec<F> {
 	let mut res = Vec::new();
 	for r in arr.iter() {
-		let c = F::from_be_bytes_mod_order(&get_bytes_array_from_hex(r));
+		let c = F::from_be_bytes_mod_order(&decode_hex(r));
 		res.push(c);
 	}
 	res
@@ -114,7 +290,7 @@ pub fn parse_matrix<F: PrimeField>(mds_entries: Vec<Vec<&str>>) -> Vec<Vec<F>> {
 	for i in 0..width {
 		for j in 0..width {
 			// TODO: Remove unwrap, handle error
-			mds[i][j] = F::from_be_bytes_mod_order(&get_bytes_array_from_hex(mds_entries[i][j]));
+			mds[i][j] = F::from_be_bytes_mod_order(&decode_hex(mds_entries[i][j]));
 		}
 	}
 	mds
@@ -135,6 +311,15 @@ pub fn get_rounds_poseidon_bn254_x5_5<F: PrimeField>() -> Vec<F> {
 	parse_vec(bn254_x5_5::ROUND_CONSTS.to_vec())
 }
 
+/// Round keys of the width 2 set, with 8 full and 56 partial rounds, for
+/// hashing a single element next to a capacity element. Filling both
+/// elements of the state makes an invertible compression, so the set must
+/// not be used as a two-to-one hash.
+#[cfg(feature = "poseidon_bn254_x5_2")]
+pub fn get_rounds_poseidon_bn254_x5_2<F: PrimeField>() -> Vec<F> {
+	parse_vec(bn254_x5_2::ROUND_CONSTS.to_vec())
+}
+
 #[cfg(feature = "poseidon_bn254_x5_3")]
 pub fn get_rounds_poseidon_bn254_x5_3<F: PrimeField>() -> Vec<F> {
 	parse_vec(bn254_x5_3::ROUND_CONSTS.to_vec())
@@ -150,6 +335,16 @@ pub fn get_mds_poseidon_bn254_x5_5<F: PrimeField>() -> Vec<Vec<F>> {
 	)
 }
 
+#[cfg(feature = "poseidon_bn254_x5_2")]
+pub fn get_mds_poseidon_bn254_x5_2<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(
+		bn254_x5_2::MDS_ENTRIES
+			.iter()
+			.map(|x| x.to_vec())
+			.collect::<Vec<_>>(),
+	)
+}
+
 #[cfg(feature = "poseidon_bn254_x5_3")]
 pub fn get_mds_poseidon_bn254_x5_3<F: PrimeField>() -> Vec<Vec<F>> {
 	parse_matrix(
@@ -250,7 +445,226 @@ pub fn get_mds_poseidon_bls381_x17_3<F: PrimeField>() -> Vec<Vec<F>> {
 	)
 }
 
+#[cfg(feature = "poseidon_ed_on_bn254_x5_3")]
+pub fn get_rounds_poseidon_ed_on_bn254_x5_3<F: PrimeField>() -> Vec<F> {
+	parse_vec(ed_on_bn254_x5_3::ROUND_CONSTS.to_vec())
+}
+
+#[cfg(feature = "poseidon_ed_on_bn254_x5_3")]
+pub fn get_mds_poseidon_ed_on_bn254_x5_3<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(
+		ed_on_bn254_x5_3::MDS_ENTRIES
+			.iter()
+			.map(|x| x.to_vec())
+			.collect::<Vec<_>>(),
+	)
+}
+
+#[cfg(feature = "poseidon_bls377_x17_3")]
+pub fn get_rounds_poseidon_bls377_x17_3<F: PrimeField>() -> Vec<F> {
+	parse_vec(bls377_x17_3::ROUND_CONSTS.to_vec())
+}
+
+#[cfg(feature = "poseidon_bls377_x17_3")]
+pub fn get_mds_poseidon_bls377_x17_3<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(
+		bls377_x17_3::MDS_ENTRIES
+			.iter()
+			.map(|x| x.to_vec())
+			.collect::<Vec<_>>(),
+	)
+}
+
+#[cfg(feature = "poseidon_bw6_761_x5_3")]
+pub fn get_rounds_poseidon_bw6_761_x5_3<F: PrimeField>() -> Vec<F> {
+	parse_vec(bw6_761_x5_3::ROUND_CONSTS.to_vec())
+}
+
+#[cfg(feature = "poseidon_bw6_761_x5_3")]
+pub fn get_mds_poseidon_bw6_761_x5_3<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(
+		bw6_761_x5_3::MDS_ENTRIES
+			.iter()
+			.map(|x| x.to_vec())
+			.collect::<Vec<_>>(),
+	)
+}
+
 #[cfg(feature = "mimc_220_ed_on_bn254")]
 pub fn get_rounds_mimc_220<F: PrimeField>() -> Vec<F> {
 	parse_vec(crate::utils::mimc::CONSTANTS.to_vec())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_bls12_381::Fr;
+	use ark_ff::{
+		biginteger::BigInteger64,
+		fields::{FftParameters, Fp64, Fp64Parameters},
+	};
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{rand::Rng, test_rng, One, UniformRand, Zero};
+
+	/// Field of the Mersenne prime 2^61 - 1, whose 8 byte chunks mostly
+	/// exceed the modulus
+	struct P61Parameters;
+	type P61 = Fp64<P61Parameters>;
+
+	impl Fp64Parameters for P61Parameters {}
+	impl FftParameters for P61Parameters {
+		type BigInt = BigInteger64;
+
+		const TWO_ADICITY: u32 = 1;
+		const TWO_ADIC_ROOT_OF_UNITY: BigInteger64 = BigInteger64([0x1ffffffffffffff7]);
+	}
+	impl FpParameters for P61Parameters {
+		const CAPACITY: u32 = Self::MODULUS_BITS - 1;
+		// 37 in Montgomery form
+		const GENERATOR: BigInteger64 = BigInteger64([0x128]);
+		const INV: u64 = 0x2000000000000001;
+		const MODULUS: BigInteger64 = BigInteger64([0x1fffffffffffffff]);
+		const MODULUS_BITS: u32 = 61;
+		const MODULUS_MINUS_ONE_DIV_TWO: BigInteger64 = BigInteger64([0xfffffffffffffff]);
+		const R: BigInteger64 = BigInteger64([0x8]);
+		const R2: BigInteger64 = BigInteger64([0x40]);
+		const REPR_SHAVE_BITS: u32 = 3;
+		const T: BigInteger64 = BigInteger64([0xfffffffffffffff]);
+		const T_MINUS_ONE_DIV_TWO: BigInteger64 = BigInteger64([0x7ffffffffffffff]);
+	}
+
+	#[test]
+	fn should_read_safe_chunks_on_small_field() {
+		assert_eq!(safe_chunk_size::<P61>(), 7);
+		assert_eq!(safe_chunk_size::<Fr>(), 31);
+
+		// A full chunk of ones is past the modulus, while safe chunks never are
+		let full = full_chunk_size::<P61>();
+		assert!(to_field_elements_chunked::<P61>(&[0xff; 8], full, Endianness::Little).is_err());
+		assert_eq!(to_field_elements::<P61>(&[0xff; 8]).unwrap().len(), 2);
+
+		// Chunk sizes that can't hold a field element are rejected
+		assert!(to_field_elements_chunked::<P61>(&[0xff; 8], 0, Endianness::Little).is_err());
+		assert!(to_field_elements_chunked::<P61>(&[0xff; 8], 9, Endianness::Little).is_err());
+
+		let rng = &mut test_rng();
+		let bytes: Vec<u8> = (0..100).map(|_| rng.gen()).collect();
+		for &endianness in [Endianness::Little, Endianness::Big].iter() {
+			let elts = to_field_elements_with::<P61>(&bytes, endianness).unwrap();
+			assert_eq!(elts.len(), 15);
+
+			// Every chunk is read back unreduced
+			let written = from_field_elements_with(&elts, Endianness::Little).unwrap();
+			for (i, chunk) in bytes.chunks(7).enumerate() {
+				let mut expected = chunk.to_vec();
+				if endianness == Endianness::Big {
+					expected.reverse();
+				}
+				expected.resize(8, 0);
+				assert_eq!(&written[i * 8..(i + 1) * 8], &expected[..]);
+			}
+
+			let cs = ConstraintSystem::<P61>::new_ref();
+			let bytes_var =
+				Vec::<UInt8<P61>>::new_witness(cs.clone(), || Ok(bytes.clone())).unwrap();
+			let elts_var = to_field_var_elements_with(&bytes_var, endianness).unwrap();
+			for (elt_var, elt) in elts_var.iter().zip(&elts) {
+				assert_eq!(elt_var.value().unwrap(), *elt);
+			}
+			assert_eq!(elts_var.len(), elts.len());
+			assert!(cs.is_satisfied().unwrap());
+
+			assert!(to_field_var_elements_chunked(&bytes_var, 9, endianness).is_err());
+		}
+	}
+
+	#[test]
+	fn should_round_trip_bytes_in_both_byte_orders() {
+		let rng = &mut test_rng();
+		let mut bytes: Vec<u8> = (0..64).map(|_| rng.gen()).collect();
+		// Keep the most significant byte of each chunk small in both byte
+		// orders, so every chunk is a canonical field element
+		for i in (0..64).step_by(32) {
+			bytes[i] &= 0x1f;
+			bytes[i + 31] &= 0x1f;
+		}
+
+		let full = full_chunk_size::<Fr>();
+		let le = to_field_elements_chunked::<Fr>(&bytes, full, Endianness::Little).unwrap();
+		let be = to_field_elements_chunked::<Fr>(&bytes, full, Endianness::Big).unwrap();
+		assert_ne!(le, be);
+
+		assert_eq!(from_field_elements_with(&le, Endianness::Little).unwrap(), bytes);
+		assert_eq!(from_field_elements_with(&be, Endianness::Big).unwrap(), bytes);
+		assert_ne!(from_field_elements_with(&be, Endianness::Little).unwrap(), bytes);
+	}
+
+	#[test]
+	fn should_read_short_chunks_as_numbers() {
+		let rng = &mut test_rng();
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		// Short enough that every chunk is canonical, with a partial last chunk
+		let bytes: Vec<u8> = (0..40).map(|_| rng.gen()).collect();
+		let bytes_var = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(bytes.clone())).unwrap();
+		for &chunk_size in [safe_chunk_size::<Fr>(), 16].iter() {
+			let le = to_field_elements_chunked::<Fr>(&bytes, chunk_size, Endianness::Little);
+			let be = to_field_elements_chunked::<Fr>(&bytes, chunk_size, Endianness::Big);
+			let le_var = to_field_var_elements_chunked(&bytes_var, chunk_size, Endianness::Little);
+			let be_var = to_field_var_elements_chunked(&bytes_var, chunk_size, Endianness::Big);
+			let (le, be) = (le.unwrap(), be.unwrap());
+			let (le_var, be_var) = (le_var.unwrap(), be_var.unwrap());
+
+			for (i, chunk) in bytes.chunks(chunk_size).enumerate() {
+				assert_eq!(le[i], Fr::from_le_bytes_mod_order(chunk));
+				assert_eq!(be[i], Fr::from_be_bytes_mod_order(chunk));
+				assert_eq!(le_var[i].value().unwrap(), le[i]);
+				assert_eq!(be_var[i].value().unwrap(), be[i]);
+			}
+		}
+
+		// A lone byte is its own value in both byte orders
+		let one = to_field_elements_chunked::<Fr>(&[1], 31, Endianness::Big).unwrap();
+		assert_eq!(one, vec![Fr::one()]);
+		assert!(cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_convert_field_elements_to_fixed_bytes() {
+		let rng = &mut test_rng();
+		for _ in 0..10 {
+			let x = Fr::rand(rng);
+			assert_eq!(fr_from_bytes_le::<Fr>(&fr_to_bytes_le(&x).unwrap()).unwrap(), x);
+			assert_eq!(fr_from_bytes_be::<Fr>(&fr_to_bytes_be(&x).unwrap()).unwrap(), x);
+		}
+
+		let mut one = [0u8; FIELD_BYTES_SIZE];
+		one[0] = 1;
+		assert_eq!(fr_to_bytes_le(&Fr::zero()).unwrap(), [0u8; FIELD_BYTES_SIZE]);
+		assert_eq!(fr_to_bytes_le(&Fr::one()).unwrap(), one);
+		assert_eq!(fr_from_bytes_le::<Fr>(&one).unwrap(), Fr::one());
+		one.reverse();
+		assert_eq!(fr_to_bytes_be(&Fr::one()).unwrap(), one);
+		assert_eq!(fr_from_bytes_be::<Fr>(&one).unwrap(), Fr::one());
+		assert_eq!(fr_from_bytes_be::<Fr>(&[0u8; FIELD_BYTES_SIZE]).unwrap(), Fr::zero());
+
+		// The modulus is the first value that isn't canonical
+		let mut modulus = fr_to_bytes_le(&-Fr::one()).unwrap();
+		assert_eq!(modulus[0], 0);
+		modulus[0] = 1;
+		assert!(matches!(
+			fr_from_bytes_le::<Fr>(&modulus),
+			Err(FieldBytesError::NonCanonical)
+		));
+		modulus.reverse();
+		assert!(fr_from_bytes_be::<Fr>(&modulus).is_err());
+		assert!(fr_from_bytes_le::<Fr>(&[0xff; FIELD_BYTES_SIZE]).is_err());
+
+		// Past the 8 bytes of a small field, any set byte is out of range
+		let mut bytes = [0u8; FIELD_BYTES_SIZE];
+		bytes[8] = 1;
+		assert!(fr_from_bytes_le::<P61>(&bytes).is_err());
+		bytes[8] = 0;
+		bytes[0] = 5;
+		assert_eq!(fr_from_bytes_le::<P61>(&bytes).unwrap(), P61::from(5u64));
+	}
+}